@@ -0,0 +1,4 @@
+// Migrations are embedded with include_str!, which doesn't notice new migration directories
+fn main() {
+    println!("cargo:rerun-if-changed=../migrations");
+}
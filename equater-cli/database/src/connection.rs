@@ -1,18 +1,252 @@
+use diesel::connection::SimpleConnection;
 use diesel::{
-    r2d2::{ConnectionManager, Pool},
-    SqliteConnection,
+    r2d2::{ConnectionManager, CustomizeConnection, Pool, PoolError},
+    QueryResult, SqliteConnection,
 };
+use diesel_migrations::{setup_database, MigrationConnection, RunMigrationsError};
 use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
+/// Migrations are compiled into the binary so that a new install doesn't need the diesel CLI.
+/// Written out instead of using `embed_migrations!` so that the newest version can be read.
+#[allow(dead_code)]
+mod embedded_migrations {
+    #[derive(EmbedMigrations)]
+    #[embed_migrations_options(migrations_path = "../migrations")]
+    struct _Dummy;
+
+    pub fn latest_version() -> &'static str {
+        ALL_MIGRATIONS
+            .iter()
+            .map(|migration| migration.version())
+            .max()
+            .unwrap_or("00000000000000")
+    }
+}
+
+const DATABASE_FILE_NAME: &str = "equater_cli.db";
+/// How long a connection waits on another process (e.g. a second terminal) holding a write lock
+const BUSY_TIMEOUT_MILLISECONDS: u32 = 5_000;
+
+/// Migrations are checked and applied by one pool at a time, since tests open several at once
+static MIGRATION_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug)]
+pub enum ConnectionError {
+    /// No database url was configured and there's no home directory to default to
+    NoDataDirectory,
+    CreateDirectory {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Connect {
+        database_url: String,
+        source: PoolError,
+    },
+    Migration(RunMigrationsError),
+    /// The database was migrated by a newer version of the CLI
+    NewerSchema {
+        schema_version: String,
+        supported_version: String,
+    },
+}
+
+impl Display for ConnectionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionError::NoDataDirectory => write!(
+                f,
+                "Could not find a home directory for the local database -- set database_url with equater config set"
+            ),
+            ConnectionError::CreateDirectory { path, source } => {
+                write!(f, "Could not create {}: {}", path.display(), source)
+            }
+            ConnectionError::Connect {
+                database_url,
+                source,
+            } => write!(f, "Could not open {}: {}", database_url, source),
+            ConnectionError::Migration(err) => {
+                write!(f, "Could not migrate the local database: {}", err)
+            }
+            ConnectionError::NewerSchema {
+                schema_version,
+                supported_version,
+            } => write!(
+                f,
+                "The local database is at schema version {} but this version of the CLI only supports up to {} -- upgrade the CLI",
+                schema_version, supported_version
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConnectionError {}
+
+#[derive(Debug)]
+struct ConnectionOptions;
+
+impl CustomizeConnection<SqliteConnection, diesel::r2d2::Error> for ConnectionOptions {
+    fn on_acquire(&self, connection: &mut SqliteConnection) -> Result<(), diesel::r2d2::Error> {
+        connection
+            .batch_execute(&format!(
+                "PRAGMA busy_timeout = {}",
+                BUSY_TIMEOUT_MILLISECONDS
+            ))
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+/// `$XDG_DATA_HOME/Equater`, falling back to `~/.local/share/Equater`
+pub fn data_directory() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|path| path.join("Equater"))
+}
+
+/// `equater_cli.db` in the data directory
+pub fn default_database_url() -> Option<String> {
+    data_directory().map(|directory| directory.join(DATABASE_FILE_NAME).display().to_string())
+}
+
+/// The newest migration compiled into this version of the CLI
+pub fn supported_schema_version() -> &'static str {
+    embedded_migrations::latest_version()
+}
+
+/// The newest migration that has been applied to the database
+pub fn schema_version(connection: &SqliteConnection) -> QueryResult<Option<String>> {
+    connection.latest_run_migration_version()
+}
+
+/// SQLite creates the file if it doesn't exist, and the directory it goes in is created here.
+/// Pending migrations are applied before the pool is returned. There's no implicit default --
+/// the CLI resolves the url from its configuration.
+///
 /// Only documentation I could find is in the source
 /// https://github.com/diesel-rs/diesel/blob/master/diesel/src/r2d2.rs
-pub fn connect() -> Pool<ConnectionManager<SqliteConnection>> {
-    let database_url =
-        env::var("EQUATER_LOCAL_DATABASE_URL").expect("EQUATER_LOCAL_DATABASE_URL is not set");
-    let manager = ConnectionManager::<SqliteConnection>::new(database_url.clone());
-
-    Pool::builder()
+pub fn connect_to(
+    database_url: &str,
+) -> Result<Pool<ConnectionManager<SqliteConnection>>, ConnectionError> {
+    create_parent_directory(database_url)?;
+    let to_connection_error = |source: PoolError| ConnectionError::Connect {
+        database_url: database_url.to_string(),
+        source,
+    };
+    let manager = ConnectionManager::<SqliteConnection>::new(database_url);
+    let pool = Pool::builder()
         .test_on_check_out(true)
+        .connection_customizer(Box::new(ConnectionOptions))
         .build(manager)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
+        .map_err(to_connection_error)?;
+
+    let connection = pool.get().map_err(to_connection_error)?;
+    migrate(&connection)?;
+
+    Ok(pool)
+}
+
+/// Only plain paths are handled -- `file:` URIs and in-memory databases are left to SQLite
+fn create_parent_directory(database_url: &str) -> Result<(), ConnectionError> {
+    if database_url.starts_with("file:") || database_url == ":memory:" {
+        return Ok(());
+    }
+
+    match Path::new(database_url).parent() {
+        Some(directory) if !directory.as_os_str().is_empty() => fs::create_dir_all(directory)
+            .map_err(|source| ConnectionError::CreateDirectory {
+                path: directory.to_path_buf(),
+                source,
+            }),
+        _ => Ok(()),
+    }
+}
+
+fn migrate(connection: &SqliteConnection) -> Result<(), ConnectionError> {
+    let _lock = MIGRATION_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    setup_database(connection).map_err(|err| ConnectionError::Migration(err.into()))?;
+
+    let supported_version = supported_schema_version();
+    let schema_version =
+        schema_version(connection).map_err(|err| ConnectionError::Migration(err.into()))?;
+    if let Some(schema_version) =
+        schema_version.filter(|version| version.as_str() > supported_version)
+    {
+        return Err(ConnectionError::NewerSchema {
+            schema_version,
+            supported_version: supported_version.to_string(),
+        });
+    }
+
+    embedded_migrations::run(connection).map_err(ConnectionError::Migration)
+}
+
+/// Tests share one database in the temp directory per test process, created fresh on first use,
+/// so they never read or write the operator's real database
+#[cfg(test)]
+pub(crate) fn connect_to_test_database(
+) -> Result<Pool<ConnectionManager<SqliteConnection>>, ConnectionError> {
+    static TEST_DATABASE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+    let database_url = TEST_DATABASE.get_or_init(|| {
+        let path = env::temp_dir()
+            .join(format!("equater-database-tests-{}", std::process::id()))
+            .join("test.db");
+        let _ = fs::remove_file(&path);
+
+        path.display().to_string()
+    });
+
+    connect_to(database_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::connection::SimpleConnection;
+    use std::fs;
+
+    use super::{connect_to, schema_version, supported_schema_version, ConnectionError};
+
+    fn make_database_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("equater-{}-{}.db", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        path.display().to_string()
+    }
+
+    #[test]
+    fn should_create_and_migrate_a_new_database() {
+        let database_url = make_database_path("new");
+
+        let connection = connect_to(&database_url).unwrap().get().unwrap();
+        let version = schema_version(&connection).unwrap();
+        fs::remove_file(&database_url).unwrap();
+
+        assert_eq!(version.as_deref(), Some(supported_schema_version()));
+    }
+
+    #[test]
+    fn should_refuse_a_database_from_a_newer_cli() {
+        let database_url = make_database_path("newer");
+        connect_to(&database_url)
+            .unwrap()
+            .get()
+            .unwrap()
+            .batch_execute(
+                "insert into __diesel_schema_migrations (version) values ('99990101000000')",
+            )
+            .unwrap();
+
+        let result = connect_to(&database_url);
+        fs::remove_file(&database_url).unwrap();
+
+        assert!(matches!(
+            result,
+            Err(ConnectionError::NewerSchema { schema_version, .. }) if schema_version == "99990101000000"
+        ));
+    }
 }
@@ -1,11 +1,15 @@
+#![allow(non_local_definitions)]
 #[macro_use]
 extern crate diesel;
+#[macro_use]
+extern crate diesel_migrations;
 extern crate core;
 
 pub mod connection;
 pub mod models;
 pub mod repository;
 pub mod schema;
+pub mod token_cipher;
 
 #[cfg(test)]
 mod tests {
@@ -1,2 +1,7 @@
+pub mod audit_log;
+pub mod mirror;
+pub mod money;
+pub mod profile;
 pub mod shared_expense;
 pub mod user;
+pub mod vendor_review;
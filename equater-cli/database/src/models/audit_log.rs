@@ -0,0 +1,44 @@
+use crate::schema::audit_log;
+use diesel::Queryable;
+
+/// One request sent to the API through the CLI
+#[derive(Queryable, Debug)]
+pub struct AuditLogEntry {
+    pub id: i32,
+    /// UTC, e.g. 2022-05-14 17:53:16
+    pub date_time_requested: String,
+    pub profile_name: String,
+    pub api_base: String,
+    /// The server environment, e.g. production. None when it was never requested.
+    pub environment: Option<String>,
+    /// None for requests sent before anyone was signed in, e.g. signing in
+    pub operator_email: Option<String>,
+    pub method: String,
+    /// Relative to the api base, including the query string
+    pub path: String,
+    /// JSON with secrets redacted
+    pub request_body: Option<String>,
+    /// None when the server couldn't be reached
+    pub response_status: Option<i32>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "audit_log"]
+pub struct AuditLogEntryData {
+    pub date_time_requested: String,
+    pub profile_name: String,
+    pub api_base: String,
+    pub environment: Option<String>,
+    pub operator_email: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub request_body: Option<String>,
+    pub response_status: Option<i32>,
+}
+
+/// Requests that don't change anything, which are only logged when asked for
+pub const READ_METHODS: [&str; 3] = ["GET", "HEAD", "OPTIONS"];
+
+pub fn is_read_method(method: &str) -> bool {
+    READ_METHODS.contains(&method)
+}
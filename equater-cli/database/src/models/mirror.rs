@@ -0,0 +1,77 @@
+use crate::schema::{mirrored_users, mirrored_vendors};
+use diesel::Queryable;
+
+/// A vendor as it was when `equater sync` last saw it
+#[derive(Queryable, QueryableByName, Debug)]
+#[table_name = "mirrored_vendors"]
+pub struct MirroredVendor {
+    pub id: i32,
+    pub profile_id: i32,
+    pub vendor_id: i32,
+    pub friendly_name: String,
+    pub ppd_id: Option<String>,
+    pub has_been_reviewed_internally: bool,
+    pub total_number_of_expense_sharing_agreements: i32,
+    /// JSON exactly as the API serialized it
+    pub data: String,
+    /// Set by sqlite, e.g. 2022-05-14 17:53:16
+    pub date_time_synced: String,
+}
+
+#[derive(Insertable, AsChangeset, Debug)]
+#[table_name = "mirrored_vendors"]
+pub struct MirroredVendorData {
+    pub profile_id: i32,
+    pub vendor_id: i32,
+    pub friendly_name: String,
+    pub ppd_id: Option<String>,
+    pub has_been_reviewed_internally: bool,
+    pub total_number_of_expense_sharing_agreements: i32,
+    pub data: String,
+}
+
+/// A user as they were when `equater sync` last saw them
+#[derive(Queryable, QueryableByName, Debug)]
+#[table_name = "mirrored_users"]
+pub struct MirroredUser {
+    pub id: i32,
+    pub profile_id: i32,
+    pub user_id: i32,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    /// JSON exactly as the API serialized it
+    pub data: String,
+    /// Set by sqlite, e.g. 2022-05-14 17:53:16
+    pub date_time_synced: String,
+}
+
+#[derive(Insertable, AsChangeset, Debug)]
+#[table_name = "mirrored_users"]
+pub struct MirroredUserData {
+    pub profile_id: i32,
+    pub user_id: i32,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub data: String,
+}
+
+/// What writing a record to the mirror did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorWrite {
+    Inserted,
+    Updated,
+    /// The record hadn't changed, so only its sync time was bumped
+    Unchanged,
+}
+
+/// Turns free text into an FTS5 query that prefix matches every word, e.g. `net fli` becomes
+/// `"net"* "fli"*`. Quoting each word keeps FTS5 syntax like `-` and `:` from being interpreted.
+pub fn to_full_text_query(search_term: &str) -> String {
+    search_term
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
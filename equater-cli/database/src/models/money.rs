@@ -0,0 +1,131 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, Neg, Sub};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// An amount of US dollars stored as a whole number of cents. The API sends every amount as
+/// cents, so it's serialized as a plain integer.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    pub fn cents(&self) -> i64 {
+        self.0
+    }
+
+    /// The amount as a number of dollars, which is what the API expects in request bodies that
+    /// simulate Plaid transactions
+    pub fn dollars(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+}
+
+impl fmt::Display for Money {
+    /// e.g. -1234 -> -$12.34
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+
+        write!(f, "{}${}.{:02}", sign, cents / 100, cents % 100)
+    }
+}
+
+impl FromStr for Money {
+    type Err = String;
+
+    /// Parses dollars with at most two decimal places, e.g. 12.34, $12 or -0.5
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("{} isn't an amount of dollars, e.g. 12.34", value);
+        let (sign, unsigned) = match value.trim().strip_prefix('-') {
+            Some(unsigned) => (-1, unsigned),
+            None => (1, value.trim()),
+        };
+        let unsigned = unsigned.strip_prefix('$').unwrap_or(unsigned);
+        let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let is_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || fraction.len() > 2 || !is_digits(whole) || !is_digits(fraction) {
+            return Err(invalid());
+        }
+
+        let dollars: i64 = whole.parse().map_err(|_| invalid())?;
+        let cents: i64 = format!("{:0<2}", fraction).parse().map_err(|_| invalid())?;
+        let total = dollars
+            .checked_mul(100)
+            .and_then(|total| total.checked_add(cents))
+            .ok_or_else(invalid)?;
+
+        Ok(Money(sign * total))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money(self.0 + other.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, other: Money) -> Money {
+        Money(self.0 - other.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Money;
+
+    #[test]
+    fn should_format_as_dollars() {
+        assert_eq!(Money::from_cents(1234).to_string(), "$12.34");
+        assert_eq!(Money::from_cents(-5).to_string(), "-$0.05");
+        assert_eq!(Money::ZERO.to_string(), "$0.00");
+    }
+
+    #[test]
+    fn should_add_up_amounts() {
+        let total: Money = [150, 250, -100].into_iter().map(Money::from_cents).sum();
+
+        assert_eq!(total, Money::from_cents(300));
+        assert_eq!(serde_json::to_string(&total).unwrap(), "300");
+    }
+
+    #[test]
+    fn should_parse_dollars() {
+        assert_eq!("12.34".parse::<Money>(), Ok(Money::from_cents(1234)));
+        assert_eq!("$12".parse::<Money>(), Ok(Money::from_cents(1200)));
+        assert_eq!("-0.5".parse::<Money>(), Ok(Money::from_cents(-50)));
+        assert!("12.345".parse::<Money>().is_err());
+        assert!("twelve".parse::<Money>().is_err());
+        assert!(".50".parse::<Money>().is_err());
+        assert_eq!(Money::from_cents(1234).dollars(), 12.34);
+    }
+}
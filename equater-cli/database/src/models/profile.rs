@@ -0,0 +1,35 @@
+use crate::schema::profiles;
+use diesel::Queryable;
+use serde::Deserialize;
+
+/// A named environment (local, staging, production, etc.) that the CLI can talk to
+#[derive(Queryable, Debug, Clone)]
+pub struct Profile {
+    pub id: i32,
+    pub name: String,
+    pub api_base: String,
+    pub is_active: bool,
+}
+
+#[derive(Deserialize, Insertable)]
+#[table_name = "profiles"]
+pub struct ProfileData {
+    pub name: String,
+    pub api_base: String,
+    pub is_active: bool,
+}
+
+impl Profile {
+    pub fn get_id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The base url without a trailing slash so that paths can be appended, e.g. `{}/api/user`
+    pub fn get_api_base(&self) -> &str {
+        self.api_base.trim_end_matches('/')
+    }
+}
@@ -1 +1,231 @@
+use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
+use super::money::Money;
+
+/// Mirrors the SharedExpense entity in the API. Dates are ISO8601 strings.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedExpense {
+    pub id: u32,
+    pub uuid: String,
+    pub unique_vendor_id: Option<u32>,
+    pub expense_owner_user_id: u32,
+    pub expense_owner_source_account_id: Option<u32>,
+    pub expense_owner_destination_account_id: Option<u32>,
+    pub expense_nick_name: String,
+    pub date_time_created: String,
+    pub is_active: bool,
+    pub is_pending: bool,
+    pub shared_expense_type: SharedExpenseType,
+    pub expense_recurrence_interval: Option<RecurringExpenseInterval>,
+    pub expense_recurrence_frequency: Option<u32>,
+    pub target_date_of_first_charge: Option<String>,
+    pub date_last_charged: Option<String>,
+    pub date_next_payment_scheduled: Option<String>,
+    pub recurring_payment_end_date: Option<String>,
+    pub date_time_deactivated: Option<String>,
+}
+
+/// Mirrors the SharedExpenseUserAgreement entity in the API
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedExpenseUserAgreement {
+    pub id: u32,
+    pub uuid: String,
+    pub shared_expense_id: u32,
+    pub user_id: u32,
+    pub payment_account_id: Option<u32>,
+    pub contribution_type: ExpenseContributionType,
+    /// Cents for fixed contributions, a whole percentage for percentage contributions and null
+    /// when the expense is split evenly. Read it through `contribution()`.
+    pub(crate) contribution_value: Option<i64>,
+    pub is_pending: bool,
+    pub is_active: bool,
+    pub date_time_created: String,
+    pub date_time_became_active: Option<String>,
+    pub date_time_became_inactive: Option<String>,
+}
+
+/// Someone who was invited to a shared expense before they had an account
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserInvite {
+    pub id: u32,
+    pub uuid: String,
+    pub email: String,
+    pub shared_expense_id: Option<u32>,
+    pub initiating_user_id: u32,
+    pub contribution_type: ExpenseContributionType,
+    /// Read it through `contribution()`
+    pub(crate) contribution_value: Option<i64>,
+    pub date_time_created: String,
+    pub is_converted: bool,
+    pub date_time_became_user: Option<String>,
+}
+
+/// A payment from one user to another that settles part of a shared expense
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedExpenseTransaction {
+    pub id: u32,
+    pub uuid: String,
+    /// Null for recurring payments, which aren't triggered by a Plaid transaction
+    pub plaid_transaction_id: Option<u32>,
+    pub shared_expense_id: u32,
+    pub shared_expense_user_agreement_id: u32,
+    pub source_account_id: u32,
+    pub destination_account_id: u32,
+    pub source_user_id: u32,
+    pub destination_user_id: u32,
+    pub total_transaction_amount: Money,
+    pub total_fee_amount: Money,
+    pub date_time_initiated: String,
+    pub has_been_transferred_to_destination: bool,
+    pub date_time_transferred_to_destination: Option<String>,
+    pub number_of_times_attempted: u32,
+    pub date_time_transaction_scheduled: Option<String>,
+    pub dwolla_transfer_url: Option<String>,
+    pub dwolla_transfer_id: Option<String>,
+    /// One of Dwolla's transfer statuses: pending, processed, failed or cancelled
+    pub dwolla_status: Option<String>,
+    pub date_time_dwolla_status_updated: Option<String>,
+}
+
+impl SharedExpenseUserAgreement {
+    pub fn contribution(&self) -> Option<Contribution> {
+        Contribution::new(self.contribution_type, self.contribution_value)
+    }
+}
+
+impl UserInvite {
+    pub fn contribution(&self) -> Option<Contribution> {
+        Contribution::new(self.contribution_type, self.contribution_value)
+    }
+}
+
+/// What a user agreed to pay each time the shared expense is charged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contribution {
+    Fixed(Money),
+    /// A whole percentage of each charge
+    Percentage(i64),
+    SplitEvenly,
+}
+
+impl Contribution {
+    /// None when a fixed or percentage contribution is missing its value
+    pub fn new(contribution_type: ExpenseContributionType, value: Option<i64>) -> Option<Self> {
+        match (contribution_type, value) {
+            (ExpenseContributionType::Fixed, Some(cents)) => {
+                Some(Contribution::Fixed(Money::from_cents(cents)))
+            }
+            (ExpenseContributionType::Percentage, Some(percentage)) => {
+                Some(Contribution::Percentage(percentage))
+            }
+            (ExpenseContributionType::SplitEvenly, _) => Some(Contribution::SplitEvenly),
+            (_, None) => None,
+        }
+    }
+}
+
+impl fmt::Display for Contribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Contribution::Fixed(amount) => write!(f, "{}", amount),
+            Contribution::Percentage(percentage) => write!(f, "{}%", percentage),
+            Contribution::SplitEvenly => write!(f, "Split evenly"),
+        }
+    }
+}
+
+/// Stored as a tinyint, so it's serialized as a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum SharedExpenseType {
+    SharedBill,
+    RecurringPayment,
+}
+
+/// Stored as a tinyint, so it's serialized as a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum RecurringExpenseInterval {
+    Days,
+    Months,
+}
+
+/// Stored as an int, so it's serialized as a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum ExpenseContributionType {
+    Percentage,
+    Fixed,
+    SplitEvenly,
+}
+
+impl TryFrom<u8> for SharedExpenseType {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(SharedExpenseType::SharedBill),
+            1 => Ok(SharedExpenseType::RecurringPayment),
+            value => Err(format!("unknown shared expense type {}", value)),
+        }
+    }
+}
+
+impl From<SharedExpenseType> for u8 {
+    fn from(value: SharedExpenseType) -> Self {
+        match value {
+            SharedExpenseType::SharedBill => 0,
+            SharedExpenseType::RecurringPayment => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for RecurringExpenseInterval {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RecurringExpenseInterval::Days),
+            1 => Ok(RecurringExpenseInterval::Months),
+            value => Err(format!("unknown recurring expense interval {}", value)),
+        }
+    }
+}
+
+impl From<RecurringExpenseInterval> for u8 {
+    fn from(value: RecurringExpenseInterval) -> Self {
+        match value {
+            RecurringExpenseInterval::Days => 0,
+            RecurringExpenseInterval::Months => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for ExpenseContributionType {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ExpenseContributionType::Percentage),
+            1 => Ok(ExpenseContributionType::Fixed),
+            2 => Ok(ExpenseContributionType::SplitEvenly),
+            value => Err(format!("unknown contribution type {}", value)),
+        }
+    }
+}
+
+impl From<ExpenseContributionType> for u8 {
+    fn from(value: ExpenseContributionType) -> Self {
+        match value {
+            ExpenseContributionType::Percentage => 0,
+            ExpenseContributionType::Fixed => 1,
+            ExpenseContributionType::SplitEvenly => 2,
+        }
+    }
+}
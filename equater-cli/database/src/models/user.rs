@@ -2,9 +2,10 @@ use crate::schema::users;
 use diesel::Queryable;
 use serde::Deserialize;
 
-#[derive(Queryable)]
+#[derive(Queryable, Debug)]
 pub struct LocalUser {
     pub id: i32,
+    pub profile_id: i32,
     pub email: String,
     pub auth_token: String,
 }
@@ -12,6 +13,7 @@ pub struct LocalUser {
 #[derive(Deserialize, Insertable)]
 #[table_name = "users"]
 pub struct UserData {
+    pub profile_id: i32,
     pub email: String,
     pub auth_token: String,
 }
@@ -21,6 +23,10 @@ impl LocalUser {
         self.id
     }
 
+    pub fn get_profile_id(&self) -> i32 {
+        self.profile_id
+    }
+
     pub fn get_email(&self) -> &str {
         self.email.as_str()
     }
@@ -0,0 +1,31 @@
+use crate::schema::vendor_reviews;
+use diesel::Queryable;
+
+pub const STATUS_REVIEWED: &str = "reviewed";
+pub const STATUS_SKIPPED: &str = "skipped";
+
+/// A vendor that's been dealt with during `equater review vendors`
+#[derive(Queryable, Clone, Debug)]
+pub struct VendorReview {
+    pub id: i32,
+    pub profile_id: i32,
+    pub vendor_id: i32,
+    /// Either `STATUS_REVIEWED` or `STATUS_SKIPPED`
+    pub status: String,
+    /// Set by sqlite, e.g. 2022-05-14 17:53:16
+    pub date_time_reviewed: String,
+}
+
+#[derive(Insertable)]
+#[table_name = "vendor_reviews"]
+pub struct VendorReviewData {
+    pub profile_id: i32,
+    pub vendor_id: i32,
+    pub status: String,
+}
+
+impl VendorReview {
+    pub fn was_skipped(&self) -> bool {
+        self.status == STATUS_SKIPPED
+    }
+}
@@ -1 +1,6 @@
+pub mod audit_log_repository;
+pub mod mirrored_user_repository;
+pub mod mirrored_vendor_repository;
+pub mod profile_repository;
 pub mod user_repository;
+pub mod vendor_review_repository;
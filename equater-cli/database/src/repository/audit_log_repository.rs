@@ -0,0 +1,143 @@
+use crate::models::audit_log::{AuditLogEntry, AuditLogEntryData, READ_METHODS};
+use crate::schema::audit_log::dsl::*;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{insert_into, RunQueryDsl};
+
+/// Narrows down `find_entries`. Every field is optional and they're combined with AND.
+#[derive(Debug, Default)]
+pub struct AuditLogFilter {
+    pub profile_name: Option<String>,
+    pub operator_email: Option<String>,
+    pub method: Option<String>,
+    /// Matches any part of the path
+    pub path: Option<String>,
+    /// Inclusive, compared as text so a date (2022-05-01) or a full timestamp both work
+    pub since: Option<String>,
+    /// Exclusive, so that `until` can be the day after the last one to include
+    pub until: Option<String>,
+    pub include_reads: bool,
+    pub limit: Option<i64>,
+}
+
+/// The audit log isn't scoped to a profile -- entries outlive the profile they were made with
+pub struct AuditLogRepository<'a> {
+    connection: &'a SqliteConnection,
+}
+
+impl<'a> AuditLogRepository<'a> {
+    pub fn new(connection: &'a SqliteConnection) -> Self {
+        Self { connection }
+    }
+
+    pub fn record(&self, entries: &[AuditLogEntryData]) -> Result<usize, Error> {
+        insert_into(audit_log)
+            .values(entries)
+            .execute(self.connection)
+    }
+
+    /// Newest first
+    pub fn find_entries(&self, filter: &AuditLogFilter) -> Result<Vec<AuditLogEntry>, Error> {
+        let mut query = audit_log.into_boxed();
+        if let Some(name) = &filter.profile_name {
+            query = query.filter(profile_name.eq(name));
+        }
+        if let Some(email) = &filter.operator_email {
+            query = query.filter(operator_email.eq(email));
+        }
+        if let Some(request_method) = &filter.method {
+            query = query.filter(method.eq(request_method.to_uppercase()));
+        }
+        if let Some(request_path) = &filter.path {
+            query = query.filter(path.like(format!("%{}%", request_path)));
+        }
+        if let Some(since) = &filter.since {
+            query = query.filter(date_time_requested.ge(since));
+        }
+        if let Some(until) = &filter.until {
+            query = query.filter(date_time_requested.lt(until));
+        }
+        if !filter.include_reads {
+            query = query.filter(method.ne_all(READ_METHODS));
+        }
+        if let Some(limit) = filter.limit {
+            query = query.limit(limit);
+        }
+
+        query
+            .order((date_time_requested.desc(), id.desc()))
+            .load(self.connection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::prelude::*;
+    use serial_test::serial;
+
+    use crate::connection::connect_to_test_database;
+    use crate::models::audit_log::AuditLogEntryData;
+    use crate::repository::audit_log_repository::{AuditLogFilter, AuditLogRepository};
+
+    fn make_entry(profile: &str, request_method: &str, date_time: &str) -> AuditLogEntryData {
+        AuditLogEntryData {
+            date_time_requested: String::from(date_time),
+            profile_name: String::from(profile),
+            api_base: String::from("http://localhost:7111"),
+            environment: Some(String::from("development")),
+            operator_email: Some(String::from("admin@example.com")),
+            method: String::from(request_method),
+            path: String::from("/api/vendor/12"),
+            request_body: None,
+            response_status: Some(200),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn should_filter_entries_newest_first() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let repository = AuditLogRepository::new(&connection);
+        let profile = "audit-log-repository-test";
+        repository
+            .record(&[
+                make_entry(profile, "PATCH", "2022-05-01 09:00:00"),
+                make_entry(profile, "GET", "2022-05-02 09:00:00"),
+                make_entry(profile, "DELETE", "2022-05-03 09:00:00"),
+            ])
+            .unwrap();
+
+        let mutations = repository
+            .find_entries(&AuditLogFilter {
+                profile_name: Some(String::from(profile)),
+                ..AuditLogFilter::default()
+            })
+            .unwrap();
+        let everything = repository
+            .find_entries(&AuditLogFilter {
+                profile_name: Some(String::from(profile)),
+                include_reads: true,
+                since: Some(String::from("2022-05-02")),
+                until: Some(String::from("2022-05-04")),
+                ..AuditLogFilter::default()
+            })
+            .unwrap();
+        diesel::delete(
+            crate::schema::audit_log::table
+                .filter(crate::schema::audit_log::profile_name.eq(profile)),
+        )
+        .execute(&connection)
+        .unwrap();
+
+        let methods: Vec<&str> = mutations
+            .iter()
+            .map(|entry| entry.method.as_str())
+            .collect();
+        assert_eq!(methods, vec!["DELETE", "PATCH"]);
+        let methods: Vec<&str> = everything
+            .iter()
+            .map(|entry| entry.method.as_str())
+            .collect();
+        assert_eq!(methods, vec!["DELETE", "GET"]);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::models::mirror::{to_full_text_query, MirrorWrite, MirroredUser, MirroredUserData};
+use crate::schema::mirrored_users::dsl::*;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{BigInt, Integer, Text};
+use diesel::{insert_into, sql_query, RunQueryDsl};
+
+/// The user mirror is scoped to a profile since user ids differ between environments
+pub struct MirroredUserRepository<'a> {
+    connection: &'a SqliteConnection,
+    profile_id: i32,
+}
+
+impl<'a> MirroredUserRepository<'a> {
+    pub fn new(connection: &'a SqliteConnection, profile: i32) -> Self {
+        Self {
+            connection,
+            profile_id: profile,
+        }
+    }
+
+    pub fn get_profile_id(&self) -> i32 {
+        self.profile_id
+    }
+
+    pub fn find_user(&self, mirrored_user_id: i32) -> Result<Option<MirroredUser>, Error> {
+        mirrored_users
+            .filter(profile_id.eq(self.profile_id))
+            .filter(user_id.eq(mirrored_user_id))
+            .first(self.connection)
+            .optional()
+    }
+
+    pub fn find_user_ids(&self) -> Result<Vec<i32>, Error> {
+        mirrored_users
+            .filter(profile_id.eq(self.profile_id))
+            .select(user_id)
+            .order(user_id.asc())
+            .load(self.connection)
+    }
+
+    /// Inserts or updates a user. Users that haven't changed only have their sync time bumped.
+    pub fn save_user(&self, user: &MirroredUserData) -> Result<MirrorWrite, Error> {
+        let synced_now = date_time_synced.eq(sql::<Text>("current_timestamp"));
+
+        match self.find_user(user.user_id)? {
+            None => {
+                insert_into(mirrored_users)
+                    .values(user)
+                    .execute(self.connection)?;
+
+                Ok(MirrorWrite::Inserted)
+            }
+            Some(existing) if existing.data == user.data => {
+                diesel::update(mirrored_users.find(existing.id))
+                    .set(synced_now)
+                    .execute(self.connection)?;
+
+                Ok(MirrorWrite::Unchanged)
+            }
+            Some(existing) => {
+                diesel::update(mirrored_users.find(existing.id))
+                    .set((user, synced_now))
+                    .execute(self.connection)?;
+
+                Ok(MirrorWrite::Updated)
+            }
+        }
+    }
+
+    /// Full-text search over emails and names, best matches first
+    pub fn search(&self, search_term: &str, limit: i64) -> Result<Vec<MirroredUser>, Error> {
+        let query = to_full_text_query(search_term);
+        if query.is_empty() {
+            return Ok(vec![]);
+        }
+
+        sql_query(
+            "select mirrored_users.* from mirrored_users \
+             join mirrored_users_search on mirrored_users_search.rowid = mirrored_users.id \
+             where mirrored_users_search match ? and mirrored_users.profile_id = ? \
+             order by mirrored_users_search.rank limit ?",
+        )
+        .bind::<Text, _>(query)
+        .bind::<Integer, _>(self.profile_id)
+        .bind::<BigInt, _>(limit)
+        .load(self.connection)
+    }
+
+    /// Drops a user that no longer exists
+    pub fn remove_user(&self, removed_user_id: i32) -> Result<usize, Error> {
+        diesel::delete(
+            mirrored_users
+                .filter(profile_id.eq(self.profile_id))
+                .filter(user_id.eq(removed_user_id)),
+        )
+        .execute(self.connection)
+    }
+
+    pub fn clear(&self) -> Result<usize, Error> {
+        diesel::delete(mirrored_users.filter(profile_id.eq(self.profile_id)))
+            .execute(self.connection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connection::connect_to_test_database;
+    use crate::models::mirror::{MirrorWrite, MirroredUserData};
+    use crate::repository::mirrored_user_repository::MirroredUserRepository;
+    use crate::repository::profile_repository::ProfileRepository;
+
+    #[test]
+    fn should_search_mirrored_users_by_name_and_email() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let profile = ProfileRepository::new(&connection)
+            .find_or_create_profile("mirrored-user-repository-test", "http://localhost:7111")
+            .unwrap();
+        let repository = MirroredUserRepository::new(&connection, profile.get_id());
+        repository.clear().unwrap();
+
+        let user = MirroredUserData {
+            profile_id: profile.get_id(),
+            user_id: 7,
+            email: String::from("james.holden@example.com"),
+            first_name: String::from("James"),
+            last_name: String::from("Holden"),
+            data: String::from("{\"id\":7}"),
+        };
+        assert_eq!(repository.save_user(&user).unwrap(), MirrorWrite::Inserted);
+
+        assert_eq!(repository.search("hold", 10).unwrap().len(), 1);
+        assert_eq!(repository.search("james holden", 10).unwrap().len(), 1);
+        assert!(repository.search("naomi", 10).unwrap().is_empty());
+        assert_eq!(repository.find_user_ids().unwrap(), vec![7]);
+
+        repository.remove_user(7).unwrap();
+        assert!(repository.search("hold", 10).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::models::mirror::{to_full_text_query, MirrorWrite, MirroredVendor, MirroredVendorData};
+use crate::schema::mirrored_vendors::dsl::*;
+use diesel::connection::SimpleConnection;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::sql_types::{BigInt, Integer, Text};
+use diesel::{insert_into, sql_query, RunQueryDsl};
+
+/// SQLite limits how many values one statement can bind, so kept vendor ids are inserted into a
+/// temp table this many at a time
+const KEPT_IDS_PER_INSERT: usize = 500;
+
+/// The vendor mirror is scoped to a profile since vendor ids differ between environments
+pub struct MirroredVendorRepository<'a> {
+    connection: &'a SqliteConnection,
+    profile_id: i32,
+}
+
+impl<'a> MirroredVendorRepository<'a> {
+    pub fn new(connection: &'a SqliteConnection, profile: i32) -> Self {
+        Self {
+            connection,
+            profile_id: profile,
+        }
+    }
+
+    pub fn get_profile_id(&self) -> i32 {
+        self.profile_id
+    }
+
+    pub fn find_vendor(&self, mirrored_vendor_id: i32) -> Result<Option<MirroredVendor>, Error> {
+        mirrored_vendors
+            .filter(profile_id.eq(self.profile_id))
+            .filter(vendor_id.eq(mirrored_vendor_id))
+            .first(self.connection)
+            .optional()
+    }
+
+    /// Inserts or updates a vendor. Vendors that haven't changed only have their sync time
+    /// bumped, which keeps the search index from being rewritten on every sync.
+    pub fn save_vendor(&self, vendor: &MirroredVendorData) -> Result<MirrorWrite, Error> {
+        let synced_now = date_time_synced.eq(sql::<Text>("current_timestamp"));
+
+        match self.find_vendor(vendor.vendor_id)? {
+            None => {
+                insert_into(mirrored_vendors)
+                    .values(vendor)
+                    .execute(self.connection)?;
+
+                Ok(MirrorWrite::Inserted)
+            }
+            Some(existing) if existing.data == vendor.data => {
+                diesel::update(mirrored_vendors.find(existing.id))
+                    .set(synced_now)
+                    .execute(self.connection)?;
+
+                Ok(MirrorWrite::Unchanged)
+            }
+            Some(existing) => {
+                diesel::update(mirrored_vendors.find(existing.id))
+                    .set((vendor, synced_now))
+                    .execute(self.connection)?;
+
+                Ok(MirrorWrite::Updated)
+            }
+        }
+    }
+
+    /// Full-text search over friendly names and PPD IDs, best matches first
+    pub fn search(&self, search_term: &str, limit: i64) -> Result<Vec<MirroredVendor>, Error> {
+        let query = to_full_text_query(search_term);
+        if query.is_empty() {
+            return Ok(vec![]);
+        }
+
+        sql_query(
+            "select mirrored_vendors.* from mirrored_vendors \
+             join mirrored_vendors_search on mirrored_vendors_search.rowid = mirrored_vendors.id \
+             where mirrored_vendors_search match ? and mirrored_vendors.profile_id = ? \
+             order by mirrored_vendors_search.rank limit ?",
+        )
+        .bind::<Text, _>(query)
+        .bind::<Integer, _>(self.profile_id)
+        .bind::<BigInt, _>(limit)
+        .load(self.connection)
+    }
+
+    pub fn find_vendors_that_require_review(&self) -> Result<Vec<MirroredVendor>, Error> {
+        mirrored_vendors
+            .filter(profile_id.eq(self.profile_id))
+            .filter(has_been_reviewed_internally.eq(false))
+            .order(friendly_name.asc())
+            .load(self.connection)
+    }
+
+    /// Vendors with the most bills split first
+    pub fn find_popular_vendors(&self, limit: i64) -> Result<Vec<MirroredVendor>, Error> {
+        mirrored_vendors
+            .filter(profile_id.eq(self.profile_id))
+            .order(total_number_of_expense_sharing_agreements.desc())
+            .limit(limit)
+            .load(self.connection)
+    }
+
+    /// Every vendor in alphabetical order, like GET /api/vendor
+    pub fn find_vendors(&self, offset: i64, limit: i64) -> Result<Vec<MirroredVendor>, Error> {
+        mirrored_vendors
+            .filter(profile_id.eq(self.profile_id))
+            .order(friendly_name.asc())
+            .offset(offset)
+            .limit(limit)
+            .load(self.connection)
+    }
+
+    /// Saves every vendor in the catalog and drops the ones that aren't in it any more. It's one
+    /// transaction, so a sync that fails partway leaves the previous mirror as it was. Returns
+    /// what happened to each vendor and how many were removed.
+    pub fn replace_vendors(
+        &self,
+        vendors: &[MirroredVendorData],
+    ) -> Result<(Vec<MirrorWrite>, usize), Error> {
+        self.connection.transaction(|| {
+            let writes = vendors
+                .iter()
+                .map(|vendor| self.save_vendor(vendor))
+                .collect::<Result<Vec<MirrorWrite>, Error>>()?;
+            let kept: Vec<i32> = vendors.iter().map(|vendor| vendor.vendor_id).collect();
+            let removed = self.remove_vendors_except(&kept)?;
+
+            Ok((writes, removed))
+        })
+    }
+
+    /// Drops vendors that have been merged or deleted since they were mirrored. The catalog can
+    /// have more vendors than a statement can bind, so the ids to keep go through a temp table.
+    pub fn remove_vendors_except(&self, vendor_ids: &[i32]) -> Result<usize, Error> {
+        self.connection.batch_execute(
+            "create temp table if not exists kept_vendor_ids (vendor_id integer primary key); \
+             delete from kept_vendor_ids;",
+        )?;
+        for chunk in vendor_ids.chunks(KEPT_IDS_PER_INSERT) {
+            // Integers can't inject anything, so they're formatted into the statement directly
+            let values: Vec<String> = chunk
+                .iter()
+                .map(|kept_id| format!("({})", kept_id))
+                .collect();
+            self.connection.batch_execute(&format!(
+                "insert or ignore into kept_vendor_ids (vendor_id) values {};",
+                values.join(", ")
+            ))?;
+        }
+
+        let removed = sql_query(
+            "delete from mirrored_vendors where profile_id = ? \
+             and vendor_id not in (select vendor_id from kept_vendor_ids)",
+        )
+        .bind::<Integer, _>(self.profile_id)
+        .execute(self.connection)?;
+        self.connection
+            .batch_execute("delete from kept_vendor_ids;")?;
+
+        Ok(removed)
+    }
+
+    pub fn clear(&self) -> Result<usize, Error> {
+        diesel::delete(mirrored_vendors.filter(profile_id.eq(self.profile_id)))
+            .execute(self.connection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connection::connect_to_test_database;
+    use crate::models::mirror::{MirrorWrite, MirroredVendorData};
+    use crate::repository::mirrored_vendor_repository::MirroredVendorRepository;
+    use crate::repository::profile_repository::ProfileRepository;
+
+    fn make_vendor(profile_id: i32, vendor_id: i32, friendly_name: &str) -> MirroredVendorData {
+        MirroredVendorData {
+            profile_id,
+            vendor_id,
+            friendly_name: String::from(friendly_name),
+            ppd_id: None,
+            has_been_reviewed_internally: true,
+            total_number_of_expense_sharing_agreements: vendor_id,
+            data: format!("{{\"id\":{}}}", vendor_id),
+        }
+    }
+
+    #[test]
+    fn should_search_mirrored_vendors_and_track_changes() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let profile = ProfileRepository::new(&connection)
+            .find_or_create_profile("mirrored-vendor-repository-test", "http://localhost:7111")
+            .unwrap();
+        let repository = MirroredVendorRepository::new(&connection, profile.get_id());
+        repository.clear().unwrap();
+
+        let netflix = make_vendor(profile.get_id(), 1, "Netflix");
+        assert_eq!(
+            repository.save_vendor(&netflix).unwrap(),
+            MirrorWrite::Inserted
+        );
+        assert_eq!(
+            repository.save_vendor(&netflix).unwrap(),
+            MirrorWrite::Unchanged
+        );
+        let renamed = MirroredVendorData {
+            friendly_name: String::from("Netflix Streaming"),
+            data: String::from("{\"id\":1,\"renamed\":true}"),
+            ..make_vendor(profile.get_id(), 1, "")
+        };
+        assert_eq!(
+            repository.save_vendor(&renamed).unwrap(),
+            MirrorWrite::Updated
+        );
+        repository
+            .save_vendor(&make_vendor(profile.get_id(), 2, "Comcast"))
+            .unwrap();
+
+        let results = repository.search("stream", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].friendly_name, "Netflix Streaming");
+        assert!(repository.search("\"", 10).unwrap().is_empty());
+
+        let popular = repository.find_popular_vendors(1).unwrap();
+        assert_eq!(popular[0].vendor_id, 2);
+
+        assert_eq!(repository.remove_vendors_except(&[2]).unwrap(), 1);
+        assert!(repository.search("netflix", 10).unwrap().is_empty());
+        assert_eq!(repository.find_vendors(0, 100).unwrap().len(), 1);
+
+        // More ids than SQLite can bind in one statement
+        let kept: Vec<i32> = (2..40_000).collect();
+        assert_eq!(repository.remove_vendors_except(&kept).unwrap(), 0);
+        let (writes, removed) = repository
+            .replace_vendors(&[make_vendor(profile.get_id(), 3, "Hulu")])
+            .unwrap();
+        assert_eq!(writes, vec![MirrorWrite::Inserted]);
+        assert_eq!(removed, 1);
+
+        repository.clear().unwrap();
+    }
+}
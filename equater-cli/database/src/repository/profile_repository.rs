@@ -0,0 +1,160 @@
+use crate::models::profile::{Profile, ProfileData};
+use crate::models::user::LocalUser;
+use crate::schema::profiles::dsl::*;
+use crate::schema::{users, vendor_reviews};
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{insert_into, RunQueryDsl};
+
+pub struct ProfileRepository<'a> {
+    connection: &'a SqliteConnection,
+}
+
+impl<'a> ProfileRepository<'a> {
+    pub fn new(connection: &'a SqliteConnection) -> Self {
+        Self { connection }
+    }
+
+    pub fn find_profiles(&self) -> Result<Vec<Profile>, Error> {
+        profiles.order(name.asc()).load(self.connection)
+    }
+
+    /// Every profile along with the user that's signed in to it, if any. The users' auth tokens
+    /// are left encrypted.
+    pub fn find_profiles_with_users(&self) -> Result<Vec<(Profile, Option<LocalUser>)>, Error> {
+        profiles
+            .left_join(users::table)
+            .select((
+                crate::schema::profiles::all_columns,
+                (
+                    users::id,
+                    users::profile_id,
+                    users::email,
+                    users::auth_token,
+                )
+                    .nullable(),
+            ))
+            .order(name.asc())
+            .load(self.connection)
+    }
+
+    pub fn find_profile_by_name(&self, profile_name: &str) -> Result<Profile, Error> {
+        profiles
+            .filter(name.eq(profile_name))
+            .first(self.connection)
+    }
+
+    pub fn find_active_profile(&self) -> Result<Profile, Error> {
+        profiles.filter(is_active.eq(true)).first(self.connection)
+    }
+
+    pub fn create_profile(&self, data: &ProfileData) -> Result<Profile, Error> {
+        self.connection.transaction(|| {
+            if data.is_active {
+                diesel::update(profiles)
+                    .set(is_active.eq(false))
+                    .execute(self.connection)?;
+            }
+            insert_into(profiles)
+                .values(data)
+                .execute(self.connection)?;
+
+            self.find_profile_by_name(&data.name)
+        })
+    }
+
+    pub fn find_or_create_profile(
+        &self,
+        profile_name: &str,
+        profile_api_base: &str,
+    ) -> Result<Profile, Error> {
+        match self.find_profile_by_name(profile_name) {
+            Ok(profile) => Ok(profile),
+            Err(Error::NotFound) => self.create_profile(&ProfileData {
+                name: String::from(profile_name),
+                api_base: String::from(profile_api_base),
+                is_active: false,
+            }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Marks the profile as active and every other profile as inactive
+    pub fn set_active_profile(&self, profile_name: &str) -> Result<Profile, Error> {
+        self.connection.transaction(|| {
+            let profile = self.find_profile_by_name(profile_name)?;
+            diesel::update(profiles)
+                .set(is_active.eq(false))
+                .execute(self.connection)?;
+            diesel::update(profiles.filter(id.eq(profile.id)))
+                .set(is_active.eq(true))
+                .execute(self.connection)?;
+
+            self.find_profile_by_name(profile_name)
+        })
+    }
+
+    /// Removes the profile along with the user that's signed in to it and any review progress
+    pub fn remove_profile(&self, profile_name: &str) -> Result<Profile, Error> {
+        // Zeroes the deleted user's auth token rather than leaving it in a free page
+        self.connection.batch_execute("PRAGMA secure_delete = ON")?;
+        self.connection.transaction(|| {
+            let profile = self.find_profile_by_name(profile_name)?;
+            diesel::delete(users::table.filter(users::profile_id.eq(profile.id)))
+                .execute(self.connection)?;
+            diesel::delete(vendor_reviews::table.filter(vendor_reviews::profile_id.eq(profile.id)))
+                .execute(self.connection)?;
+            diesel::delete(profiles.filter(id.eq(profile.id))).execute(self.connection)?;
+
+            Ok(profile)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connection::connect_to_test_database;
+    use crate::models::profile::ProfileData;
+    use crate::repository::profile_repository::ProfileRepository;
+
+    #[test]
+    fn should_switch_the_active_profile() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let repository = ProfileRepository::new(&connection);
+        let _ = repository.remove_profile("profile-repository-test-local");
+        let _ = repository.remove_profile("profile-repository-test-staging");
+        repository
+            .create_profile(&ProfileData {
+                name: String::from("profile-repository-test-local"),
+                api_base: String::from("http://localhost:7111/"),
+                is_active: true,
+            })
+            .unwrap();
+        repository
+            .create_profile(&ProfileData {
+                name: String::from("profile-repository-test-staging"),
+                api_base: String::from("https://staging.equater.io"),
+                is_active: false,
+            })
+            .unwrap();
+
+        let active = repository.find_active_profile().unwrap();
+        assert_eq!(active.get_name(), "profile-repository-test-local");
+        assert_eq!(active.get_api_base(), "http://localhost:7111");
+
+        repository
+            .set_active_profile("profile-repository-test-staging")
+            .unwrap();
+        let active = repository.find_active_profile().unwrap();
+        assert_eq!(active.get_name(), "profile-repository-test-staging");
+
+        repository
+            .remove_profile("profile-repository-test-staging")
+            .unwrap();
+        assert!(repository
+            .find_profile_by_name("profile-repository-test-staging")
+            .is_err());
+    }
+}
@@ -1,58 +1,265 @@
 use crate::models::user::{LocalUser, UserData};
 use crate::schema::users::dsl::*;
+use crate::token_cipher::TokenCipher;
+use diesel::connection::SimpleConnection;
 use diesel::prelude::*;
 use diesel::result::Error;
 use diesel::{insert_into, RunQueryDsl};
 
+/// Cached users are scoped to a profile so that each environment can hold its own session.
+/// Auth tokens are encrypted before they're written and decrypted when they're read, so
+/// `LocalUser::auth_token` is always plaintext.
 pub struct UserRepository<'a> {
     connection: &'a SqliteConnection,
+    profile_id: i32,
+    cipher: TokenCipher,
+    sessions: UserSessionRepository<'a>,
+}
+
+/// Signs users out. Deleting tokens doesn't need to decrypt them, so unlike `UserRepository`
+/// this works without the token key.
+pub struct UserSessionRepository<'a> {
+    connection: &'a SqliteConnection,
+    profile_id: i32,
 }
 
 impl<'a> UserRepository<'a> {
-    pub fn new(connection: &'a SqliteConnection) -> Self {
-        Self { connection }
+    pub fn new(connection: &'a SqliteConnection, profile: i32, cipher: TokenCipher) -> Self {
+        Self {
+            connection,
+            profile_id: profile,
+            cipher,
+            sessions: UserSessionRepository::new(connection, profile),
+        }
+    }
+
+    pub fn get_profile_id(&self) -> i32 {
+        self.profile_id
     }
 
-    /// There can only ever be 1 user in this application at a time
+    /// There can only ever be 1 user per profile at a time. A token that can't be decrypted
+    /// (e.g. the key changed) is a DeserializationError.
     pub fn find_user(&self) -> Result<LocalUser, Error> {
-        users.first(self.connection)
+        let user: LocalUser = users
+            .select((id, profile_id, email, auth_token))
+            .filter(profile_id.eq(self.profile_id))
+            .first(self.connection)?;
+        let token = self
+            .cipher
+            .decrypt(&user.auth_token, user.profile_id)
+            .map_err(|err| Error::DeserializationError(Box::new(err)))?;
+
+        Ok(LocalUser {
+            auth_token: token,
+            ..user
+        })
     }
 
     pub fn create_user(&self, data: &UserData) -> Result<LocalUser, Error> {
-        let num_deleted_rows = self.truncate()?;
-        println!("Deleted {} row(s) from user table", num_deleted_rows);
-        let result = insert_into(users).values(data).execute(self.connection)?;
-        println!("Inserted {} rows into user table", result);
+        self.delete_user()?;
+        let encrypted_token = self
+            .cipher
+            .encrypt(&data.auth_token, data.profile_id)
+            .map_err(|err| Error::SerializationError(Box::new(err)))?;
+        insert_into(users)
+            .values((
+                profile_id.eq(data.profile_id),
+                email.eq(&data.email),
+                auth_token.eq(encrypted_token),
+                auth_token_encrypted.eq(true),
+            ))
+            .execute(self.connection)?;
 
         self.find_user()
     }
 
+    /// Tokens cached before they were encrypted at rest are flagged by the migration and
+    /// encrypted here. Only this profile's token is touched -- another profile's is encrypted
+    /// the next time that profile is used. Returns the number of tokens that were encrypted.
+    pub fn encrypt_plaintext_tokens(&self) -> Result<usize, Error> {
+        let plaintext: Vec<(i32, i32, String)> = users
+            .select((id, profile_id, auth_token))
+            .filter(profile_id.eq(self.profile_id))
+            .filter(auth_token_encrypted.eq(false))
+            .load(self.connection)?;
+        if plaintext.is_empty() {
+            return Ok(0);
+        }
+
+        self.sessions.enable_secure_delete()?;
+        self.connection.transaction(|| {
+            for (user_id, user_profile_id, token) in &plaintext {
+                let encrypted_token = self
+                    .cipher
+                    .encrypt(token, *user_profile_id)
+                    .map_err(|err| Error::SerializationError(Box::new(err)))?;
+                diesel::update(users.filter(id.eq(user_id)))
+                    .set((
+                        auth_token.eq(encrypted_token),
+                        auth_token_encrypted.eq(true),
+                    ))
+                    .execute(self.connection)?;
+            }
+
+            Ok(plaintext.len())
+        })
+    }
+
+    /// Signs the user out of this profile only. Signing out of every profile is left to
+    /// `UserSessionRepository::truncate`.
+    pub fn delete_user(&self) -> Result<usize, Error> {
+        self.sessions.delete_user()
+    }
+}
+
+impl<'a> UserSessionRepository<'a> {
+    pub fn new(connection: &'a SqliteConnection, profile: i32) -> Self {
+        Self {
+            connection,
+            profile_id: profile,
+        }
+    }
+
+    /// Signs the user out of this profile only
+    pub fn delete_user(&self) -> Result<usize, Error> {
+        self.enable_secure_delete()?;
+        diesel::delete(users.filter(profile_id.eq(self.profile_id))).execute(self.connection)
+    }
+
+    /// Signs the user out of every profile
     pub fn truncate(&self) -> Result<usize, Error> {
+        self.enable_secure_delete()?;
         diesel::delete(users).execute(self.connection)
     }
+
+    /// SQLite otherwise leaves deleted rows, and so old tokens, in free pages of the file
+    fn enable_secure_delete(&self) -> Result<(), Error> {
+        self.connection.batch_execute("PRAGMA secure_delete = ON")
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use dotenv::dotenv;
+    use diesel::prelude::*;
+    use serial_test::serial;
 
-    use crate::connection::connect;
+    use crate::connection::connect_to_test_database;
     use crate::models::user::UserData;
+    use crate::repository::profile_repository::ProfileRepository;
     use crate::repository::user_repository::UserRepository;
+    use crate::schema::users;
+    use crate::token_cipher::TokenCipher;
+
+    fn make_cipher() -> TokenCipher {
+        TokenCipher::from_key([42; 32])
+    }
 
     #[test]
+    #[serial]
     fn should_create_user() {
-        dotenv().ok();
-        let connection = connect().get().unwrap();
-        let repository = UserRepository {
-            connection: &connection,
-        };
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let profile = ProfileRepository::new(&connection)
+            .find_or_create_profile("user-repository-test", "http://localhost:7111")
+            .unwrap();
+        let repository = UserRepository::new(&connection, profile.get_id(), make_cipher());
         let data = UserData {
+            profile_id: profile.get_id(),
             email: String::from("foo"),
             auth_token: String::from("bar"),
         };
         repository.create_user(&data).unwrap();
         let user = repository.find_user().unwrap();
         assert_eq!(user.get_email(), "foo");
+        assert_eq!(user.get_auth_token(), "bar");
+    }
+
+    #[test]
+    #[serial]
+    fn should_only_replace_the_user_for_the_same_profile() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let profile_repository = ProfileRepository::new(&connection);
+        let first = profile_repository
+            .find_or_create_profile("user-repository-test-first", "http://localhost:7111")
+            .unwrap();
+        let second = profile_repository
+            .find_or_create_profile("user-repository-test-second", "http://localhost:7112")
+            .unwrap();
+        let first_repository = UserRepository::new(&connection, first.get_id(), make_cipher());
+        let second_repository = UserRepository::new(&connection, second.get_id(), make_cipher());
+        first_repository
+            .create_user(&UserData {
+                profile_id: first.get_id(),
+                email: String::from("first"),
+                auth_token: String::from("first"),
+            })
+            .unwrap();
+        second_repository
+            .create_user(&UserData {
+                profile_id: second.get_id(),
+                email: String::from("second"),
+                auth_token: String::from("second"),
+            })
+            .unwrap();
+
+        assert_eq!(first_repository.find_user().unwrap().get_email(), "first");
+        assert_eq!(second_repository.find_user().unwrap().get_email(), "second");
+    }
+
+    #[test]
+    #[serial]
+    fn should_encrypt_tokens_at_rest() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let profile = ProfileRepository::new(&connection)
+            .find_or_create_profile("user-repository-test-encrypted", "http://localhost:7111")
+            .unwrap();
+        let other_profile = ProfileRepository::new(&connection)
+            .find_or_create_profile("user-repository-test-other", "http://localhost:7112")
+            .unwrap();
+        let repository = UserRepository::new(&connection, profile.get_id(), make_cipher());
+        repository.delete_user().unwrap();
+        UserRepository::new(&connection, other_profile.get_id(), make_cipher())
+            .delete_user()
+            .unwrap();
+        diesel::insert_into(users::table)
+            .values(vec![
+                (
+                    users::profile_id.eq(profile.get_id()),
+                    users::email.eq("plaintext"),
+                    users::auth_token.eq("plaintext-token"),
+                ),
+                (
+                    users::profile_id.eq(other_profile.get_id()),
+                    users::email.eq("other"),
+                    users::auth_token.eq("other-token"),
+                ),
+            ])
+            .execute(&*connection)
+            .unwrap();
+
+        let encrypted = repository.encrypt_plaintext_tokens().unwrap();
+        let stored: String = users::table
+            .select(users::auth_token)
+            .filter(users::profile_id.eq(profile.get_id()))
+            .first(&*connection)
+            .unwrap();
+        let other_profile_token: String = users::table
+            .select(users::auth_token)
+            .filter(users::profile_id.eq(other_profile.get_id()))
+            .first(&*connection)
+            .unwrap();
+        let other_key = UserRepository::new(
+            &connection,
+            profile.get_id(),
+            TokenCipher::from_key([7; 32]),
+        );
+
+        assert_eq!(encrypted, 1);
+        assert_eq!(other_profile_token, "other-token");
+        assert!(!stored.contains("plaintext-token"));
+        assert_eq!(
+            repository.find_user().unwrap().get_auth_token(),
+            "plaintext-token"
+        );
+        assert!(other_key.find_user().is_err());
     }
 }
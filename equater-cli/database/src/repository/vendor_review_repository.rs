@@ -0,0 +1,84 @@
+use crate::models::vendor_review::{VendorReview, VendorReviewData};
+use crate::schema::vendor_reviews::dsl::*;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{replace_into, RunQueryDsl};
+
+/// Review progress is scoped to a profile since vendor ids differ between environments
+pub struct VendorReviewRepository<'a> {
+    connection: &'a SqliteConnection,
+    profile_id: i32,
+}
+
+impl<'a> VendorReviewRepository<'a> {
+    pub fn new(connection: &'a SqliteConnection, profile: i32) -> Self {
+        Self {
+            connection,
+            profile_id: profile,
+        }
+    }
+
+    pub fn find_reviews(&self) -> Result<Vec<VendorReview>, Error> {
+        vendor_reviews
+            .filter(profile_id.eq(self.profile_id))
+            .order(id.asc())
+            .load(self.connection)
+    }
+
+    /// Records the outcome for a vendor, replacing any earlier outcome
+    pub fn record_review(
+        &self,
+        reviewed_vendor_id: i32,
+        review_status: &str,
+    ) -> Result<VendorReview, Error> {
+        let data = VendorReviewData {
+            profile_id: self.profile_id,
+            vendor_id: reviewed_vendor_id,
+            status: String::from(review_status),
+        };
+        replace_into(vendor_reviews)
+            .values(&data)
+            .execute(self.connection)?;
+
+        vendor_reviews
+            .filter(profile_id.eq(self.profile_id))
+            .filter(vendor_id.eq(reviewed_vendor_id))
+            .first(self.connection)
+    }
+
+    /// Forgets all progress so that the next review starts from the top of the queue
+    pub fn clear(&self) -> Result<usize, Error> {
+        diesel::delete(vendor_reviews.filter(profile_id.eq(self.profile_id)))
+            .execute(self.connection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::connection::connect_to_test_database;
+    use crate::models::vendor_review::{STATUS_REVIEWED, STATUS_SKIPPED};
+    use crate::repository::profile_repository::ProfileRepository;
+    use crate::repository::vendor_review_repository::VendorReviewRepository;
+
+    #[test]
+    fn should_record_and_clear_review_progress() {
+        let connection = connect_to_test_database().unwrap().get().unwrap();
+        let profile = ProfileRepository::new(&connection)
+            .find_or_create_profile("vendor-review-repository-test", "http://localhost:7111")
+            .unwrap();
+        let repository = VendorReviewRepository::new(&connection, profile.get_id());
+        repository.clear().unwrap();
+
+        repository.record_review(1, STATUS_SKIPPED).unwrap();
+        repository.record_review(2, STATUS_REVIEWED).unwrap();
+        let review = repository.record_review(1, STATUS_REVIEWED).unwrap();
+        assert!(!review.was_skipped());
+
+        let reviews = repository.find_reviews().unwrap();
+        assert_eq!(reviews.len(), 2);
+
+        repository.clear().unwrap();
+        assert!(repository.find_reviews().unwrap().is_empty());
+    }
+}
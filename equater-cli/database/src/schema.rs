@@ -1,9 +1,86 @@
-use diesel::table;
+use diesel::{allow_tables_to_appear_in_same_query, joinable, table};
+
+table! {
+    audit_log (id) {
+        id -> Integer,
+        date_time_requested -> Text,
+        profile_name -> Text,
+        api_base -> Text,
+        environment -> Nullable<Text>,
+        operator_email -> Nullable<Text>,
+        method -> Text,
+        path -> Text,
+        request_body -> Nullable<Text>,
+        response_status -> Nullable<Integer>,
+    }
+}
+
+table! {
+    mirrored_users (id) {
+        id -> Integer,
+        profile_id -> Integer,
+        user_id -> Integer,
+        email -> Text,
+        first_name -> Text,
+        last_name -> Text,
+        data -> Text,
+        date_time_synced -> Text,
+    }
+}
+
+table! {
+    mirrored_vendors (id) {
+        id -> Integer,
+        profile_id -> Integer,
+        vendor_id -> Integer,
+        friendly_name -> Text,
+        ppd_id -> Nullable<Text>,
+        has_been_reviewed_internally -> Bool,
+        total_number_of_expense_sharing_agreements -> Integer,
+        data -> Text,
+        date_time_synced -> Text,
+    }
+}
+
+table! {
+    profiles (id) {
+        id -> Integer,
+        name -> Text,
+        api_base -> Text,
+        is_active -> Bool,
+    }
+}
 
 table! {
     users (id) {
         id -> Integer,
+        profile_id -> Integer,
         email -> Text,
         auth_token -> Text,
+        auth_token_encrypted -> Bool,
     }
 }
+
+table! {
+    vendor_reviews (id) {
+        id -> Integer,
+        profile_id -> Integer,
+        vendor_id -> Integer,
+        status -> Text,
+        date_time_reviewed -> Text,
+    }
+}
+
+joinable!(mirrored_users -> profiles (profile_id));
+joinable!(mirrored_vendors -> profiles (profile_id));
+joinable!(users -> profiles (profile_id));
+joinable!(vendor_reviews -> profiles (profile_id));
+
+allow_tables_to_appear_in_same_query!(
+    audit_log,
+    mirrored_users,
+    mirrored_vendors,
+    profiles,
+    users,
+    vendor_reviews,
+);
@@ -0,0 +1,296 @@
+use crate::connection::data_directory;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt::{Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::num::NonZeroU32;
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 100_000;
+/// Prefix of every encrypted token so that the format can change without guessing
+const FORMAT_VERSION: &str = "v1";
+
+#[derive(Debug)]
+pub struct TokenCipherError(pub String);
+
+impl Display for TokenCipherError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for TokenCipherError {}
+
+enum TokenKey {
+    /// Stretched with PBKDF2 and a random salt for every token
+    Passphrase(String),
+    /// 32 random bytes, used as-is
+    Key([u8; KEY_LEN]),
+}
+
+/// Encrypts cached auth tokens with AES-256-GCM. The key is derived from a passphrase or read
+/// from a key file (`token.key` in the data directory by default), which is generated on first
+/// use and must only be readable by its owner.
+///
+/// Tokens are stored as `v1$salt$nonce$ciphertext` in hex. The profile id is bound to the
+/// ciphertext as associated data, so a token can't be copied from one profile to another.
+pub struct TokenCipher {
+    key: TokenKey,
+    random: SystemRandom,
+}
+
+impl TokenCipher {
+    pub fn from_passphrase(passphrase: &str) -> Result<Self, TokenCipherError> {
+        if passphrase.is_empty() {
+            return Err(TokenCipherError(String::from(
+                "The token passphrase can't be empty",
+            )));
+        }
+
+        Ok(Self {
+            key: TokenKey::Passphrase(passphrase.to_string()),
+            random: SystemRandom::new(),
+        })
+    }
+
+    pub fn from_key(key: [u8; KEY_LEN]) -> Self {
+        Self {
+            key: TokenKey::Key(key),
+            random: SystemRandom::new(),
+        }
+    }
+
+    /// Reads the key file, creating it with a new random key if it doesn't exist yet
+    pub fn from_key_file(path: &Path) -> Result<Self, TokenCipherError> {
+        if !path.exists() {
+            create_key_file(path)?;
+        }
+
+        ensure_private(path)?;
+
+        let contents = fs::read_to_string(path).map_err(|err| key_file_error(path, err))?;
+        let key = decode_hex(contents.trim())
+            .ok()
+            .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+            .ok_or_else(|| {
+                TokenCipherError(format!(
+                    "{} must contain a {}-byte key in hex",
+                    path.display(),
+                    KEY_LEN
+                ))
+            })?;
+
+        Ok(Self::from_key(key))
+    }
+
+    pub fn encrypt(&self, token: &str, profile_id: i32) -> Result<String, TokenCipherError> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        self.fill_random(&mut salt)?;
+        self.fill_random(&mut nonce)?;
+
+        let mut in_out = token.as_bytes().to_vec();
+        self.make_key(&salt)?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(profile_id.to_be_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| TokenCipherError(String::from("Could not encrypt the auth token")))?;
+
+        Ok(format!(
+            "{}${}${}${}",
+            FORMAT_VERSION,
+            encode_hex(&salt),
+            encode_hex(&nonce),
+            encode_hex(&in_out)
+        ))
+    }
+
+    /// Fails when the token was encrypted with a different key or for a different profile
+    pub fn decrypt(&self, stored: &str, profile_id: i32) -> Result<String, TokenCipherError> {
+        let malformed = || TokenCipherError(String::from("The cached auth token is malformed"));
+        let parts: Vec<&str> = stored.split('$').collect();
+        let (salt, nonce, ciphertext) = match parts.as_slice() {
+            [FORMAT_VERSION, salt, nonce, ciphertext] => (
+                decode_hex(salt).map_err(|_| malformed())?,
+                decode_hex(nonce).map_err(|_| malformed())?,
+                decode_hex(ciphertext).map_err(|_| malformed())?,
+            ),
+            _ => return Err(malformed()),
+        };
+        let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(|_| malformed())?;
+
+        let mut in_out = ciphertext;
+        let plaintext = self
+            .make_key(&salt)?
+            .open_in_place(nonce, Aad::from(profile_id.to_be_bytes()), &mut in_out)
+            .map_err(|_| {
+                TokenCipherError(String::from(
+                    "The cached auth token could not be decrypted with the current key",
+                ))
+            })?;
+
+        String::from_utf8(plaintext.to_vec()).map_err(|_| malformed())
+    }
+
+    fn make_key(&self, salt: &[u8]) -> Result<LessSafeKey, TokenCipherError> {
+        let mut key = [0u8; KEY_LEN];
+        match &self.key {
+            TokenKey::Passphrase(passphrase) => pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                NonZeroU32::new(PBKDF2_ITERATIONS).unwrap(),
+                salt,
+                passphrase.as_bytes(),
+                &mut key,
+            ),
+            TokenKey::Key(bytes) => key = *bytes,
+        }
+
+        UnboundKey::new(&AES_256_GCM, &key)
+            .map(LessSafeKey::new)
+            .map_err(|_| TokenCipherError(String::from("Invalid token encryption key")))
+    }
+
+    fn fill_random(&self, bytes: &mut [u8]) -> Result<(), TokenCipherError> {
+        self.random
+            .fill(bytes)
+            .map_err(|_| TokenCipherError(String::from("Could not generate random bytes")))
+    }
+}
+
+/// `token.key` in the data directory
+pub fn default_key_file() -> Option<PathBuf> {
+    data_directory().map(|directory| directory.join("token.key"))
+}
+
+/// The file is created with mode 0600 so that the key is never readable by other users, even
+/// for a moment
+fn create_key_file(path: &Path) -> Result<(), TokenCipherError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| key_file_error(path, err))?;
+    }
+
+    let mut key = [0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| TokenCipherError(String::from("Could not generate random bytes")))?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    match options.open(path) {
+        Ok(mut file) => {
+            writeln!(file, "{}", encode_hex(&key)).map_err(|err| key_file_error(path, err))
+        }
+        // Another process created it first
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => Ok(()),
+        Err(err) => Err(key_file_error(path, err)),
+    }
+}
+
+#[cfg(unix)]
+fn ensure_private(path: &Path) -> Result<(), TokenCipherError> {
+    let permissions = fs::metadata(path)
+        .map_err(|err| key_file_error(path, err))?
+        .permissions();
+    if permissions.mode() & 0o077 != 0 {
+        return Err(TokenCipherError(format!(
+            "{} can be read by other users -- run chmod 600 {}",
+            path.display(),
+            path.display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Other platforms don't have unix permission bits, so the data directory's own access control
+/// is relied on instead
+#[cfg(not(unix))]
+fn ensure_private(_path: &Path) -> Result<(), TokenCipherError> {
+    Ok(())
+}
+
+fn key_file_error(path: &Path, err: std::io::Error) -> TokenCipherError {
+    TokenCipherError(format!(
+        "Could not use the key file {}: {}",
+        path.display(),
+        err
+    ))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, std::num::ParseIntError> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2).unwrap_or("?"), 16))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(unix)]
+    use std::fs;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::TokenCipher;
+
+    #[test]
+    fn should_round_trip_a_token() {
+        let cipher = TokenCipher::from_key([7; 32]);
+
+        let encrypted = cipher.encrypt("eyJhbGciOiJIUzI1NiJ9", 3).unwrap();
+
+        assert!(encrypted.starts_with("v1$"));
+        assert!(!encrypted.contains("eyJhbGciOiJIUzI1NiJ9"));
+        assert_eq!(
+            cipher.decrypt(&encrypted, 3).unwrap(),
+            "eyJhbGciOiJIUzI1NiJ9"
+        );
+    }
+
+    #[test]
+    fn should_not_decrypt_with_another_key_or_profile() {
+        let cipher = TokenCipher::from_passphrase("correct horse").unwrap();
+        let encrypted = cipher.encrypt("token", 3).unwrap();
+
+        let other_passphrase = TokenCipher::from_passphrase("battery staple").unwrap();
+
+        assert!(other_passphrase.decrypt(&encrypted, 3).is_err());
+        assert!(cipher.decrypt(&encrypted, 4).is_err());
+        assert!(cipher.decrypt("token", 3).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_create_a_private_key_file_and_refuse_shared_ones() {
+        let directory = std::env::temp_dir().join(format!("equater-key-{}", std::process::id()));
+        let path = directory.join("token.key");
+        let _ = fs::remove_file(&path);
+
+        let cipher = TokenCipher::from_key_file(&path).unwrap();
+        let encrypted = cipher.encrypt("token", 1).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        let reloaded = TokenCipher::from_key_file(&path).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        let shared = TokenCipher::from_key_file(&path);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(reloaded.decrypt(&encrypted, 1).unwrap(), "token");
+        assert!(shared.is_err());
+    }
+}
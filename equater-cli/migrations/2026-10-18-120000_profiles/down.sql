@@ -0,0 +1,8 @@
+drop table if exists users;
+drop table if exists profiles;
+
+create table if not exists users (
+    id integer primary key autoincrement not null,
+    email text not null,
+    auth_token text not null
+);
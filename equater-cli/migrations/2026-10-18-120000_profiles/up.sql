@@ -0,0 +1,19 @@
+create table if not exists profiles (
+    id integer primary key autoincrement not null,
+    name text not null unique,
+    api_base text not null,
+    is_active boolean not null default 0
+);
+
+-- Cached users are now scoped to a profile. Existing rows only hold a cached auth token, so they're
+-- dropped rather than migrated and the next command will prompt for a login.
+drop table if exists users;
+
+create table if not exists users (
+    id integer primary key autoincrement not null,
+    profile_id integer not null references profiles(id) on delete cascade,
+    email text not null,
+    auth_token text not null
+);
+
+create unique index if not exists users_profile_id on users (profile_id);
@@ -0,0 +1,10 @@
+-- Progress through `equater review vendors` so that an interrupted review resumes where it left off
+create table if not exists vendor_reviews (
+    id integer primary key autoincrement not null,
+    profile_id integer not null references profiles(id) on delete cascade,
+    vendor_id integer not null,
+    status text not null,
+    date_time_reviewed text not null default current_timestamp
+);
+
+create unique index if not exists vendor_reviews_profile_id_vendor_id on vendor_reviews (profile_id, vendor_id);
@@ -0,0 +1,4 @@
+drop table if exists mirrored_users_search;
+drop table if exists mirrored_vendors_search;
+drop table if exists mirrored_users;
+drop table if exists mirrored_vendors;
@@ -0,0 +1,78 @@
+-- A local copy of vendors and users kept up to date by `equater sync`, so searches can be served
+-- without a network round trip. `data` holds the record exactly as the API serialized it.
+create table if not exists mirrored_vendors (
+    id integer primary key autoincrement not null,
+    profile_id integer not null references profiles(id) on delete cascade,
+    vendor_id integer not null,
+    friendly_name text not null,
+    ppd_id text,
+    has_been_reviewed_internally boolean not null,
+    total_number_of_expense_sharing_agreements integer not null,
+    data text not null,
+    date_time_synced text not null default current_timestamp
+);
+
+create unique index if not exists mirrored_vendors_profile_id_vendor_id on mirrored_vendors (profile_id, vendor_id);
+
+create table if not exists mirrored_users (
+    id integer primary key autoincrement not null,
+    profile_id integer not null references profiles(id) on delete cascade,
+    user_id integer not null,
+    email text not null,
+    first_name text not null,
+    last_name text not null,
+    data text not null,
+    date_time_synced text not null default current_timestamp
+);
+
+create unique index if not exists mirrored_users_profile_id_user_id on mirrored_users (profile_id, user_id);
+
+-- External content indexes that the triggers below keep in step with the mirror tables
+create virtual table if not exists mirrored_vendors_search using fts5(
+    friendly_name,
+    ppd_id,
+    content = 'mirrored_vendors',
+    content_rowid = 'id'
+);
+
+create trigger if not exists mirrored_vendors_after_insert after insert on mirrored_vendors begin
+    insert into mirrored_vendors_search (rowid, friendly_name, ppd_id)
+    values (new.id, new.friendly_name, new.ppd_id);
+end;
+
+create trigger if not exists mirrored_vendors_after_delete after delete on mirrored_vendors begin
+    insert into mirrored_vendors_search (mirrored_vendors_search, rowid, friendly_name, ppd_id)
+    values ('delete', old.id, old.friendly_name, old.ppd_id);
+end;
+
+create trigger if not exists mirrored_vendors_after_update after update on mirrored_vendors begin
+    insert into mirrored_vendors_search (mirrored_vendors_search, rowid, friendly_name, ppd_id)
+    values ('delete', old.id, old.friendly_name, old.ppd_id);
+    insert into mirrored_vendors_search (rowid, friendly_name, ppd_id)
+    values (new.id, new.friendly_name, new.ppd_id);
+end;
+
+create virtual table if not exists mirrored_users_search using fts5(
+    email,
+    first_name,
+    last_name,
+    content = 'mirrored_users',
+    content_rowid = 'id'
+);
+
+create trigger if not exists mirrored_users_after_insert after insert on mirrored_users begin
+    insert into mirrored_users_search (rowid, email, first_name, last_name)
+    values (new.id, new.email, new.first_name, new.last_name);
+end;
+
+create trigger if not exists mirrored_users_after_delete after delete on mirrored_users begin
+    insert into mirrored_users_search (mirrored_users_search, rowid, email, first_name, last_name)
+    values ('delete', old.id, old.email, old.first_name, old.last_name);
+end;
+
+create trigger if not exists mirrored_users_after_update after update on mirrored_users begin
+    insert into mirrored_users_search (mirrored_users_search, rowid, email, first_name, last_name)
+    values ('delete', old.id, old.email, old.first_name, old.last_name);
+    insert into mirrored_users_search (rowid, email, first_name, last_name)
+    values (new.id, new.email, new.first_name, new.last_name);
+end;
@@ -0,0 +1,4 @@
+-- Encrypted tokens are useless to older versions of the CLI, so those sessions are signed out
+delete from users where auth_token_encrypted = 1;
+
+alter table users drop column auth_token_encrypted;
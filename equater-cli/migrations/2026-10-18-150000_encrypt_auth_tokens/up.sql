@@ -0,0 +1,3 @@
+-- Cached auth tokens are encrypted with a key that only the CLI has, so SQL can't encrypt them.
+-- Existing rows are flagged as plaintext here and re-encrypted the next time the CLI starts.
+alter table users add column auth_token_encrypted boolean not null default 0;
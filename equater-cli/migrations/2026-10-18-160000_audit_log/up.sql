@@ -0,0 +1,16 @@
+-- Every request the CLI sends that changes data, so admin actions can be traced back to an operator.
+-- The profile is stored by name rather than referenced so that removing a profile keeps its history.
+create table if not exists audit_log (
+    id integer primary key autoincrement not null,
+    date_time_requested text not null,
+    profile_name text not null,
+    api_base text not null,
+    environment text,
+    operator_email text,
+    method text not null,
+    path text not null,
+    request_body text,
+    response_status integer
+);
+
+create index if not exists audit_log_date_time_requested on audit_log (date_time_requested);
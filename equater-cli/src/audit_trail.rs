@@ -0,0 +1,181 @@
+use std::sync::Mutex;
+
+use chrono::Utc;
+use database::models::audit_log::is_read_method;
+use reqwest::{Request, Url};
+use serde_json::Value;
+
+/// Every request sent through `SendJson` during this run, written to the audit log once the
+/// command finishes
+static AUDIT_TRAIL: Mutex<Vec<AuditedRequest>> = Mutex::new(Vec::new());
+
+pub const REDACTED: &str = "[REDACTED]";
+/// Body fields whose name contains any of these are never written to the audit log or a cassette
+const SECRET_FIELDS: [&str; 7] = [
+    "password",
+    "token",
+    "secret",
+    "authorization",
+    "accountnumber",
+    "routingnumber",
+    "presigned",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedRequest {
+    /// UTC in the same format sqlite uses, e.g. 2022-05-14 17:53:16
+    pub date_time_requested: String,
+    pub method: String,
+    /// Path and query string
+    pub path: String,
+    pub request_body: Option<String>,
+    /// None when the server couldn't be reached
+    pub response_status: Option<u16>,
+}
+
+impl AuditedRequest {
+    pub fn new(request: &Request) -> Self {
+        Self {
+            date_time_requested: Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            method: request.method().to_string(),
+            path: path_and_query(request.url()),
+            request_body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .filter(|bytes| !bytes.is_empty())
+                .map(redact_body),
+            response_status: None,
+        }
+    }
+}
+
+pub fn record(request: AuditedRequest) {
+    AUDIT_TRAIL
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .push(request);
+}
+
+/// How many requests that may have changed data have been sent so far. A request the server
+/// turned away with a 401 didn't change anything, while one that never got a response might have.
+pub fn changes_sent() -> usize {
+    AUDIT_TRAIL
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .iter()
+        .filter(|request| !is_read_method(&request.method) && request.response_status != Some(401))
+        .count()
+}
+
+/// Empties the trail
+pub fn take() -> Vec<AuditedRequest> {
+    std::mem::take(&mut *AUDIT_TRAIL.lock().unwrap_or_else(|err| err.into_inner()))
+}
+
+/// JSON bodies are kept with secrets replaced. Anything else, such as an image, is only
+/// described by its size.
+fn redact_body(bytes: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes>", bytes.len()),
+    }
+}
+
+/// Replaces strings held by secret fields, at any depth. Only strings are replaced so that a
+/// redacted body still decodes into the same models, which replayed cassettes rely on. Shared by
+/// the audit log and cassettes so the two can't disagree about what's secret.
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::String(_) if is_secret_field(key) => {
+                        *field = Value::String(String::from(REDACTED))
+                    }
+                    field => redact_value(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// The url without the api base, e.g. `/api/vendor/search?searchTerm=netflix`
+pub fn path_and_query(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// Field names are compared without case or separators, so `authToken`, `auth_token` and
+/// `Auth-Token` are all secret. Names such as `tokenType` or `dateTimeTokenCreated` describe a
+/// secret rather than hold one.
+pub fn is_secret_field(name: &str) -> bool {
+    let name = name.to_lowercase().replace(['_', '-'], "");
+    if name.ends_with("type") || name.starts_with("datetime") {
+        return false;
+    }
+
+    SECRET_FIELDS.iter().any(|secret| name.contains(secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{changes_sent, record, AuditedRequest};
+    use crate::equater_client::EquaterClient;
+
+    fn make_request(method: &str, response_status: Option<u16>) -> AuditedRequest {
+        AuditedRequest {
+            date_time_requested: String::from("2022-05-14 17:53:16"),
+            method: String::from(method),
+            path: String::from("/api/vendor/12"),
+            request_body: None,
+            response_status,
+        }
+    }
+
+    #[test]
+    fn it_should_only_count_changes_that_may_have_reached_the_server() {
+        let before = changes_sent();
+        record(make_request("GET", Some(200)));
+        record(make_request("PATCH", Some(401)));
+        record(make_request("PATCH", Some(200)));
+        record(make_request("PUT", None));
+
+        assert_eq!(changes_sent() - before, 2);
+    }
+
+    #[test]
+    fn it_should_redact_secrets_from_request_bodies() {
+        let client = EquaterClient::new("http://localhost:7111").unwrap();
+        let request = client
+            .post("/api/auth/login")
+            .query(&[("redirect", "false")])
+            .json(&json!({
+                "email": "admin@example.com",
+                "password": "hunter2",
+                "accounts": [{ "account_number": "1234", "name": "Checking" }],
+                "authToken": "eyJhbGciOiJIUzI1NiJ9",
+            }))
+            .build()
+            .unwrap();
+
+        let audited = AuditedRequest::new(&request);
+        let body = audited.request_body.unwrap();
+
+        assert_eq!(audited.method, "POST");
+        assert_eq!(audited.path, "/api/auth/login?redirect=false");
+        assert!(body.contains("admin@example.com"));
+        assert!(body.contains("Checking"));
+        assert!(!body.contains("hunter2"));
+        assert!(!body.contains("1234"));
+        assert!(!body.contains("eyJhbGciOiJIUzI1NiJ9"));
+    }
+}
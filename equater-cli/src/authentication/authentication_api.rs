@@ -1,17 +1,14 @@
-use std::env;
-
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
 use crate::user::{SignInResponse, User};
 use async_trait::async_trait;
 use dialoguer::{theme::ColorfulTheme, Input, Password};
-use http::{header::AUTHORIZATION, HeaderMap, HeaderValue};
-use reqwest::Result;
 use serde::Serialize;
 
 #[derive(Clone)]
 pub enum SignInStrategy {
     CommandLineInput,
-    /// Used in test only
-    #[allow(dead_code)]
+    /// e.g. from `login --password-stdin`
     StoredValues {
         email: String,
         password: String,
@@ -54,11 +51,11 @@ impl SignInRequest {
 }
 
 pub struct AuthenticationApi {
-    client: reqwest::Client,
+    client: EquaterClient,
 }
 
 impl AuthenticationApi {
-    pub fn new(client: reqwest::Client) -> Self {
+    pub fn new(client: EquaterClient) -> Self {
         AuthenticationApi { client }
     }
 }
@@ -66,22 +63,18 @@ impl AuthenticationApi {
 #[async_trait]
 impl RemoteAuthentication for AuthenticationApi {
     async fn sign_in(&self, request: &SignInRequest) -> Result<SignInResponse> {
-        let api_base = env::var("EQUATER_API_BASE").expect("EQUATER_API_BASE must be set");
-        let url = format!("{}/api/auth/admin-login", api_base);
-        let response = self.client.post(url).json(&request).send().await?;
-
-        return response.json::<SignInResponse>().await;
+        self.client
+            .post("/api/auth/admin-login")
+            .json(&request)
+            .send_json::<SignInResponse>()
+            .await
     }
 
     async fn get_user(&self, auth_token: &str) -> Result<User> {
-        let api_base = env::var("EQUATER_API_BASE").expect("EQUATER_API_BASE must be set");
-        let url = format!("{}/api/user", api_base);
-        let mut header_map = HeaderMap::new();
-        let header_token =
-            HeaderValue::from_str(format!("Bearer {}", auth_token).as_str()).unwrap();
-        header_map.insert(AUTHORIZATION, header_token);
-        let response = self.client.get(url).headers(header_map).send().await?;
-
-        return response.json::<User>().await;
+        self.client
+            .with_auth_token(auth_token)
+            .get("/api/user")
+            .send_json::<User>()
+            .await
     }
 }
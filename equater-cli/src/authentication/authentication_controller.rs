@@ -1,4 +1,5 @@
 use super::authentication_api::{RemoteAuthentication, SignInRequest, SignInStrategy};
+use crate::error::{EquaterError, Result};
 use crate::user::SignInResponse;
 use database::{
     models::user::{LocalUser, UserData},
@@ -26,47 +27,45 @@ impl<'a, T: RemoteAuthentication> AuthenticationController<'a, T> {
     ///     3. If the auth token is valid, return the local user
     ///     4. If the auth token is invalid, attempt to sign in
     ///     5. If sign in is successful, cache the local user
-    pub async fn sign_in(&self, strategy: SignInStrategy) -> Result<LocalUser, String> {
+    pub async fn sign_in(&self, strategy: SignInStrategy) -> Result<LocalUser> {
         let request = SignInRequest::new(strategy);
-        println!("Checking cached user");
+        verbose!("Checking cached user");
         let local_user = self.find_local_user_by_email(&request).await;
 
         if (self.verify_local_user(local_user).await).is_ok() {
-            println!("Cached user verified");
+            verbose!("Cached user verified");
         } else {
-            println!("Cached user not found -- signing in via server");
+            verbose!("Cached user not found -- signing in via server");
         }
 
-        let response = self
-            .remote_repository
-            .sign_in(&request)
-            .await
-            .map_err(|e| e.to_string())?;
+        let response = self.remote_repository.sign_in(&request).await?;
 
-        self.cache_user(&response).await.map_err(|e| e.to_string())
+        self.cache_user(&response).await
     }
 
-    pub async fn find_and_verify_local_user(&self) -> Result<LocalUser, String> {
-        let local_user = self
-            .local_repository
-            .find_user()
-            .map_err(|err| err.to_string());
-
-        self.verify_local_user(local_user).await
+    /// The cached user for the current profile. The auth token isn't verified up front -- a 401
+    /// from any request signs the user in again instead. So does a token that was encrypted with
+    /// a different key.
+    pub fn find_local_user(&self) -> Result<LocalUser> {
+        self.local_repository.find_user().map_err(|err| match err {
+            Error::NotFound => EquaterError::NotSignedIn,
+            Error::DeserializationError(err) => {
+                verbose!("Ignoring the cached user: {}", err);
+                EquaterError::NotSignedIn
+            }
+            err => EquaterError::from(err),
+        })
     }
 
-    async fn cache_user(&self, response: &SignInResponse) -> Result<LocalUser, Error> {
+    async fn cache_user(&self, response: &SignInResponse) -> Result<LocalUser> {
         let data = self.map_user_data(response);
 
-        self.local_repository.create_user(&data)
+        Ok(self.local_repository.create_user(&data)?)
     }
 
     /// Find the local user but only consider it a match if the supplied email matches
-    async fn find_local_user_by_email(&self, request: &SignInRequest) -> Result<LocalUser, String> {
-        let local_user = self
-            .local_repository
-            .find_user()
-            .map_err(|err| err.to_string());
+    async fn find_local_user_by_email(&self, request: &SignInRequest) -> Result<LocalUser> {
+        let local_user = self.find_local_user();
 
         local_user.and_then(|user| {
             let email_matches = user.get_email() == request.email;
@@ -74,38 +73,31 @@ impl<'a, T: RemoteAuthentication> AuthenticationController<'a, T> {
             if email_matches {
                 Ok(user)
             } else {
-                Err(String::from(
-                    "Could not find a cached user with a matching email",
-                ))
+                Err(EquaterError::NotSignedIn)
             }
         })
     }
 
-    async fn verify_local_user(
-        &self,
-        local_user: Result<LocalUser, String>,
-    ) -> Result<LocalUser, String> {
+    async fn verify_local_user(&self, local_user: Result<LocalUser>) -> Result<LocalUser> {
         if let Ok(user) = local_user {
-            println!(
+            verbose!(
                 "Found local user with email {} -- verifying auth token",
                 user.email
             );
-            let remote_user = self
-                .remote_repository
+            self.remote_repository
                 .get_user(user.auth_token.as_str())
-                .await;
+                .await?;
+            verbose!("Cached user verified");
 
-            if remote_user.is_ok() {
-                println!("Cached user verified");
-                return Ok(user);
-            }
+            return Ok(user);
         }
 
-        Err(String::from("Invalid local user"))
+        Err(EquaterError::NotSignedIn)
     }
 
     fn map_user_data(&self, response: &SignInResponse) -> UserData {
         UserData {
+            profile_id: self.local_repository.get_profile_id(),
             email: response.user.email.clone(),
             auth_token: response.auth_token.clone(),
         }
@@ -115,9 +107,10 @@ impl<'a, T: RemoteAuthentication> AuthenticationController<'a, T> {
 #[cfg(test)]
 mod tests {
     use super::AuthenticationController;
-    use crate::authentication::authentication_api::SignInStrategy;
+    use crate::authentication::authentication_api::{AuthenticationApi, SignInStrategy};
     use crate::authentication::authentication_api_fake::AuthenticationApiFake;
-    use database::{connection::connect, repository::user_repository::UserRepository};
+    use crate::test_common::{connect_to_test_database, make_replay_client, make_test_profile};
+    use database::{repository::user_repository::UserRepository, token_cipher::TokenCipher};
     use diesel::SqliteConnection;
     use fake::{
         faker::internet::en::{Password, SafeEmail},
@@ -139,15 +132,20 @@ mod tests {
         }
     }
 
-    fn make_local_repository(connection: &SqliteConnection) -> UserRepository {
-        UserRepository::new(connection)
+    fn make_local_repository(connection: &SqliteConnection) -> UserRepository<'_> {
+        let profile = make_test_profile(connection);
+
+        UserRepository::new(
+            connection,
+            profile.get_id(),
+            TokenCipher::from_key([42; 32]),
+        )
     }
 
     #[tokio::test]
     #[serial]
     async fn it_should_respond_successfully() {
-        dotenv::from_filename(".test.env").ok();
-        let connection = connect().get().unwrap();
+        let connection = connect_to_test_database().get().unwrap();
         let local_repository = make_local_repository(&connection);
         let controller = make_controller(&local_repository, false);
         let email = SafeEmail().fake();
@@ -162,23 +160,21 @@ mod tests {
     #[tokio::test]
     #[serial]
     async fn it_should_store_a_cached_copy_of_the_user_on_success() {
-        dotenv::from_filename(".test.env").ok();
-        let connection = connect().get().unwrap();
+        let connection = connect_to_test_database().get().unwrap();
         let local_repository = make_local_repository(&connection);
         let controller = make_controller(&local_repository, false);
         let email = SafeEmail().fake();
         let password = Password(Range { start: 12, end: 30 }).fake();
         let strategy = SignInStrategy::StoredValues { email, password };
         let _ = controller.sign_in(strategy).await.unwrap();
-        let local_user = UserRepository::new(&connection).find_user();
+        let local_user = local_repository.find_user();
         assert!(local_user.is_ok(), "Local user not found");
     }
 
     #[tokio::test]
     #[serial]
     async fn it_should_overwrite_the_users_credentials_when_updating_the_cached_data() {
-        dotenv::from_filename(".test.env").ok();
-        let connection = connect().get().unwrap();
+        let connection = connect_to_test_database().get().unwrap();
         let local_repository = make_local_repository(&connection);
         let email: String = SafeEmail().fake();
         let password = Password(Range { start: 12, end: 30 }).fake();
@@ -191,8 +187,8 @@ mod tests {
         let _ = controller.sign_in(strategy.clone()).await.unwrap();
         let existing_user = local_repository.find_user().unwrap();
         assert!(
-            local_repository.truncate().is_ok(),
-            "Failed to truncate user table"
+            local_repository.delete_user().is_ok(),
+            "Failed to sign the test profile out"
         );
         // First sign-in attempt (should overwrite the cache)
         let _ = controller.sign_in(strategy.clone()).await.unwrap();
@@ -204,8 +200,7 @@ mod tests {
     #[tokio::test]
     #[serial]
     async fn it_should_respond_to_the_user_with_an_error_when_the_server_responds_with_an_error() {
-        dotenv::from_filename(".test.env").ok();
-        let connection = connect().get().unwrap();
+        let connection = connect_to_test_database().get().unwrap();
         let local_repository = make_local_repository(&connection);
         let controller = make_controller(&local_repository, true);
         let email = SafeEmail().fake();
@@ -220,5 +215,40 @@ mod tests {
             response.is_err(),
             "Controller did not respond with an error"
         );
+        let error = response.unwrap_err();
+        assert!(error.is_unauthorized());
+        assert_eq!(
+            error.to_string(),
+            "Invalid username or password (401 Unauthorized)"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn it_should_cache_the_user_from_a_replayed_sign_in() {
+        let connection = connect_to_test_database().get().unwrap();
+        let local_repository = make_local_repository(&connection);
+        let controller = AuthenticationController {
+            remote_repository: AuthenticationApi::new(make_replay_client("sign_in")),
+            local_repository: &local_repository,
+        };
+        let strategy = SignInStrategy::StoredValues {
+            email: String::from("admin@example.com"),
+            password: String::from("correct horse battery staple"),
+        };
+
+        let error = controller.sign_in(strategy.clone()).await.unwrap_err();
+        let local_user = controller.sign_in(strategy).await.unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid username or password (401 Unauthorized)"
+        );
+        assert_eq!(local_user.email, "admin@example.com");
+        assert_eq!(local_user.auth_token, "[REDACTED]");
+        assert_eq!(
+            local_repository.find_user().unwrap().email,
+            "admin@example.com"
+        );
     }
 }
@@ -0,0 +1,362 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use http::StatusCode;
+use reqwest::{Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::audit_trail::{path_and_query, redact_value};
+use crate::error::{EquaterError, Result};
+
+/// Bumped whenever the file format changes, so that an old cassette fails to load instead of
+/// replaying the wrong thing
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// Request and response pairs recorded from a server with `--record`, which `--replay` and tests
+/// serve without touching the network. Auth tokens and other secrets are redacted before anything
+/// is saved.
+pub struct Cassette {
+    path: PathBuf,
+    state: Mutex<CassetteState>,
+}
+
+#[derive(Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    /// Lines up with `interactions`. Each interaction is replayed once, so asking for the same
+    /// request twice gets the responses in the order they were recorded.
+    replayed: Vec<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CassetteFile {
+    version: u32,
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query string. The api base is left out so that a cassette replays against any
+    /// profile.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    /// JSON bodies are kept as JSON so that cassettes are easy to read and edit by hand
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    /// A body that isn't JSON, such as an error page from a proxy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl RecordedRequest {
+    pub fn new(request: &Request) -> Self {
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .filter(|bytes| !bytes.is_empty())
+            .map(|bytes| match serde_json::from_slice::<Value>(bytes) {
+                Ok(mut value) => {
+                    redact_value(&mut value);
+                    value
+                }
+                Err(_) => Value::String(format!("<{} bytes>", bytes.len())),
+            });
+
+        Self {
+            method: request.method().to_string(),
+            path: path_and_query(request.url()),
+            body,
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn new(status: StatusCode, bytes: &[u8]) -> Self {
+        let mut response = Self {
+            status: status.as_u16(),
+            body: None,
+            text: None,
+        };
+        if bytes.is_empty() {
+            return response;
+        }
+
+        match serde_json::from_slice::<Value>(bytes) {
+            Ok(mut value) => {
+                redact_value(&mut value);
+                response.body = Some(value);
+            }
+            Err(_) => response.text = Some(String::from_utf8_lossy(bytes).into_owned()),
+        }
+
+        response
+    }
+
+    fn to_response(&self) -> Result<Response> {
+        let body = match (&self.body, &self.text) {
+            (Some(value), _) => value.to_string(),
+            (None, Some(text)) => text.clone(),
+            (None, None) => String::new(),
+        };
+        let response = http::Response::builder()
+            .status(self.status)
+            .body(body)
+            .map_err(|err| {
+                EquaterError::Configuration(format!("Invalid response in cassette: {}", err))
+            })?;
+
+        // reqwest supports converting an http::response::Response into a reqwest::Response
+        Ok(response.into())
+    }
+}
+
+impl Cassette {
+    /// An empty cassette, which `save` writes to `path`
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let invalid = |reason: String| {
+            EquaterError::Configuration(format!(
+                "Could not load the cassette {}: {}",
+                path.display(),
+                reason
+            ))
+        };
+        let contents = fs::read_to_string(path).map_err(|err| invalid(err.to_string()))?;
+        let value: Value =
+            serde_json::from_str(&contents).map_err(|err| invalid(err.to_string()))?;
+        // Checked before the rest of the file so that an old cassette says so, rather than
+        // failing on whichever field changed
+        let version = value.get("version").and_then(Value::as_u64);
+        if version != Some(u64::from(CASSETTE_VERSION)) {
+            return Err(invalid(format!(
+                "expected version {} but found {} -- record it again",
+                CASSETTE_VERSION,
+                version.map_or_else(|| String::from("none"), |version| version.to_string())
+            )));
+        }
+        let file: CassetteFile =
+            serde_json::from_value(value).map_err(|err| invalid(err.to_string()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            state: Mutex::new(CassetteState {
+                replayed: vec![false; file.interactions.len()],
+                interactions: file.interactions,
+            }),
+        })
+    }
+
+    /// Notes the interaction and hands back a copy of the response. The command still sees the
+    /// real body -- only what's saved is redacted.
+    pub async fn record(&self, request: &Request, response: Response) -> Result<Response> {
+        let status = response.status();
+        let bytes = response.bytes().await?;
+        let interaction = Interaction {
+            request: RecordedRequest::new(request),
+            response: RecordedResponse::new(status, &bytes),
+        };
+        let mut state = self.lock();
+        state.interactions.push(interaction);
+        state.replayed.push(true);
+
+        Ok(http::Response::builder()
+            .status(status)
+            .body(bytes)
+            .map_err(|err| EquaterError::Decode(err.to_string()))?
+            .into())
+    }
+
+    /// The first recorded response to the same method, path and body that hasn't been replayed
+    /// yet
+    pub fn replay(&self, request: &Request) -> Result<Response> {
+        let recorded = RecordedRequest::new(request);
+        let mut state = self.lock();
+        let state = &mut *state;
+        let index = state
+            .interactions
+            .iter()
+            .zip(&state.replayed)
+            .position(|(interaction, replayed)| !replayed && interaction.request == recorded)
+            .ok_or_else(|| {
+                EquaterError::Configuration(format!(
+                    "The cassette {} has no response left for {} {}",
+                    self.path.display(),
+                    recorded.method,
+                    recorded.path
+                ))
+            })?;
+        state.replayed[index] = true;
+
+        state.interactions[index].response.to_response()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let file = CassetteFile {
+            version: CASSETTE_VERSION,
+            interactions: self.lock().interactions.clone(),
+        };
+        let mut contents = serde_json::to_string_pretty(&file).map_err(io::Error::from)?;
+        contents.push('\n');
+        if let Some(directory) = self
+            .path
+            .parent()
+            .filter(|path| !path.as_os_str().is_empty())
+        {
+            fs::create_dir_all(directory)?;
+        }
+
+        Ok(fs::write(&self.path, contents)?)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CassetteState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use http::StatusCode;
+    use serde_json::{json, Value};
+
+    use super::{Cassette, CASSETTE_VERSION};
+    use crate::equater_client::{read_json, EquaterClient};
+
+    #[tokio::test]
+    async fn it_should_redact_secrets_from_saved_interactions() {
+        let path =
+            std::env::temp_dir().join(format!("equater-cassette-{}.json", std::process::id()));
+        let client = EquaterClient::new("http://localhost:7111").unwrap();
+        let request = client
+            .post("/api/auth/admin-login")
+            .json(&json!({ "email": "admin@example.com", "password": "hunter2" }))
+            .build()
+            .unwrap();
+        let body = json!({
+            "authToken": "eyJhbGciOiJIUzI1NiJ9",
+            "user": {
+                "email": "admin@example.com",
+                "linkTokens": [{ "plaidLinkToken": "link-sandbox-1", "tokenType": "ITEM_UPDATE" }],
+            },
+        });
+        let response: reqwest::Response = http::Response::builder()
+            .status(StatusCode::CREATED)
+            .body(body.to_string())
+            .unwrap()
+            .into();
+
+        let cassette = Cassette::new(&path);
+        let live: Value = read_json(cassette.record(&request, response).await.unwrap())
+            .await
+            .unwrap();
+        cassette.save().unwrap();
+        let saved: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let replayed: Value = read_json(Cassette::load(&path).unwrap().replay(&request).unwrap())
+            .await
+            .unwrap();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(live, body);
+        assert_eq!(saved["version"], CASSETTE_VERSION);
+        assert_eq!(
+            saved,
+            json!({
+                "version": CASSETTE_VERSION,
+                "interactions": [{
+                    "request": {
+                        "method": "POST",
+                        "path": "/api/auth/admin-login",
+                        "body": { "email": "admin@example.com", "password": "[REDACTED]" },
+                    },
+                    "response": { "status": 201, "body": replayed },
+                }],
+            })
+        );
+        assert_eq!(replayed["authToken"], "[REDACTED]");
+        assert_eq!(
+            replayed["user"]["linkTokens"][0]["plaidLinkToken"],
+            "[REDACTED]"
+        );
+        assert_eq!(
+            replayed["user"]["linkTokens"][0]["tokenType"],
+            "ITEM_UPDATE"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_replay_each_interaction_once() {
+        let path = std::env::temp_dir().join(format!(
+            "equater-cassette-replay-{}.json",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            json!({
+                "version": CASSETTE_VERSION,
+                "interactions": [{
+                    "request": { "method": "GET", "path": "/api/environment" },
+                    "response": { "status": 200, "body": { "environment": "development" } },
+                }],
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let cassette = Cassette::load(&path).unwrap();
+        fs::remove_file(&path).ok();
+        let client = EquaterClient::new("http://localhost:7111").unwrap();
+        let request = client.get("/api/environment").build().unwrap();
+
+        let first: Value = read_json(cassette.replay(&request).unwrap()).await.unwrap();
+        let second = cassette.replay(&request).err().unwrap();
+
+        assert_eq!(first, json!({ "environment": "development" }));
+        assert!(second
+            .to_string()
+            .contains("has no response left for GET /api/environment"));
+    }
+
+    #[test]
+    fn it_should_refuse_cassettes_from_another_version() {
+        let path = std::env::temp_dir().join(format!(
+            "equater-cassette-version-{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, r#"{ "version": 0, "interactions": [] }"#).unwrap();
+
+        let result = Cassette::load(&path);
+        fs::remove_file(&path).ok();
+
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("expected version 1 but found 0"));
+    }
+}
@@ -1,69 +1,798 @@
-use crate::flag_command::FlagCommand;
+use std::path::PathBuf;
 
+use chrono::NaiveDate;
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use database::models::money::Money;
+
+use crate::dev_notify::dev_notify_command::NotificationKind;
+use crate::output::{ColorMode, OutputFormat};
+use crate::vendor::VendorAssociationType;
+
+/// The full command grammar for the CLI. Help menus, unknown-flag errors (with "did you mean"
+/// suggestions) and argument validation are all derived from this tree.
+#[derive(Debug, Parser)]
+#[command(
+    name = "equater",
+    version,
+    about = "Admin tooling for the Equater API",
+    arg_required_else_help = true,
+    propagate_version = true
+)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Flags that are accepted anywhere on the command line, e.g. `equater fetch users --verbose`
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// Load settings from this dotenv file instead of .env
+    #[arg(long, global = true, value_name = "FILE")]
+    pub env: Option<PathBuf>,
+
+    /// Run the command against this profile instead of the active one
+    #[arg(short, long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Print diagnostic output while the command runs
+    #[arg(short, long, global = true)]
+    pub verbose: bool,
+
+    /// Format used to print results. Defaults to the output setting, which is table unless
+    /// configured
+    #[arg(short, long, global = true, value_enum)]
+    pub output: Option<OutputFormat>,
+
+    /// Comma separated columns to print, e.g. id,email. Nested fields use a dot, e.g. vendor.id
+    #[arg(long, global = true, value_delimiter = ',', value_name = "COLUMNS")]
+    pub columns: Option<Vec<String>>,
+
+    /// Serve fetch users and fetch vendors from the local mirror instead of the API
+    #[arg(long, visible_alias = "cached", global = true)]
+    pub offline: bool,
+
+    /// Skip confirmation prompts, including typing the environment's name before changing data on
+    /// production
+    #[arg(short, long, global = true)]
+    pub yes: bool,
+
+    /// When to color tables and banners. Defaults to the color setting
+    #[arg(long, global = true, value_enum)]
+    pub color: Option<ColorMode>,
+
+    /// Also write requests that only read data to the audit log
+    #[arg(long, global = true)]
+    pub audit_reads: bool,
+
+    /// Save every API request and its response to this cassette file, with secrets redacted.
+    /// Refused on production.
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Answer API requests from a cassette file saved with --record instead of the server
+    #[arg(long, global = true, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
 pub enum Command {
-    Login,
-    Logout,
+    /// Sign in with an admin account and cache the auth token locally
+    Login(LoginArgs),
+    /// Remove the locally cached auth token
+    Logout(LogoutArgs),
+    /// Fetch users or vendors from the API
+    #[command(subcommand)]
     Fetch(FetchCommand),
-    Help,
+    /// Look up everything about a single user
+    #[command(subcommand)]
+    Users(UsersCommand),
+    /// Manage vendors
+    #[command(subcommand)]
+    Vendors(VendorsCommand),
+    /// Inspect a shared expense along with its agreements and transactions
+    #[command(subcommand)]
+    Expense(ExpenseCommand),
+    /// Inspect an agreement to split a shared expense
+    #[command(subcommand)]
+    Agreement(AgreementCommand),
+    /// Inspect a transaction between two users
+    #[command(subcommand)]
+    Transaction(TransactionCommand),
+    /// Work through queues that require manual review
+    #[command(subcommand)]
+    Review(ReviewCommand),
+    /// List shared bill agreements that have never been matched to a transaction
+    Watchlist(WatchlistArgs),
+    /// Browse users, vendors, the review queue and the watchlist in a full-screen terminal UI
+    Tui,
+    /// Refresh the local mirror of vendors and users used by --offline
+    Sync(SyncArgs),
+    /// List the requests that changed data through the CLI, newest first
+    History(HistoryArgs),
+    /// Simulate Plaid transactions on a development or staging server
+    #[command(subcommand)]
+    Simulate(SimulateCommand),
+    /// Tools for testing the mobile apps against a development or staging server
+    #[command(subcommand)]
+    Dev(DevCommand),
+    /// Manage named profiles for each environment (local, staging, production, etc.)
+    #[command(subcommand)]
+    Profile(ProfileCommand),
+    /// Inspect the local database
+    #[command(subcommand)]
+    Database(DatabaseCommand),
+    /// Show or change settings and where each value comes from
+    #[command(subcommand)]
+    Config(ConfigCommand),
 }
 
+#[derive(Debug, Args)]
+pub struct LoginArgs {
+    /// Sign in as this admin instead of being prompted
+    #[arg(long, requires = "password_stdin")]
+    pub email: Option<String>,
+    /// Read the password from the first line of stdin instead of prompting, e.g. in scripts
+    #[arg(long, requires = "email")]
+    pub password_stdin: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct LogoutArgs {
+    /// Sign out of every profile and wipe every cached auth token
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, Subcommand)]
 pub enum FetchCommand {
-    Users(String),        // Search parameter
-    Vendors(FlagCommand), // Search parameter
-}
-
-impl Command {
-    pub fn from(input: &Vec<String>) -> Result<Self, &'static str> {
-        let command = match input.get(1) {
-            Some(value) => Ok(value),
-            None => Err(Command::make_default_error()),
-        }?;
-
-        match command.to_lowercase().trim() {
-            "login" => Ok(Self::Login),
-            "logout" => Ok(Self::Logout),
-            "fetch" => Ok(Self::Fetch(FetchCommand::from(input)?)),
-            "--help" => Ok(Self::Help),
-            "-h" => Ok(Self::Help),
-            _ => Err(Command::make_default_error()),
+    /// Search for users by name or email
+    Users(FetchUsersArgs),
+    /// Search, list popular or list vendors that require review
+    Vendors(FetchVendorsArgs),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum UsersCommand {
+    /// Show a user's profile, accounts, relationships, agreements and transactions
+    Show {
+        /// ID or email of the user
+        user: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExpenseCommand {
+    /// Show a shared expense and the full lifecycle of every agreement and transaction
+    Show {
+        /// ID of the shared expense
+        id: u32,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AgreementCommand {
+    /// Show an agreement, the shared expense it belongs to and the transactions it's paid
+    Show {
+        /// ID of the agreement
+        id: u32,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TransactionCommand {
+    /// Show a transaction and where its transfer is
+    Show {
+        /// ID of the transaction
+        id: u32,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VendorsCommand {
+    /// List, create or delete vendor associations, or merge one vendor into another
+    #[command(subcommand)]
+    Associations(AssociationsCommand),
+    /// Upload vendor logos
+    #[command(subcommand)]
+    Logo(LogoCommand),
+    /// List the transactions matched to a vendor along with summary statistics
+    Transactions(VendorTransactionsArgs),
+    /// Edit or create vendors in bulk from a CSV, JSON or NDJSON file, after confirming a diff
+    Apply(ApplyVendorsArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ApplyVendorsArgs {
+    /// Rows of id, friendly_name, ppd_id and vendor_identity_cannot_be_determined. Rows without
+    /// an id create a vendor and empty fields are left as they are.
+    pub file: PathBuf,
+
+    /// Print the diff without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// How many requests to send at once
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=16))]
+    pub concurrency: u32,
+
+    /// Write the rows that fail to this file, in the same format, so that only they can be
+    /// applied again
+    #[arg(long, value_name = "FILE")]
+    pub failures: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct VendorTransactionsArgs {
+    /// ID of the vendor
+    pub vendor_id: u32,
+
+    /// Only include transactions on or after this date, e.g. 2022-05-01
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub since: Option<NaiveDate>,
+
+    /// Only include transactions on or before this date, e.g. 2022-05-31
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub until: Option<NaiveDate>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LogoCommand {
+    /// Upload a PNG, JPEG, GIF or WebP logo (up to 5 MB). This also marks the vendor as reviewed.
+    Upload(UploadLogoArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct UploadLogoArgs {
+    /// ID of the vendor
+    pub vendor_id: u32,
+
+    /// Path to the image
+    pub file: PathBuf,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AssociationsCommand {
+    /// List the vendors associated with a vendor
+    List {
+        /// ID of the vendor
+        vendor_id: u32,
+    },
+    /// Count charges from one vendor towards bills split with another
+    Create(CreateAssociationArgs),
+    /// Remove an association from a vendor
+    Delete {
+        /// ID of the vendor
+        vendor_id: u32,
+        /// ID of the association (see equater vendors associations list)
+        association_id: u32,
+    },
+    /// Move every transaction from a vendor to an existing vendor and delete it
+    Merge(MergeVendorsArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct CreateAssociationArgs {
+    /// ID of the vendor whose bills should pick up the associated vendor's charges
+    pub vendor_id: u32,
+
+    /// ID of the associated vendor
+    pub associated_vendor_id: u32,
+
+    /// How the two vendors are related
+    #[arg(short = 't', long = "type", value_enum, default_value = "other")]
+    pub association_type: VendorAssociationType,
+
+    /// Why the vendors are associated
+    #[arg(short, long)]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct MergeVendorsArgs {
+    /// ID of the vendor that will be merged and deleted
+    pub vendor_id: u32,
+
+    /// ID of the existing vendor that will be kept
+    #[arg(long, value_name = "EXISTING_VENDOR_ID")]
+    pub into: u32,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReviewCommand {
+    /// Step through new vendors one at a time and mark them as reviewed
+    Vendors(ReviewVendorsArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ReviewVendorsArgs {
+    /// Forget skipped vendors and start from the top of the queue
+    #[arg(long)]
+    pub restart: bool,
+
+    /// Number of recent transactions to show for each vendor
+    #[arg(short, long, default_value_t = 10)]
+    pub transactions: usize,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SimulateCommand {
+    /// Store a transaction for an account as if Plaid had just reported it
+    Transaction(SimulateTransactionArgs),
+    /// Charge a shared expense as if its vendor had billed it, or run a recurring payment now
+    Expense(SimulateExpenseArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SimulateTransactionArgs {
+    /// ID of the account the transaction is charged to
+    #[arg(long, value_name = "ID")]
+    pub account: u32,
+
+    /// Name of the transaction, as a bank would report it
+    #[arg(long)]
+    pub name: String,
+
+    /// Merchant name. Defaults to the transaction name.
+    #[arg(long)]
+    pub merchant: Option<String>,
+
+    /// Amount in dollars, e.g. 12.34. Negative amounts are refunds.
+    #[arg(long, allow_hyphen_values = true)]
+    pub amount: Money,
+
+    /// PPD ID sent with the transaction
+    #[arg(long)]
+    pub ppd_id: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct SimulateExpenseArgs {
+    /// ID of the shared expense
+    pub id: u32,
+
+    /// Amount in dollars the vendor charged. Required for shared bills, and not used by recurring
+    /// payments.
+    #[arg(long)]
+    pub amount: Option<Money>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DevCommand {
+    /// Send an agreement or transaction push notification again
+    Notify(NotifyArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyArgs {
+    /// Which notification to send
+    #[arg(value_enum)]
+    pub kind: NotificationKind,
+
+    /// IDs of the agreements or transactions to send it for
+    #[arg(required = true)]
+    pub ids: Vec<u32>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProfileCommand {
+    /// Add a profile that points at an Equater API
+    Add(AddProfileArgs),
+    /// List every profile and the user signed in to it
+    List,
+    /// Make a profile the default for every command
+    Use {
+        /// Name of the profile
+        name: String,
+    },
+    /// Remove a profile and sign out of it
+    Remove {
+        /// Name of the profile
+        name: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct AddProfileArgs {
+    /// Name used to refer to the profile, e.g. staging
+    pub name: String,
+
+    /// Base url of the API, e.g. https://www.equater.io
+    #[arg(long)]
+    pub api_base: String,
+
+    /// Make this the active profile
+    #[arg(long)]
+    pub activate: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DatabaseCommand {
+    /// Show where the local database is and its schema version
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Show a setting's effective value and where it came from
+    Get {
+        /// Name of the setting, e.g. output
+        key: String,
+    },
+    /// Save a setting to the config file
+    Set {
+        /// Name of the setting, e.g. output
+        key: String,
+        value: String,
+    },
+    /// Show every setting's effective value and where it came from
+    List,
+    /// Open the config file in $VISUAL or $EDITOR
+    Edit,
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct FetchUsersArgs {
+    /// Name or email to search for
+    #[arg(short, long)]
+    pub search: String,
+
+    /// Maximum number of users to print
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+}
+
+/// Only one of `--search`, `--review-required`, `--popular`, `--all` or `--page` may be supplied.
+/// When none of them are supplied popular vendors are listed.
+#[derive(Clone, Debug, Args)]
+#[command(group(
+    ArgGroup::new("operation").args(["search", "review_required", "popular", "all", "page"])
+))]
+pub struct FetchVendorsArgs {
+    /// Name of an approved vendor to search for
+    #[arg(short, long)]
+    pub search: Option<String>,
+
+    /// List new vendors that require manual review
+    #[arg(long)]
+    pub review_required: bool,
+
+    /// List the most popular vendors (in terms of most bills split)
+    #[arg(long)]
+    pub popular: bool,
+
+    /// List every vendor, printing each page of 100 as soon as it arrives
+    #[arg(long)]
+    pub all: bool,
+
+    /// List a single page of 100 vendors, starting from page 0
+    #[arg(long, value_name = "N")]
+    pub page: Option<u32>,
+
+    /// Maximum number of vendors to print. With --all no more pages are fetched once reached.
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct SyncArgs {
+    /// Add users matching this search to the mirror. Can be repeated.
+    #[arg(long = "search-users", value_name = "TERM")]
+    pub search_users: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchlistArgs {
+    /// Only show agreements for vendors that have never been matched to a shared bill
+    #[arg(long)]
+    pub new_vendors: bool,
+    /// Print the watchlist without prompting to jump to a vendor or user
+    #[arg(long)]
+    pub no_jump: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Include requests made with every profile instead of only the active one
+    #[arg(long)]
+    pub all_profiles: bool,
+
+    /// Only include requests made by this operator
+    #[arg(long, value_name = "EMAIL")]
+    pub operator: Option<String>,
+
+    /// Only include requests with this HTTP method, e.g. PATCH
+    #[arg(long)]
+    pub method: Option<String>,
+
+    /// Only include requests whose path contains this, e.g. /api/vendor/12
+    #[arg(long)]
+    pub path: Option<String>,
+
+    /// Only include requests on or after this date (UTC), e.g. 2022-05-01
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub since: Option<NaiveDate>,
+
+    /// Only include requests on or before this date (UTC), e.g. 2022-05-31
+    #[arg(long, value_name = "YYYY-MM-DD")]
+    pub until: Option<NaiveDate>,
+
+    /// Include requests that only read data, which are logged with --audit-reads
+    #[arg(long)]
+    pub reads: bool,
+
+    /// Maximum number of entries to print
+    #[arg(short, long)]
+    pub limit: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AssociationsCommand, Cli, Command, ConfigCommand, FetchCommand, LoginArgs, LogoutArgs,
+        ProfileCommand, ReviewCommand, SimulateCommand, VendorsCommand,
+    };
+    use crate::output::{ColorMode, OutputFormat};
+    use crate::vendor::VendorAssociationType;
+    use clap::{error::ErrorKind, CommandFactory, Parser};
+
+    #[test]
+    fn it_should_have_a_valid_command_tree() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn it_should_accept_combined_flags() {
+        let cli = Cli::try_parse_from([
+            "equater", "fetch", "vendors", "--search", "netflix", "--limit", "20", "--output",
+            "json",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.global.output, Some(OutputFormat::Json));
+        match cli.command {
+            Command::Fetch(FetchCommand::Vendors(args)) => {
+                assert_eq!(args.search.as_deref(), Some("netflix"));
+                assert_eq!(args.limit, Some(20));
+            }
+            _ => panic!("Expected fetch vendors"),
+        }
+    }
+
+    #[test]
+    fn it_should_accept_global_flags_before_the_subcommand() {
+        let cli =
+            Cli::try_parse_from(["equater", "--verbose", "--env", ".test.env", "logout"]).unwrap();
+
+        assert!(cli.global.verbose);
+        assert_eq!(cli.global.env.unwrap().to_str(), Some(".test.env"));
+    }
+
+    #[test]
+    fn it_should_accept_a_profile_override_after_the_subcommand() {
+        let cli =
+            Cli::try_parse_from(["equater", "fetch", "users", "-s", "robert", "-p", "staging"])
+                .unwrap();
+
+        assert_eq!(cli.global.profile.as_deref(), Some("staging"));
+    }
+
+    #[test]
+    fn it_should_parse_profile_subcommands() {
+        let cli = Cli::try_parse_from([
+            "equater",
+            "profile",
+            "add",
+            "local",
+            "--api-base",
+            "http://localhost:7111",
+            "--activate",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Profile(ProfileCommand::Add(args)) => {
+                assert_eq!(args.name, "local");
+                assert_eq!(args.api_base, "http://localhost:7111");
+                assert!(args.activate);
+            }
+            _ => panic!("Expected profile add"),
         }
     }
 
-    pub fn make_default_error() -> &'static str {
-        "Please enter a valid command"
+    #[test]
+    fn it_should_parse_config_set_with_a_color_override() {
+        let cli = Cli::try_parse_from([
+            "equater",
+            "config",
+            "set",
+            "page_size",
+            "25",
+            "--color",
+            "never",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.global.color, Some(ColorMode::Never));
+        assert!(matches!(
+            cli.command,
+            Command::Config(ConfigCommand::Set { key, value }) if key == "page_size" && value == "25"
+        ));
     }
 
-    pub fn make_help_menu() -> &'static str {
-        "todo: help menu"
+    #[test]
+    fn it_should_only_read_the_password_from_stdin_with_an_email() {
+        let cli = Cli::try_parse_from([
+            "equater",
+            "login",
+            "--email",
+            "admin@example.com",
+            "--password-stdin",
+        ])
+        .unwrap();
+        let without_email = Cli::try_parse_from(["equater", "login", "--password-stdin"]);
+
+        assert!(matches!(
+            cli.command,
+            Command::Login(LoginArgs { email: Some(email), password_stdin: true }) if email == "admin@example.com"
+        ));
+        assert_eq!(
+            without_email.unwrap_err().kind(),
+            ErrorKind::MissingRequiredArgument
+        );
     }
-}
 
-impl FetchCommand {
-    pub fn from(input: &Vec<String>) -> Result<Self, &'static str> {
-        println!("{:?}", input);
-        let command = input.get(2).ok_or_else(Command::make_default_error)?;
+    #[test]
+    fn it_should_parse_logout_all() {
+        let single = Cli::try_parse_from(["equater", "logout"]).unwrap();
+        let all = Cli::try_parse_from(["equater", "logout", "--all"]).unwrap();
+
+        assert!(matches!(
+            single.command,
+            Command::Logout(LogoutArgs { all: false })
+        ));
+        assert!(matches!(
+            all.command,
+            Command::Logout(LogoutArgs { all: true })
+        ));
+    }
 
-        return match command.to_lowercase().trim() {
-            "users" => {
-                let search_term_error =
-                    "Please specify a search term like equater fetch users --search foo";
-                let flag_command = FlagCommand::new_from_clone(input.get(3), input.get(4));
+    #[test]
+    fn it_should_default_the_number_of_transactions_to_review() {
+        let cli = Cli::try_parse_from(["equater", "review", "vendors", "--restart"]).unwrap();
 
-                if !flag_command.flag_is("--search") || !flag_command.has_value() {
-                    println!("here");
-                    return Err(search_term_error);
-                }
+        match cli.command {
+            Command::Review(ReviewCommand::Vendors(args)) => {
+                assert!(args.restart);
+                assert_eq!(args.transactions, 10);
+            }
+            _ => panic!("Expected review vendors"),
+        }
+    }
 
-                let value = flag_command.value_or(search_term_error)?;
+    #[test]
+    fn it_should_parse_vendor_associations() {
+        let cli = Cli::try_parse_from([
+            "equater",
+            "vendors",
+            "associations",
+            "create",
+            "12",
+            "34",
+            "--type",
+            "parent-company",
+            "--notes",
+            "TRG manages Icon Central",
+        ])
+        .unwrap();
 
-                return Ok(Self::Users(value));
+        match cli.command {
+            Command::Vendors(VendorsCommand::Associations(AssociationsCommand::Create(args))) => {
+                assert_eq!(args.vendor_id, 12);
+                assert_eq!(args.associated_vendor_id, 34);
+                assert_eq!(args.association_type, VendorAssociationType::ParentCompany);
+                assert_eq!(args.notes.as_deref(), Some("TRG manages Icon Central"));
             }
-            "vendors" => {
-                let flag_command = FlagCommand::new_from_clone(input.get(3), input.get(4));
+            _ => panic!("Expected vendors associations create"),
+        }
 
-                return Ok(Self::Vendors(flag_command));
+        let error =
+            Cli::try_parse_from(["equater", "vendors", "associations", "merge", "12"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn it_should_parse_simulated_amounts_as_dollars() {
+        let cli = Cli::try_parse_from([
+            "equater",
+            "simulate",
+            "transaction",
+            "--account",
+            "3",
+            "--name",
+            "NETFLIX.COM",
+            "--amount",
+            "-12.34",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Simulate(SimulateCommand::Transaction(args)) => {
+                assert_eq!(args.account, 3);
+                assert_eq!(args.amount.cents(), -1234);
+            }
+            _ => panic!("Expected simulate transaction"),
+        }
+
+        let error = Cli::try_parse_from([
+            "equater", "simulate", "expense", "100", "--amount", "12.345",
+        ])
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn it_should_parse_vendor_transaction_date_filters() {
+        let cli = Cli::try_parse_from([
+            "equater",
+            "vendors",
+            "transactions",
+            "12",
+            "--since",
+            "2022-05-01",
+        ])
+        .unwrap();
+
+        match cli.command {
+            Command::Vendors(VendorsCommand::Transactions(args)) => {
+                assert_eq!(args.vendor_id, 12);
+                assert_eq!(args.since.unwrap().to_string(), "2022-05-01");
+                assert!(args.until.is_none());
             }
-            _ => Err(Command::make_default_error()),
-        };
+            _ => panic!("Expected vendors transactions"),
+        }
+
+        let error = Cli::try_parse_from([
+            "equater",
+            "vendors",
+            "transactions",
+            "12",
+            "--until",
+            "May 1",
+        ])
+        .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn it_should_suggest_a_similar_flag_when_the_flag_is_unknown() {
+        let error =
+            Cli::try_parse_from(["equater", "fetch", "users", "--serch", "robert"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+        assert!(error.to_string().contains("--search"));
+    }
+
+    #[test]
+    fn it_should_require_a_search_term_for_users() {
+        let error = Cli::try_parse_from(["equater", "fetch", "users"]).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn it_should_reject_conflicting_vendor_operations() {
+        let error = Cli::try_parse_from([
+            "equater",
+            "fetch",
+            "vendors",
+            "--popular",
+            "--review-required",
+        ])
+        .unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::ArgumentConflict);
     }
 }
@@ -0,0 +1,2 @@
+pub mod config_command;
+pub mod config_settings;
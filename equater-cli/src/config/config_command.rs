@@ -0,0 +1,294 @@
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+use std::process;
+use std::slice;
+
+use serde::Serialize;
+
+use crate::commands::ConfigCommand;
+use crate::error::{EquaterError, Result};
+use crate::output::{Columns, OutputOptions};
+
+use super::config_settings::{parse_config_file, Config, ResolvedValue, Setting};
+
+const MASKED_VALUE: &str = "********";
+
+/// A setting's effective value and where it came from
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigEntry {
+    pub key: String,
+    pub value: Option<String>,
+    pub source: String,
+    pub environment_variable: String,
+    pub description: String,
+}
+
+impl Columns for ConfigEntry {
+    fn default_columns() -> &'static [&'static str] {
+        &["key", "value", "source"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "key",
+            "value",
+            "source",
+            "environment_variable",
+            "description",
+        ]
+    }
+}
+
+impl From<&ResolvedValue> for ConfigEntry {
+    fn from(resolved: &ResolvedValue) -> Self {
+        let setting = resolved.setting;
+
+        Self {
+            key: setting.key().to_string(),
+            value: resolved.value.as_ref().map(|value| {
+                if setting.is_secret() {
+                    String::from(MASKED_VALUE)
+                } else {
+                    value.clone()
+                }
+            }),
+            source: resolved.source.to_string(),
+            environment_variable: setting.environment_variable().to_string(),
+            description: setting.description().to_string(),
+        }
+    }
+}
+
+pub struct ManageConfigCommand<'a> {
+    config: &'a Config,
+}
+
+impl<'a> ManageConfigCommand<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        Self { config }
+    }
+
+    pub fn execute(&self, command: &ConfigCommand, output: &OutputOptions) -> Result<String> {
+        match command {
+            ConfigCommand::Get { key } => {
+                let entry = ConfigEntry::from(self.config.entry(Setting::from_key(key)?));
+                output.render(slice::from_ref(&entry))?;
+
+                Ok(String::from(""))
+            }
+            ConfigCommand::List => {
+                let entries: Vec<ConfigEntry> = self
+                    .config
+                    .entries()
+                    .iter()
+                    .map(ConfigEntry::from)
+                    .collect();
+                output.render(&entries)?;
+
+                Ok(String::from(""))
+            }
+            ConfigCommand::Set { key, value } => self.set(key, value),
+            ConfigCommand::Edit => self.edit(),
+        }
+    }
+
+    /// Writes the value to the config file. The file is rewritten, so comments aren't kept --
+    /// use `config edit` to keep them.
+    fn set(&self, key: &str, value: &str) -> Result<String> {
+        let setting = Setting::from_key(key)?;
+        setting.validate(value).map_err(EquaterError::Validation)?;
+        let path = self.config_file()?;
+
+        let mut table = match fs::read_to_string(path) {
+            Ok(contents) => {
+                parse_config_file(&contents, path)?;
+                toml::from_str::<toml::value::Table>(&contents).unwrap_or_default()
+            }
+            Err(_) => toml::value::Table::new(),
+        };
+        let toml_value = match value.parse::<i64>() {
+            Ok(number) if setting.is_integer() => toml::Value::Integer(number),
+            _ => toml::Value::String(value.to_string()),
+        };
+        table.insert(setting.key().to_string(), toml_value);
+        let contents = toml::to_string(&table).map_err(|err| {
+            EquaterError::Configuration(format!("Could not write {}: {}", path.display(), err))
+        })?;
+        write_config_file(path, &contents)?;
+
+        let displayed = if setting.is_secret() {
+            MASKED_VALUE
+        } else {
+            value
+        };
+        let source = &self.config.entry(setting).source;
+        if source.overrides_config_file() {
+            return Ok(format!(
+                "Set {} to {} in {}, but the {} takes precedence",
+                key,
+                displayed,
+                path.display(),
+                source
+            ));
+        }
+
+        Ok(format!(
+            "Set {} to {} in {}",
+            key,
+            displayed,
+            path.display()
+        ))
+    }
+
+    /// Opens the config file in `$VISUAL` or `$EDITOR`, creating it with every setting commented
+    /// out if it doesn't exist yet, and checks the file once the editor exits
+    fn edit(&self) -> Result<String> {
+        let path = self.config_file()?;
+        if !path.exists() {
+            write_config_file(path, &make_template())?;
+        }
+
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .unwrap_or_else(|_| String::from("vi"));
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or("vi");
+        let status = process::Command::new(program)
+            .args(words)
+            .arg(path)
+            .status()
+            .map_err(|err| {
+                EquaterError::Configuration(format!("Could not start {}: {}", editor, err))
+            })?;
+        if !status.success() {
+            return Err(EquaterError::Configuration(format!(
+                "{} exited with {}",
+                editor, status
+            )));
+        }
+
+        let contents = fs::read_to_string(path)?;
+        for (setting, value) in parse_config_file(&contents, path)? {
+            setting.validate(&value).map_err(|message| {
+                EquaterError::Configuration(format!("{} is invalid: {}", path.display(), message))
+            })?;
+        }
+
+        Ok(format!("Saved {}", path.display()))
+    }
+
+    fn config_file(&self) -> Result<&'a Path> {
+        self.config.config_file().ok_or_else(|| {
+            EquaterError::Configuration(String::from(
+                "Could not find a home directory for the config file",
+            ))
+        })
+    }
+}
+
+/// The file can hold the token passphrase, so it's only readable by its owner. The mode is set
+/// on every write since a file created by hand or by an editor may be readable by others.
+fn write_config_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())?;
+
+    Ok(())
+}
+
+fn make_template() -> String {
+    let mut template = String::from(
+        "# Equater CLI settings. .env, environment variables and flags take precedence over this file.\n",
+    );
+    for setting in Setting::ALL {
+        template.push_str(&format!(
+            "\n# {} ({})\n# {} = \n",
+            setting.description(),
+            setting.environment_variable(),
+            setting.key()
+        ));
+    }
+
+    template
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::{make_template, ConfigEntry, ManageConfigCommand};
+    use crate::config::config_settings::{parse_config_file, Config, Setting, ValueSource};
+
+    #[test]
+    fn it_should_mask_secrets() {
+        let mut config = Config::with_defaults(None);
+        config
+            .set(
+                Setting::TokenPassphrase,
+                String::from("correct horse"),
+                ValueSource::Environment("EQUATER_TOKEN_PASSPHRASE"),
+            )
+            .unwrap();
+
+        let entry = ConfigEntry::from(config.entry(Setting::TokenPassphrase));
+
+        assert_eq!(entry.value.as_deref(), Some("********"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_should_write_settings_to_the_config_file() {
+        let directory = std::env::temp_dir().join(format!("equater-config-{}", std::process::id()));
+        let path = directory.join("config.toml");
+        let config = Config::with_defaults(Some(path.clone()));
+        let command = ManageConfigCommand::new(&config);
+        // A file created by hand, readable by everyone
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        command.set("output", "json").unwrap();
+        command.set("page_size", "25").unwrap();
+        let invalid = command.set("page_size", "lots");
+        let contents = fs::read_to_string(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(invalid.unwrap_err().exit_code(), 2);
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(
+            parse_config_file(&contents, &path).unwrap(),
+            vec![
+                (Setting::Output, String::from("json")),
+                (Setting::PageSize, String::from("25"))
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_only_comment_out_settings_in_the_template() {
+        let template = make_template();
+
+        assert!(
+            parse_config_file(&template, std::path::Path::new("config.toml"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,633 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::ValueEnum;
+use database::connection::default_database_url;
+use database::token_cipher::{default_key_file, TokenCipher};
+
+use crate::commands::GlobalArgs;
+use crate::environment::environment_guard::ConfirmationPolicy;
+use crate::equater_client::{CONNECT_TIMEOUT, REQUEST_TIMEOUT};
+use crate::error::{EquaterError, Result};
+use crate::output::{ColorMode, OutputFormat};
+
+/// Everything that can be configured. Each setting can be set in the config file with its key,
+/// in `.env` or the environment with its variable, and some of them with a flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    ApiBase,
+    DatabaseUrl,
+    TokenKeyFile,
+    TokenPassphrase,
+    Output,
+    PageSize,
+    ConnectTimeout,
+    RequestTimeout,
+    Color,
+    Confirm,
+}
+
+impl Setting {
+    pub const ALL: [Setting; 10] = [
+        Setting::ApiBase,
+        Setting::DatabaseUrl,
+        Setting::TokenKeyFile,
+        Setting::TokenPassphrase,
+        Setting::Output,
+        Setting::PageSize,
+        Setting::ConnectTimeout,
+        Setting::RequestTimeout,
+        Setting::Color,
+        Setting::Confirm,
+    ];
+
+    pub fn from_key(key: &str) -> Result<Setting> {
+        Setting::ALL
+            .into_iter()
+            .find(|setting| setting.key() == key)
+            .ok_or_else(|| {
+                let keys: Vec<&str> = Setting::ALL.iter().map(Setting::key).collect();
+                EquaterError::Validation(format!(
+                    "Unknown setting {} -- expected one of {}",
+                    key,
+                    keys.join(", ")
+                ))
+            })
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Setting::ApiBase => "api_base",
+            Setting::DatabaseUrl => "database_url",
+            Setting::TokenKeyFile => "token_key_file",
+            Setting::TokenPassphrase => "token_passphrase",
+            Setting::Output => "output",
+            Setting::PageSize => "page_size",
+            Setting::ConnectTimeout => "connect_timeout",
+            Setting::RequestTimeout => "request_timeout",
+            Setting::Color => "color",
+            Setting::Confirm => "confirm",
+        }
+    }
+
+    pub fn environment_variable(&self) -> &'static str {
+        match self {
+            Setting::ApiBase => "EQUATER_API_BASE",
+            Setting::DatabaseUrl => "EQUATER_LOCAL_DATABASE_URL",
+            Setting::TokenKeyFile => "EQUATER_TOKEN_KEY_FILE",
+            Setting::TokenPassphrase => "EQUATER_TOKEN_PASSPHRASE",
+            Setting::Output => "EQUATER_OUTPUT",
+            Setting::PageSize => "EQUATER_PAGE_SIZE",
+            Setting::ConnectTimeout => "EQUATER_CONNECT_TIMEOUT",
+            Setting::RequestTimeout => "EQUATER_REQUEST_TIMEOUT",
+            Setting::Color => "EQUATER_COLOR",
+            Setting::Confirm => "EQUATER_CONFIRM",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Setting::ApiBase => "API used to create the default profile when there are no profiles",
+            Setting::DatabaseUrl => "Path of the local SQLite database",
+            Setting::TokenKeyFile => "Key file used to encrypt cached auth tokens",
+            Setting::TokenPassphrase => {
+                "Passphrase used to encrypt cached auth tokens instead of the key file"
+            }
+            Setting::Output => "Default --output: table, json, ndjson, csv or yaml",
+            Setting::PageSize => {
+                "Default --limit for fetch users and fetch vendors (except fetch vendors --all)"
+            }
+            Setting::ConnectTimeout => "Seconds to wait for a connection to the API",
+            Setting::RequestTimeout => "Seconds to wait for the API to respond",
+            Setting::Color => "Color tables and banners: auto, always or never",
+            Setting::Confirm => {
+                "Which changes have to be confirmed: production, always or never (--yes)"
+            }
+        }
+    }
+
+    /// Secrets are never printed by `config get` or `config list`
+    pub fn is_secret(&self) -> bool {
+        matches!(self, Setting::TokenPassphrase)
+    }
+
+    /// Written to the config file as TOML integers rather than strings
+    pub fn is_integer(&self) -> bool {
+        matches!(
+            self,
+            Setting::PageSize | Setting::ConnectTimeout | Setting::RequestTimeout
+        )
+    }
+
+    fn default_value(&self) -> Option<String> {
+        match self {
+            Setting::ApiBase | Setting::TokenPassphrase | Setting::PageSize => None,
+            Setting::DatabaseUrl => default_database_url(),
+            Setting::TokenKeyFile => default_key_file().map(|path| path.display().to_string()),
+            Setting::Output => Some(value_name(OutputFormat::default())),
+            Setting::ConnectTimeout => Some(CONNECT_TIMEOUT.as_secs().to_string()),
+            Setting::RequestTimeout => Some(REQUEST_TIMEOUT.as_secs().to_string()),
+            Setting::Color => Some(value_name(ColorMode::default())),
+            Setting::Confirm => Some(value_name(ConfirmationPolicy::default())),
+        }
+    }
+
+    /// Returns a message describing what's wrong with `value`
+    pub fn validate(&self, value: &str) -> std::result::Result<(), String> {
+        match self {
+            Setting::ApiBase if !value.starts_with("http://") && !value.starts_with("https://") => {
+                Err(format!(
+                    "{} must start with http:// or https://",
+                    self.key()
+                ))
+            }
+            Setting::DatabaseUrl | Setting::TokenKeyFile | Setting::TokenPassphrase
+                if value.trim().is_empty() =>
+            {
+                Err(format!("{} can't be empty", self.key()))
+            }
+            Setting::Output => validate_choice::<OutputFormat>(*self, value),
+            Setting::Color => validate_choice::<ColorMode>(*self, value),
+            Setting::Confirm => validate_choice::<ConfirmationPolicy>(*self, value),
+            Setting::PageSize | Setting::ConnectTimeout | Setting::RequestTimeout => {
+                match value.parse::<u64>() {
+                    Ok(number) if number > 0 => Ok(()),
+                    _ => Err(format!("{} must be a whole number above 0", self.key())),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Where an effective value came from, from lowest to highest precedence
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueSource {
+    Default,
+    ConfigFile(PathBuf),
+    DotEnv(PathBuf),
+    Environment(&'static str),
+    Flag(&'static str),
+}
+
+impl ValueSource {
+    /// True for sources that win over the config file
+    pub fn overrides_config_file(&self) -> bool {
+        matches!(
+            self,
+            ValueSource::DotEnv(_) | ValueSource::Environment(_) | ValueSource::Flag(_)
+        )
+    }
+}
+
+impl Display for ValueSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValueSource::Default => write!(f, "default"),
+            ValueSource::ConfigFile(path) => write!(f, "config file {}", path.display()),
+            ValueSource::DotEnv(path) => write!(f, "dotenv file {}", path.display()),
+            ValueSource::Environment(variable) => write!(f, "environment variable {}", variable),
+            ValueSource::Flag(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ResolvedValue {
+    pub setting: Setting,
+    pub value: Option<String>,
+    pub source: ValueSource,
+}
+
+/// Settings layered from lowest to highest precedence: built-in defaults, the config file,
+/// `.env` (or `--env`), environment variables and finally flags. Every value is validated as
+/// it's layered, so the typed accessors can fall back to defaults without reporting errors.
+#[derive(Debug)]
+pub struct Config {
+    values: Vec<ResolvedValue>,
+    config_file: Option<PathBuf>,
+    /// Whether an invalid value is an error rather than a problem to report
+    strict: bool,
+    problems: Vec<String>,
+}
+
+impl Config {
+    pub fn load(global: &GlobalArgs) -> Result<Self> {
+        Self::layer(global, true)
+    }
+
+    /// Invalid values are skipped and kept as `problems` instead of failing, so that
+    /// `config set` and `config edit` can still fix them
+    pub fn load_leniently(global: &GlobalArgs) -> Self {
+        Self::layer(global, false).expect("lenient loading doesn't fail")
+    }
+
+    fn layer(global: &GlobalArgs, strict: bool) -> Result<Self> {
+        let mut config = Self {
+            strict,
+            ..Self::with_defaults(config_file_path())
+        };
+        if let Some(path) = config.config_file.clone().filter(|path| path.exists()) {
+            config.apply_config_file(&path)?;
+        }
+        match &global.env {
+            Some(path) => config.apply_dotenv(path)?,
+            None => {
+                if let Some(path) = find_dotenv() {
+                    config.apply_dotenv(&path)?;
+                }
+            }
+        }
+        config.apply_environment(|variable| env::var(variable).ok())?;
+        config.apply_flags(global)?;
+
+        Ok(config)
+    }
+
+    pub fn with_defaults(config_file: Option<PathBuf>) -> Self {
+        let values = Setting::ALL
+            .into_iter()
+            .map(|setting| ResolvedValue {
+                setting,
+                value: setting.default_value(),
+                source: ValueSource::Default,
+            })
+            .collect();
+
+        Self {
+            values,
+            config_file,
+            strict: true,
+            problems: vec![],
+        }
+    }
+
+    /// Invalid values that `load_leniently` skipped
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    /// `$XDG_CONFIG_HOME/equater/config.toml`, falling back to `~/.config/equater/config.toml`
+    pub fn config_file(&self) -> Option<&Path> {
+        self.config_file.as_deref()
+    }
+
+    pub fn entries(&self) -> &[ResolvedValue] {
+        &self.values
+    }
+
+    pub fn entry(&self, setting: Setting) -> &ResolvedValue {
+        self.values
+            .iter()
+            .find(|resolved| resolved.setting == setting)
+            .expect("every setting has a value")
+    }
+
+    fn value(&self, setting: Setting) -> Option<&str> {
+        self.entry(setting).value.as_deref()
+    }
+
+    /// Overrides the setting if `value` is valid
+    pub fn set(&mut self, setting: Setting, value: String, source: ValueSource) -> Result<()> {
+        setting.validate(&value).map_err(|message| {
+            EquaterError::Configuration(format!(
+                "Invalid {} from {}: {}",
+                setting.key(),
+                source,
+                message
+            ))
+        })?;
+        let resolved = self
+            .values
+            .iter_mut()
+            .find(|resolved| resolved.setting == setting)
+            .expect("every setting has a value");
+        resolved.value = Some(value);
+        resolved.source = source;
+
+        Ok(())
+    }
+
+    /// Strict configs return the error, lenient ones keep it as a problem
+    fn tolerate(&mut self, result: Result<()>) -> Result<()> {
+        match result {
+            Err(err) if !self.strict => {
+                self.problems.push(err.to_string());
+
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn apply_config_file(&mut self, path: &Path) -> Result<()> {
+        let parsed = fs::read_to_string(path)
+            .map_err(|err| {
+                EquaterError::Configuration(format!("Could not read {}: {}", path.display(), err))
+            })
+            .and_then(|contents| parse_config_file(&contents, path));
+        let settings = match parsed {
+            Ok(settings) => settings,
+            Err(err) => return self.tolerate(Err(err)),
+        };
+
+        for (setting, value) in settings {
+            let result = self.set(setting, value, ValueSource::ConfigFile(path.to_path_buf()));
+            self.tolerate(result)?;
+        }
+
+        Ok(())
+    }
+
+    /// Only variables that belong to a setting are read, and nothing is added to the process
+    /// environment
+    fn apply_dotenv(&mut self, path: &Path) -> Result<()> {
+        let load_error = |err: dotenv::Error| {
+            EquaterError::Configuration(format!("Could not load {}: {}", path.display(), err))
+        };
+
+        // Deprecated in favor of loading into the process environment, which is what this avoids
+        #[allow(deprecated)]
+        let items = match dotenv::from_path_iter(path) {
+            Ok(items) => items,
+            Err(err) => return self.tolerate(Err(load_error(err))),
+        };
+        for item in items {
+            let (variable, value) = match item {
+                Ok(item) => item,
+                Err(err) => return self.tolerate(Err(load_error(err))),
+            };
+            if let Some(setting) = Setting::ALL
+                .into_iter()
+                .find(|setting| setting.environment_variable() == variable)
+            {
+                let result = self.set(setting, value, ValueSource::DotEnv(path.to_path_buf()));
+                self.tolerate(result)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_environment<F: Fn(&str) -> Option<String>>(&mut self, lookup: F) -> Result<()> {
+        for setting in Setting::ALL {
+            let variable = setting.environment_variable();
+            if let Some(value) = lookup(variable) {
+                let result = self.set(setting, value, ValueSource::Environment(variable));
+                self.tolerate(result)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_flags(&mut self, global: &GlobalArgs) -> Result<()> {
+        if let Some(output) = global.output {
+            self.set(
+                Setting::Output,
+                value_name(output),
+                ValueSource::Flag("--output"),
+            )?;
+        }
+        if let Some(color) = global.color {
+            self.set(
+                Setting::Color,
+                value_name(color),
+                ValueSource::Flag("--color"),
+            )?;
+        }
+        if global.yes {
+            self.set(
+                Setting::Confirm,
+                value_name(ConfirmationPolicy::Never),
+                ValueSource::Flag("--yes"),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn api_base(&self) -> Option<&str> {
+        self.value(Setting::ApiBase)
+    }
+
+    pub fn database_url(&self) -> Result<String> {
+        self.value(Setting::DatabaseUrl).map(String::from).ok_or_else(|| {
+            EquaterError::Configuration(String::from(
+                "Could not find a home directory for the local database -- set database_url with equater config set",
+            ))
+        })
+    }
+
+    /// The passphrase takes precedence over the key file
+    pub fn token_cipher(&self) -> Result<TokenCipher> {
+        if let Some(passphrase) = self.value(Setting::TokenPassphrase) {
+            return Ok(TokenCipher::from_passphrase(passphrase)?);
+        }
+
+        let key_file = self.value(Setting::TokenKeyFile).ok_or_else(|| {
+            EquaterError::Configuration(String::from(
+                "Could not find a home directory for the token key file -- set token_key_file or token_passphrase",
+            ))
+        })?;
+
+        Ok(TokenCipher::from_key_file(Path::new(key_file))?)
+    }
+
+    pub fn output(&self) -> OutputFormat {
+        self.parse_value_enum(Setting::Output)
+    }
+
+    pub fn page_size(&self) -> Option<usize> {
+        self.value(Setting::PageSize)
+            .and_then(|value| value.parse().ok())
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        self.parse_seconds(Setting::ConnectTimeout)
+            .unwrap_or(CONNECT_TIMEOUT)
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        self.parse_seconds(Setting::RequestTimeout)
+            .unwrap_or(REQUEST_TIMEOUT)
+    }
+
+    pub fn color(&self) -> ColorMode {
+        self.parse_value_enum(Setting::Color)
+    }
+
+    pub fn confirmation(&self) -> ConfirmationPolicy {
+        self.parse_value_enum(Setting::Confirm)
+    }
+
+    fn parse_seconds(&self, setting: Setting) -> Option<Duration> {
+        self.value(setting)
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+    }
+
+    fn parse_value_enum<T: ValueEnum + Default>(&self, setting: Setting) -> T {
+        self.value(setting)
+            .and_then(|value| T::from_str(value, true).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Keys that don't belong to a setting are an error so that typos don't go unnoticed
+pub fn parse_config_file(contents: &str, path: &Path) -> Result<Vec<(Setting, String)>> {
+    let invalid = |message: String| {
+        EquaterError::Configuration(format!("{} is invalid: {}", path.display(), message))
+    };
+    let table: toml::value::Table =
+        toml::from_str(contents).map_err(|err| invalid(err.to_string()))?;
+
+    table
+        .into_iter()
+        .map(|(key, value)| {
+            let setting = Setting::from_key(&key).map_err(|err| invalid(err.to_string()))?;
+            let value = match value {
+                toml::Value::String(value) => value,
+                toml::Value::Integer(value) => value.to_string(),
+                other => {
+                    return Err(invalid(format!(
+                        "{} must be a string or a number, not a {}",
+                        key,
+                        other.type_str()
+                    )))
+                }
+            };
+
+            Ok((setting, value))
+        })
+        .collect()
+}
+
+pub fn config_file_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|path| path.join("equater").join("config.toml"))
+}
+
+/// `.env` in the current directory or the closest parent that has one
+fn find_dotenv() -> Option<PathBuf> {
+    env::current_dir()
+        .ok()?
+        .ancestors()
+        .map(|directory| directory.join(".env"))
+        .find(|path| path.is_file())
+}
+
+fn validate_choice<T: ValueEnum>(setting: Setting, value: &str) -> std::result::Result<(), String> {
+    T::from_str(value, true).map(|_| ()).map_err(|_| {
+        let choices: Vec<String> = T::value_variants()
+            .iter()
+            .cloned()
+            .map(value_name)
+            .collect();
+        format!("{} must be one of {}", setting.key(), choices.join(", "))
+    })
+}
+
+/// e.g. `ndjson` for OutputFormat::Ndjson
+fn value_name<T: ValueEnum>(value: T) -> String {
+    value
+        .to_possible_value()
+        .map(|possible_value| possible_value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{parse_config_file, Config, Setting, ValueSource};
+    use crate::environment::environment_guard::ConfirmationPolicy;
+    use crate::output::OutputFormat;
+
+    fn make_config() -> Config {
+        Config::with_defaults(Some(PathBuf::from(
+            "/home/holden/.config/equater/config.toml",
+        )))
+    }
+
+    #[test]
+    fn it_should_layer_the_environment_over_the_config_file() {
+        let mut config = make_config();
+        let path = Path::new("/home/holden/.config/equater/config.toml");
+        for (setting, value) in
+            parse_config_file("output = \"json\"\npage_size = 25\n", path).unwrap()
+        {
+            config
+                .set(setting, value, ValueSource::ConfigFile(path.to_path_buf()))
+                .unwrap();
+        }
+
+        config
+            .apply_environment(|variable| {
+                (variable == "EQUATER_OUTPUT").then(|| String::from("csv"))
+            })
+            .unwrap();
+
+        assert_eq!(config.output(), OutputFormat::Csv);
+        assert_eq!(
+            config.entry(Setting::Output).source,
+            ValueSource::Environment("EQUATER_OUTPUT")
+        );
+        assert_eq!(config.page_size(), Some(25));
+        assert_eq!(
+            config.entry(Setting::PageSize).source,
+            ValueSource::ConfigFile(path.to_path_buf())
+        );
+        assert_eq!(config.entry(Setting::Confirm).source, ValueSource::Default);
+        assert_eq!(config.confirmation(), ConfirmationPolicy::Production);
+    }
+
+    #[test]
+    fn it_should_reject_unknown_keys_and_invalid_values() {
+        let path = Path::new("config.toml");
+        let mut config = make_config();
+
+        let unknown_key = parse_config_file("ouptut = \"json\"", path);
+        let wrong_type = parse_config_file("page_size = true", path);
+        let invalid_value = config.set(
+            Setting::RequestTimeout,
+            String::from("0"),
+            ValueSource::Environment("EQUATER_REQUEST_TIMEOUT"),
+        );
+
+        assert_eq!(unknown_key.unwrap_err().exit_code(), 3);
+        assert_eq!(wrong_type.unwrap_err().exit_code(), 3);
+        assert!(invalid_value
+            .unwrap_err()
+            .to_string()
+            .contains("EQUATER_REQUEST_TIMEOUT"));
+    }
+
+    #[test]
+    fn it_should_keep_invalid_values_as_problems_when_loading_leniently() {
+        let mut config = Config {
+            strict: false,
+            ..make_config()
+        };
+
+        config
+            .apply_environment(|variable| match variable {
+                "EQUATER_OUTPUT" => Some(String::from("xml")),
+                "EQUATER_PAGE_SIZE" => Some(String::from("25")),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.output(), OutputFormat::Table);
+        assert_eq!(config.entry(Setting::Output).source, ValueSource::Default);
+        assert_eq!(config.page_size(), Some(25));
+        assert_eq!(config.problems().len(), 1);
+        assert!(config.problems()[0].contains("EQUATER_OUTPUT"));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod dev_notify_api;
+#[cfg(test)]
+mod dev_notify_api_fake;
+pub mod dev_notify_command;
+mod dev_notify_ui;
@@ -0,0 +1,39 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use async_trait::async_trait;
+
+use super::dev_notify_command::{NotificationKind, SendsNotifications};
+
+pub struct DevNotifyApi {
+    client: EquaterClient,
+}
+
+impl DevNotifyApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl SendsNotifications for DevNotifyApi {
+    async fn send_notification(&self, kind: NotificationKind, id: u32) -> Result<()> {
+        self.client
+            .post(&notification_path(kind, id))
+            .send_empty()
+            .await
+    }
+}
+
+/// Routes of the API's ExpenseApiDevController, which only exists on non-production servers
+fn notification_path(kind: NotificationKind, id: u32) -> String {
+    let route = match kind {
+        NotificationKind::AgreementOwner => format!("agreement/{}/owner", id),
+        NotificationKind::AgreementPayee => format!("agreement/{}/payee", id),
+        NotificationKind::AgreementCreated => format!("agreement-created/{}/payee", id),
+        NotificationKind::TransactionRecipient => format!("transaction/{}/recipient", id),
+        NotificationKind::TransactionPayee => format!("transaction/{}/payee", id),
+    };
+
+    format!("/api/dev/expense/notifications/{}", route)
+}
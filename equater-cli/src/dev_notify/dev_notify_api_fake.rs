@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+
+use super::dev_notify_command::{NotificationKind, SendsNotifications};
+
+/// Sending a notification for id 13 fails, the way a payee without a device token does
+pub struct DevNotifyApiFake {
+    pub should_error: bool,
+}
+
+#[async_trait]
+impl SendsNotifications for DevNotifyApiFake {
+    async fn send_notification(&self, _kind: NotificationKind, id: u32) -> Result<()> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        if id == 13 {
+            return make_error_response(
+                500,
+                r#"{"statusCode": 500, "message": "Internal server error"}"#,
+            )
+            .await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+use async_trait::async_trait;
+use clap::ValueEnum;
+use http::StatusCode;
+use serde::Serialize;
+
+use crate::commands::NotifyArgs;
+use crate::environment::environment_guard::ApiEnvironment;
+use crate::error::{EquaterError, Result};
+use crate::inspect_expenses::inspect_expenses_command::InspectsExpenses;
+use crate::output::{Columns, OutputOptions};
+use crate::user::User;
+
+use super::dev_notify_ui::DevNotifyUi;
+
+/// The push notifications the API's ExpenseApiDevController can send again
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationKind {
+    /// Tell the expense owner where an agreement stands
+    AgreementOwner,
+    /// Tell the payee where their agreement stands
+    AgreementPayee,
+    /// Invite the payee as if the agreement had just been created
+    AgreementCreated,
+    /// Tell the expense owner about a transaction they're receiving
+    TransactionRecipient,
+    /// Tell the payee about a transaction they're paying
+    TransactionPayee,
+}
+
+impl NotificationKind {
+    /// What the id passed to the notification refers to
+    pub fn target(&self) -> &'static str {
+        match self {
+            NotificationKind::AgreementOwner
+            | NotificationKind::AgreementPayee
+            | NotificationKind::AgreementCreated => "agreement",
+            NotificationKind::TransactionRecipient | NotificationKind::TransactionPayee => {
+                "transaction"
+            }
+        }
+    }
+}
+
+#[async_trait]
+pub trait SendsNotifications {
+    async fn send_notification(&self, kind: NotificationKind, id: u32) -> Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationOutcome {
+    Sent,
+    Failed,
+}
+
+/// One notification that `dev notify` sent, or tried to
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SentNotification {
+    pub kind: NotificationKind,
+    pub id: u32,
+    pub recipient_user_id: u32,
+    /// Null when the recipient hasn't accepted the agreement yet
+    pub recipient_email: Option<String>,
+    pub result: NotificationOutcome,
+    pub error: Option<String>,
+}
+
+impl Columns for SentNotification {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "kind",
+            "id",
+            "recipient_user_id",
+            "recipient_email",
+            "result",
+            "error",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "kind",
+            "id",
+            "recipient_user_id",
+            "recipient_email",
+            "result",
+            "error",
+        ]
+    }
+}
+
+/// Who a notification goes to
+struct Recipient {
+    user_id: u32,
+    email: Option<String>,
+}
+
+impl From<&User> for Recipient {
+    fn from(user: &User) -> Self {
+        Self {
+            user_id: user.id,
+            email: Some(user.email.clone()),
+        }
+    }
+}
+
+/// Sends push notifications again so that the mobile apps can be tested against them. Like
+/// simulations, the API only sends these from development and staging servers, so production is
+/// refused before anything is sent.
+pub struct DevNotifyCommand<S: SendsNotifications, I: InspectsExpenses> {
+    remote_repository: S,
+    expense_repository: I,
+    /// The server `remote_repository` points at
+    environment: ApiEnvironment,
+}
+
+impl<S: SendsNotifications, I: InspectsExpenses> DevNotifyCommand<S, I> {
+    pub fn new(remote_repository: S, expense_repository: I, environment: ApiEnvironment) -> Self {
+        Self {
+            remote_repository,
+            expense_repository,
+            environment,
+        }
+    }
+
+    /// Every agreement or transaction is looked up before the first notification goes out, so a
+    /// typo in a batch doesn't leave it half sent. After that a failed notification doesn't stop
+    /// the rest.
+    pub async fn notify(&self, args: &NotifyArgs, output: &OutputOptions) -> Result<String> {
+        if !self.environment.allows_simulation() {
+            return Err(EquaterError::Configuration(format!(
+                "Notifications are only sent from development and staging servers, and this server is {}",
+                self.environment.server_name()
+            )));
+        }
+
+        let mut ids: Vec<u32> = vec![];
+        for id in &args.ids {
+            if !ids.contains(id) {
+                ids.push(*id);
+            }
+        }
+
+        let mut recipients = vec![];
+        let mut missing = vec![];
+        for id in ids {
+            match self.find_recipient(args.kind, id).await {
+                Ok(recipient) => recipients.push((id, recipient)),
+                Err(err) if err.status() == Some(StatusCode::NOT_FOUND) => missing.push(id),
+                Err(err) => return Err(err),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(EquaterError::Validation(describe_missing(
+                args.kind, &missing,
+            )));
+        }
+
+        let mut sent = vec![];
+        for (id, recipient) in recipients {
+            let result = self
+                .remote_repository
+                .send_notification(args.kind, id)
+                .await;
+            sent.push(SentNotification {
+                kind: args.kind,
+                id,
+                recipient_user_id: recipient.user_id,
+                recipient_email: recipient.email,
+                result: match result {
+                    Ok(()) => NotificationOutcome::Sent,
+                    Err(_) => NotificationOutcome::Failed,
+                },
+                error: result.err().map(|err| err.to_string()),
+            });
+        }
+        DevNotifyUi::new(output).render(&sent)?;
+
+        Ok(summarize(args.kind, &sent))
+    }
+
+    async fn find_recipient(&self, kind: NotificationKind, id: u32) -> Result<Recipient> {
+        match kind {
+            NotificationKind::AgreementOwner
+            | NotificationKind::AgreementPayee
+            | NotificationKind::AgreementCreated => {
+                let story = self.expense_repository.fetch_agreement(id).await?;
+                let user_id = match kind {
+                    NotificationKind::AgreementOwner => {
+                        story.story.shared_expense.expense_owner_user_id
+                    }
+                    _ => story.user_agreement.user_id,
+                };
+
+                Ok(story
+                    .story
+                    .find_user(user_id)
+                    .map(Recipient::from)
+                    .unwrap_or(Recipient {
+                        user_id,
+                        email: None,
+                    }))
+            }
+            NotificationKind::TransactionRecipient => {
+                let story = self.expense_repository.fetch_transaction(id).await?;
+
+                Ok(Recipient::from(&story.recipient))
+            }
+            NotificationKind::TransactionPayee => {
+                let story = self.expense_repository.fetch_transaction(id).await?;
+
+                Ok(Recipient::from(&story.payer))
+            }
+        }
+    }
+}
+
+fn describe_missing(kind: NotificationKind, missing: &[u32]) -> String {
+    let ids: Vec<String> = missing.iter().map(u32::to_string).collect();
+    let target = match missing.len() {
+        1 => format!("{} {} doesn't", kind.target(), ids[0]),
+        _ => format!("{}s {} don't", kind.target(), ids.join(", ")),
+    };
+
+    format!("The {} exist -- nothing was sent", target)
+}
+
+fn summarize(kind: NotificationKind, sent: &[SentNotification]) -> String {
+    let failed: Vec<String> = sent
+        .iter()
+        .filter(|notification| notification.result == NotificationOutcome::Failed)
+        .map(|notification| notification.id.to_string())
+        .collect();
+
+    if failed.is_empty() {
+        return format!("Sent {} notification(s)", sent.len());
+    }
+
+    format!(
+        "Sent {} of {} notification(s). Retry the failures with: equater dev notify {} {}",
+        sent.len() - failed.len(),
+        sent.len(),
+        kind.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default(),
+        failed.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DevNotifyCommand, NotificationKind};
+    use crate::commands::NotifyArgs;
+    use crate::dev_notify::dev_notify_api_fake::DevNotifyApiFake;
+    use crate::environment::environment_guard::ApiEnvironment;
+    use crate::inspect_expenses::inspect_expenses_api_fake::InspectExpensesApiFake;
+    use crate::output::{OutputFormat, OutputOptions};
+
+    fn make_command(
+        server_environment: &str,
+    ) -> DevNotifyCommand<DevNotifyApiFake, InspectExpensesApiFake> {
+        DevNotifyCommand::new(
+            DevNotifyApiFake {
+                should_error: false,
+            },
+            InspectExpensesApiFake {
+                should_error: false,
+            },
+            ApiEnvironment {
+                plaid_environment: None,
+                server_environment: Some(String::from(server_environment)),
+            },
+        )
+    }
+
+    fn make_args(kind: NotificationKind, ids: &[u32]) -> NotifyArgs {
+        NotifyArgs {
+            kind,
+            ids: ids.to_vec(),
+        }
+    }
+
+    fn make_output() -> OutputOptions {
+        OutputOptions {
+            format: OutputFormat::Json,
+            columns: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_refuse_to_notify_from_production() {
+        let command = make_command("production");
+
+        let result = command
+            .notify(
+                &make_args(NotificationKind::AgreementOwner, &[1000]),
+                &make_output(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 3);
+    }
+
+    #[tokio::test]
+    async fn it_should_send_nothing_when_a_target_does_not_exist() {
+        let command = make_command("development");
+
+        let result = command
+            .notify(
+                &make_args(NotificationKind::TransactionPayee, &[500, 9001, 9002]),
+                &make_output(),
+            )
+            .await;
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "The transactions 9001, 9002 don't exist -- nothing was sent"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_report_each_notification_in_a_batch() {
+        let command = make_command("staging");
+        let args = make_args(NotificationKind::TransactionRecipient, &[12, 13, 12]);
+
+        let message = command.notify(&args, &make_output()).await.unwrap();
+        let recipient = command
+            .find_recipient(NotificationKind::AgreementPayee, 1000)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            message,
+            "Sent 1 of 2 notification(s). Retry the failures with: equater dev notify transaction-recipient 13"
+        );
+        assert_eq!(recipient.user_id, 2);
+    }
+}
@@ -0,0 +1,18 @@
+use crate::error::Result;
+use crate::output::OutputOptions;
+
+use super::dev_notify_command::SentNotification;
+
+pub struct DevNotifyUi<'a> {
+    output: &'a OutputOptions,
+}
+
+impl<'a> DevNotifyUi<'a> {
+    pub fn new(output: &'a OutputOptions) -> Self {
+        Self { output }
+    }
+
+    pub fn render(&self, sent: &[SentNotification]) -> Result<()> {
+        self.output.render(sent)
+    }
+}
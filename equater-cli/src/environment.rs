@@ -0,0 +1,5 @@
+pub mod environment_api;
+#[cfg(test)]
+pub mod environment_api_fake;
+pub mod environment_guard;
+mod environment_ui;
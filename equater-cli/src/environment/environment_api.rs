@@ -0,0 +1,26 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use async_trait::async_trait;
+
+use super::environment_guard::{ApiEnvironment, FetchesEnvironment};
+
+pub struct EnvironmentApi {
+    client: EquaterClient,
+}
+
+impl EnvironmentApi {
+    /// The environment endpoint doesn't require authentication
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl FetchesEnvironment for EnvironmentApi {
+    async fn fetch_environment(&self) -> Result<ApiEnvironment> {
+        self.client
+            .get("/api/environment")
+            .send_json::<ApiEnvironment>()
+            .await
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+
+use super::environment_guard::{ApiEnvironment, FetchesEnvironment};
+
+pub struct EnvironmentApiFake {
+    pub should_error: bool,
+    /// What GET /api/environment reports as the server environment
+    pub server_environment: &'static str,
+    /// Number of times the environment was requested
+    pub requests: AtomicUsize,
+}
+
+impl EnvironmentApiFake {
+    pub fn new(server_environment: &'static str) -> Self {
+        Self {
+            should_error: false,
+            server_environment,
+            requests: AtomicUsize::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl FetchesEnvironment for EnvironmentApiFake {
+    async fn fetch_environment(&self) -> Result<ApiEnvironment> {
+        self.requests.fetch_add(1, Ordering::SeqCst);
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        Ok(ApiEnvironment {
+            plaid_environment: Some(String::from("sandbox")),
+            server_environment: Some(String::from(self.server_environment)),
+        })
+    }
+}
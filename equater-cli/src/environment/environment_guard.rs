@@ -0,0 +1,235 @@
+use std::io::{self, IsTerminal};
+
+use async_trait::async_trait;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use tokio::sync::OnceCell;
+
+use crate::error::{EquaterError, Result};
+
+use super::environment_ui::EnvironmentUi;
+
+#[async_trait]
+pub trait FetchesEnvironment {
+    async fn fetch_environment(&self) -> Result<ApiEnvironment>;
+}
+
+/// Response of GET /api/environment. Values come straight from the API's configuration, so
+/// they're compared case-insensitively.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiEnvironment {
+    /// e.g. sandbox, development or production
+    pub plaid_environment: Option<String>,
+    /// e.g. development, staging or production
+    pub server_environment: Option<String>,
+}
+
+impl ApiEnvironment {
+    /// Mirrors the API's EnvironmentGuard, which only lets development and staging servers run
+    /// dev tools like transaction simulation
+    pub fn allows_simulation(&self) -> bool {
+        matches!(self.server_name().as_str(), "development" | "staging")
+    }
+
+    /// Anything other than development or staging is treated like production, including servers
+    /// that don't report an environment
+    pub fn is_production(&self) -> bool {
+        !self.allows_simulation()
+    }
+
+    /// The server environment for messages, e.g. "staging" or "unknown"
+    pub fn server_name(&self) -> String {
+        self.server_environment
+            .as_deref()
+            .map(str::to_lowercase)
+            .unwrap_or_else(|| String::from("unknown"))
+    }
+}
+
+/// Which commands that change data have to be confirmed. Set with `confirm` in the config, and
+/// `--yes` always means never.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ConfirmationPolicy {
+    /// Type the environment's name before changing data on production
+    #[default]
+    Production,
+    /// Also confirm changes to development and staging servers
+    Always,
+    /// Never ask
+    Never,
+}
+
+/// Knows which server the active profile points at. The environment is requested at most once
+/// per run, and commands that change data are confirmed according to the `ConfirmationPolicy` --
+/// by default only on production, by typing the environment's name.
+pub struct EnvironmentGuard<T: FetchesEnvironment> {
+    remote_repository: T,
+    environment: OnceCell<ApiEnvironment>,
+    policy: ConfirmationPolicy,
+    interactive: bool,
+}
+
+impl<T: FetchesEnvironment> EnvironmentGuard<T> {
+    pub fn new(remote_repository: T, policy: ConfirmationPolicy) -> Self {
+        Self {
+            remote_repository,
+            environment: OnceCell::new(),
+            policy,
+            interactive: io::stdin().is_terminal() && io::stderr().is_terminal(),
+        }
+    }
+
+    pub async fn environment(&self) -> Result<&ApiEnvironment> {
+        self.environment
+            .get_or_try_init(|| self.remote_repository.fetch_environment())
+            .await
+    }
+
+    /// The environment if it's already been requested, without sending a request
+    pub fn cached_environment(&self) -> Option<&ApiEnvironment> {
+        self.environment.get()
+    }
+
+    /// Printed to stderr so that it never ends up in piped output. A server that can't be
+    /// reached isn't an error here -- the command itself will report it.
+    pub async fn show_banner(&self, api_base: &str) {
+        if !self.interactive {
+            return;
+        }
+
+        match self.environment().await {
+            Ok(environment) => {
+                if let Err(err) = EnvironmentUi::new(environment).render_banner(api_base) {
+                    verbose!("Could not print the environment banner: {}", err);
+                }
+            }
+            Err(err) => verbose!("Could not determine the server environment: {}", err),
+        }
+    }
+
+    /// Returns false when the operator didn't confirm. Production always asks for the
+    /// environment's name to be typed. `action` describes the change, e.g. "merge vendor 56 into
+    /// vendor 12".
+    pub async fn confirm_mutation(&self, action: &str) -> Result<bool> {
+        let environment = self.environment().await?;
+        let needs_confirmation = match self.policy {
+            ConfirmationPolicy::Production => environment.is_production(),
+            ConfirmationPolicy::Always => true,
+            ConfirmationPolicy::Never => false,
+        };
+        if !needs_confirmation {
+            return Ok(true);
+        }
+
+        if !self.interactive {
+            return Err(EquaterError::Validation(format!(
+                "Refusing to {} on the {} server without confirmation -- pass --yes to run it non-interactively",
+                action,
+                environment.server_name()
+            )));
+        }
+
+        let ui = EnvironmentUi::new(environment);
+        if environment.is_production() {
+            Ok(ui.prompt_environment_name(action)?)
+        } else {
+            Ok(ui.confirm_action(action)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use tokio::sync::OnceCell;
+
+    use super::{ApiEnvironment, ConfirmationPolicy, EnvironmentGuard};
+    use crate::environment::environment_api_fake::EnvironmentApiFake;
+
+    fn make_environment(server_environment: Option<&str>) -> ApiEnvironment {
+        ApiEnvironment {
+            plaid_environment: Some(String::from("sandbox")),
+            server_environment: server_environment.map(String::from),
+        }
+    }
+
+    fn make_guard(
+        server_environment: &'static str,
+        policy: ConfirmationPolicy,
+    ) -> EnvironmentGuard<EnvironmentApiFake> {
+        EnvironmentGuard {
+            remote_repository: EnvironmentApiFake::new(server_environment),
+            environment: OnceCell::new(),
+            policy,
+            interactive: false,
+        }
+    }
+
+    #[test]
+    fn it_should_only_allow_simulation_on_development_and_staging() {
+        assert!(make_environment(Some("staging")).allows_simulation());
+        assert!(make_environment(Some("DEVELOPMENT")).allows_simulation());
+        assert!(!make_environment(Some("production")).allows_simulation());
+        assert!(!make_environment(None).allows_simulation());
+    }
+
+    #[test]
+    fn it_should_treat_unknown_servers_as_production() {
+        assert!(make_environment(Some("PRODUCTION")).is_production());
+        assert!(make_environment(None).is_production());
+        assert!(!make_environment(Some("staging")).is_production());
+    }
+
+    #[tokio::test]
+    async fn it_should_only_request_the_environment_once() {
+        let guard = make_guard("staging", ConfirmationPolicy::Production);
+
+        guard.environment().await.unwrap();
+        guard
+            .confirm_mutation("delete association 5")
+            .await
+            .unwrap();
+
+        assert_eq!(guard.remote_repository.requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_ask_for_confirmation_outside_of_production() {
+        let guard = make_guard("development", ConfirmationPolicy::Production);
+
+        assert!(guard
+            .confirm_mutation("delete association 5")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn it_should_refuse_to_change_production_without_a_terminal() {
+        let guard = make_guard("production", ConfirmationPolicy::Production);
+
+        let result = guard.confirm_mutation("delete association 5").await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_confirm_every_server_when_the_policy_is_always() {
+        let guard = make_guard("staging", ConfirmationPolicy::Always);
+
+        let result = guard.confirm_mutation("delete association 5").await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_skip_the_confirmation_when_passed_yes() {
+        let guard = make_guard("production", ConfirmationPolicy::Never);
+
+        assert!(guard
+            .confirm_mutation("delete association 5")
+            .await
+            .unwrap());
+    }
+}
@@ -0,0 +1,68 @@
+use std::io::{self, IsTerminal};
+
+use cli_table::{print_stderr, Cell, Color, Style, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input};
+
+use super::environment_guard::ApiEnvironment;
+use crate::output::color_choice;
+
+pub struct EnvironmentUi<'a> {
+    environment: &'a ApiEnvironment,
+}
+
+impl<'a> EnvironmentUi<'a> {
+    pub fn new(environment: &'a ApiEnvironment) -> Self {
+        Self { environment }
+    }
+
+    /// e.g. PRODUCTION | https://www.equater.io | Plaid production, in red
+    pub fn render_banner(&self, api_base: &str) -> io::Result<()> {
+        let server_name = self.environment.server_name();
+        let color = match server_name.as_str() {
+            "development" => Color::Green,
+            "staging" => Color::Yellow,
+            _ => Color::Red,
+        };
+        let color_choice = color_choice(io::stderr().is_terminal());
+        let banner = format!(
+            "{} | {} | Plaid {}",
+            server_name.to_uppercase(),
+            api_base,
+            self.environment
+                .plaid_environment
+                .as_deref()
+                .unwrap_or("unknown")
+        );
+        let table = vec![vec![banner.cell().foreground_color(Some(color)).bold(true)]]
+            .table()
+            .color_choice(color_choice);
+
+        print_stderr(table)
+    }
+
+    /// The operator has to type the environment's name exactly, e.g. production
+    pub fn prompt_environment_name(&self, action: &str) -> io::Result<bool> {
+        let server_name = self.environment.server_name();
+        let typed: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "You're about to {} on the {} server. Type {} to continue",
+                action, server_name, server_name
+            ))
+            .allow_empty(true)
+            .interact_text()?;
+
+        Ok(typed.trim() == server_name)
+    }
+
+    /// Used for servers other than production when every change has to be confirmed
+    pub fn confirm_action(&self, action: &str) -> io::Result<bool> {
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "You're about to {} on the {} server. Continue?",
+                action,
+                self.environment.server_name()
+            ))
+            .default(false)
+            .interact()
+    }
+}
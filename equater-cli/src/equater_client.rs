@@ -0,0 +1,296 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use http::Method;
+#[cfg(test)]
+use reqwest::Request;
+use reqwest::{Client, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::audit_trail::{self, AuditedRequest};
+use crate::cassette::Cassette;
+use crate::error::{EquaterError, Result};
+
+/// Defaults for the connect_timeout and request_timeout settings
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shared HTTP client for every remote repository. Cloning is cheap and clones share the same
+/// connection pool, so a single instance should be created per process.
+#[derive(Clone)]
+pub struct EquaterClient {
+    client: Client,
+    api_base: String,
+    auth_token: Option<String>,
+    transport: Transport,
+}
+
+/// Where requests sent through `SendJson` go. Requests to pre-signed urls always go to the
+/// network.
+#[derive(Clone, Default)]
+pub enum Transport {
+    #[default]
+    Network,
+    /// Sent to the server, and saved to the cassette along with the response
+    Record(Arc<Cassette>),
+    /// Answered from the cassette without touching the network
+    Replay(Arc<Cassette>),
+}
+
+impl EquaterClient {
+    /// A client with the default timeouts. The CLI reads its timeouts from the config instead.
+    #[cfg(test)]
+    pub fn new(api_base: &str) -> reqwest::Result<Self> {
+        Self::with_timeouts(api_base, CONNECT_TIMEOUT, REQUEST_TIMEOUT)
+    }
+
+    pub fn with_timeouts(
+        api_base: &str,
+        connect_timeout: Duration,
+        request_timeout: Duration,
+    ) -> reqwest::Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!("equater-cli/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(connect_timeout)
+            .timeout(request_timeout)
+            .gzip(true)
+            .build()?;
+
+        Ok(Self {
+            client,
+            api_base: String::from(api_base.trim_end_matches('/')),
+            auth_token: None,
+            transport: Transport::Network,
+        })
+    }
+
+    pub fn with_transport(self, transport: Transport) -> Self {
+        Self { transport, ..self }
+    }
+
+    pub fn transport(&self) -> &Transport {
+        &self.transport
+    }
+
+    /// A client that sends `Authorization: Bearer <auth_token>` with every request
+    pub fn with_auth_token(&self, auth_token: &str) -> Self {
+        Self {
+            client: self.client.clone(),
+            api_base: self.api_base.clone(),
+            auth_token: Some(String::from(auth_token)),
+            transport: self.transport.clone(),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> ApiRequest {
+        self.request(Method::GET, path)
+    }
+
+    pub fn post(&self, path: &str) -> ApiRequest {
+        self.request(Method::POST, path)
+    }
+
+    pub fn put(&self, path: &str) -> ApiRequest {
+        self.request(Method::PUT, path)
+    }
+
+    pub fn patch(&self, path: &str) -> ApiRequest {
+        self.request(Method::PATCH, path)
+    }
+
+    pub fn delete(&self, path: &str) -> ApiRequest {
+        self.request(Method::DELETE, path)
+    }
+
+    /// PUT to a pre-signed S3 url. The url carries its own signature, so neither the api base nor
+    /// the auth token are added.
+    pub fn put_pre_signed(&self, pre_signed_url: &str) -> RequestBuilder {
+        self.client.put(pre_signed_url)
+    }
+
+    /// `path` is relative to the api base, e.g. `/api/user`. Query parameters should be added with
+    /// `ApiRequest::query` so that they're url encoded.
+    pub fn request(&self, method: Method, path: &str) -> ApiRequest {
+        let url = format!("{}{}", self.api_base, path);
+        let builder = self.client.request(method, url);
+        let builder = match &self.auth_token {
+            Some(auth_token) => builder.bearer_auth(auth_token),
+            None => builder,
+        };
+
+        ApiRequest {
+            builder,
+            transport: self.transport.clone(),
+        }
+    }
+}
+
+/// A request to the API, built like a `RequestBuilder` and sent through the client's `Transport`
+pub struct ApiRequest {
+    builder: RequestBuilder,
+    transport: Transport,
+}
+
+impl ApiRequest {
+    pub fn query<T: Serialize + ?Sized>(self, query: &T) -> Self {
+        Self {
+            builder: self.builder.query(query),
+            ..self
+        }
+    }
+
+    pub fn json<T: Serialize + ?Sized>(self, json: &T) -> Self {
+        Self {
+            builder: self.builder.json(json),
+            ..self
+        }
+    }
+
+    #[cfg(test)]
+    pub fn build(self) -> reqwest::Result<Request> {
+        self.builder.build()
+    }
+
+    /// Notes the request in the audit trail along with the response status, unless it's replayed
+    /// from a cassette. Requests with a streamed body can't be copied, so they aren't noted or
+    /// recorded -- none of the API's endpoints take one.
+    async fn send(self) -> Result<Response> {
+        let request = self.builder.try_clone().and_then(|copy| copy.build().ok());
+        if let Transport::Replay(cassette) = &self.transport {
+            return match &request {
+                Some(request) => cassette.replay(request),
+                None => Err(EquaterError::Configuration(String::from(
+                    "Requests with a streamed body can't be replayed",
+                ))),
+            };
+        }
+
+        let audited = request.as_ref().map(AuditedRequest::new);
+        let result = self.builder.send().await;
+        if let Some(mut audited) = audited {
+            audited.response_status = result
+                .as_ref()
+                .ok()
+                .map(|response| response.status().as_u16());
+            audit_trail::record(audited);
+        }
+
+        match (&self.transport, request) {
+            (Transport::Record(cassette), Some(request)) => {
+                cassette.record(&request, result?).await
+            }
+            _ => Ok(result?),
+        }
+    }
+}
+
+/// Sends the request and decodes a successful response body, or turns an unsuccessful one into a
+/// typed `EquaterError`
+#[async_trait]
+pub trait SendJson {
+    async fn send_json<T: DeserializeOwned>(self) -> Result<T>;
+    /// For endpoints that respond with an empty body
+    async fn send_empty(self) -> Result<()>;
+}
+
+#[async_trait]
+impl SendJson for ApiRequest {
+    async fn send_json<T: DeserializeOwned>(self) -> Result<T> {
+        let response = self.send().await?;
+
+        read_json(response).await
+    }
+
+    async fn send_empty(self) -> Result<()> {
+        let response = self.send().await?;
+
+        read_empty(response).await
+    }
+}
+
+pub async fn read_json<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let status = response.status();
+    let body = response.text().await?;
+
+    if !status.is_success() {
+        return Err(EquaterError::from_response_body(status, &body));
+    }
+
+    serde_json::from_str::<T>(&body).map_err(|err| EquaterError::Decode(err.to_string()))
+}
+
+pub async fn read_empty(response: Response) -> Result<()> {
+    let status = response.status();
+
+    if !status.is_success() {
+        let body = response.text().await?;
+        return Err(EquaterError::from_response_body(status, &body));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EquaterClient;
+    use http::header::AUTHORIZATION;
+
+    #[test]
+    fn it_should_join_the_api_base_and_path() {
+        let client = EquaterClient::new("http://localhost:7111/").unwrap();
+        let request = client.get("/api/user").build().unwrap();
+
+        assert_eq!(request.url().as_str(), "http://localhost:7111/api/user");
+        assert!(request.headers().get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn it_should_send_the_auth_token_as_a_bearer_token() {
+        let client = EquaterClient::new("http://localhost:7111")
+            .unwrap()
+            .with_auth_token("token");
+        let request = client.get("/api/user").build().unwrap();
+
+        assert_eq!(
+            request.headers().get(AUTHORIZATION).unwrap(),
+            "Bearer token"
+        );
+    }
+
+    #[test]
+    fn it_should_not_send_the_auth_token_to_pre_signed_urls() {
+        let client = EquaterClient::new("http://localhost:7111")
+            .unwrap()
+            .with_auth_token("token");
+        let request = client
+            .put_pre_signed("https://bucket.s3.amazonaws.com/logo?X-Amz-Signature=abc")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().as_str(),
+            "https://bucket.s3.amazonaws.com/logo?X-Amz-Signature=abc"
+        );
+        assert!(request.headers().get(AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn it_should_url_encode_query_parameters() {
+        let client = EquaterClient::new("http://localhost:7111").unwrap();
+        let request = client
+            .get("/api/vendor/search")
+            .query(&[
+                ("searchTerm", "AT&T wireless"),
+                ("requiringInternalReview", "false"),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().query(),
+            Some("searchTerm=AT%26T+wireless&requiringInternalReview=false")
+        );
+    }
+}
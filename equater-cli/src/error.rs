@@ -0,0 +1,242 @@
+use std::fmt::{Display, Formatter};
+
+use database::connection::ConnectionError;
+use database::token_cipher::TokenCipherError;
+use http::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
+
+pub type Result<T> = std::result::Result<T, EquaterError>;
+
+/// Every failure the CLI can surface to the operator. Each variant maps to its own process exit
+/// code so that scripts can tell a bad token apart from a server that's down.
+#[derive(Debug)]
+pub enum EquaterError {
+    /// The request never received a response (DNS, TLS, refused connections, timeouts)
+    Transport(reqwest::Error),
+    /// The server responded with an error body, e.g. `{"error": "Invalid username or password"}`
+    Server {
+        status: StatusCode,
+        message: String,
+    },
+    /// The server responded with a non-success status and a body we couldn't make sense of
+    Http {
+        status: StatusCode,
+        body: String,
+    },
+    /// The server responded successfully but the body didn't match the expected model
+    Decode(String),
+    /// There's no cached session for the current profile
+    NotSignedIn,
+    /// The session expired after part of the output was written or a change was sent, so
+    /// signing in again and retrying would repeat it
+    SessionExpired(String),
+    LocalDatabase(String),
+    Configuration(String),
+    /// Input supplied by the operator failed validation
+    Validation(String),
+    /// Results couldn't be written to the terminal
+    Io(std::io::Error),
+}
+
+/// Nest responds with `{"statusCode": 400, "message": "...", "error": "Bad Request"}` where message
+/// can also be a list of validation messages. Some controllers respond with only `{"error": "..."}`.
+#[derive(Deserialize)]
+struct ServerErrorBody {
+    message: Option<Value>,
+    error: Option<String>,
+}
+
+impl EquaterError {
+    /// Build an error from a non-success response body
+    pub fn from_response_body(status: StatusCode, body: &str) -> Self {
+        let message = serde_json::from_str::<ServerErrorBody>(body)
+            .ok()
+            .and_then(|error_body| {
+                let message = match error_body.message {
+                    Some(Value::String(message)) => Some(message),
+                    Some(Value::Array(messages)) => Some(
+                        messages
+                            .iter()
+                            .filter_map(Value::as_str)
+                            .collect::<Vec<&str>>()
+                            .join(", "),
+                    ),
+                    _ => None,
+                };
+
+                message.or(error_body.error)
+            })
+            .filter(|message| !message.is_empty());
+
+        match message {
+            Some(message) => EquaterError::Server { status, message },
+            None => EquaterError::Http {
+                status,
+                body: String::from(body),
+            },
+        }
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            EquaterError::Server { status, .. } => Some(*status),
+            EquaterError::Http { status, .. } => Some(*status),
+            EquaterError::Transport(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// True when signing in again could resolve the error
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, EquaterError::NotSignedIn) || self.status() == Some(StatusCode::UNAUTHORIZED)
+    }
+
+    /// 2 is reserved for usage errors, which is also what clap exits with
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            EquaterError::Validation(_) => 2,
+            EquaterError::Configuration(_) => 3,
+            EquaterError::LocalDatabase(_) => 4,
+            EquaterError::Transport(_) => 5,
+            EquaterError::NotSignedIn | EquaterError::SessionExpired(_) => 6,
+            EquaterError::Server { status, .. } | EquaterError::Http { status, .. }
+                if *status == StatusCode::UNAUTHORIZED || *status == StatusCode::FORBIDDEN =>
+            {
+                6
+            }
+            EquaterError::Server { .. } | EquaterError::Http { .. } => 7,
+            EquaterError::Decode(_) => 8,
+            EquaterError::Io(_) => 9,
+        }
+    }
+}
+
+impl Display for EquaterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EquaterError::Transport(err) => {
+                let url = err
+                    .url()
+                    .map(|url| url.to_string())
+                    .unwrap_or_else(|| String::from("the server"));
+
+                if err.is_timeout() {
+                    write!(f, "The request to {} timed out", url)
+                } else if err.is_connect() {
+                    write!(f, "Could not connect to {}", url)
+                } else {
+                    write!(f, "The request to {} failed: {}", url, err)
+                }
+            }
+            EquaterError::Server { status, message } => write!(f, "{} ({})", message, status),
+            EquaterError::Http { status, body } if body.trim().is_empty() => {
+                write!(f, "The server responded with {}", status)
+            }
+            EquaterError::Http { status, body } => {
+                write!(f, "The server responded with {}: {}", status, body.trim())
+            }
+            EquaterError::Decode(message) => {
+                write!(f, "Unexpected response from the server: {}", message)
+            }
+            EquaterError::NotSignedIn => {
+                write!(f, "You're not signed in -- sign in with equater login")
+            }
+            EquaterError::SessionExpired(message) => write!(f, "{}", message),
+            EquaterError::LocalDatabase(message) => write!(f, "Local database error: {}", message),
+            EquaterError::Configuration(message) => write!(f, "{}", message),
+            EquaterError::Validation(message) => write!(f, "{}", message),
+            EquaterError::Io(err) => write!(f, "Error writing output: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for EquaterError {}
+
+impl From<reqwest::Error> for EquaterError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_decode() {
+            EquaterError::Decode(err.to_string())
+        } else {
+            EquaterError::Transport(err)
+        }
+    }
+}
+
+impl From<diesel::result::Error> for EquaterError {
+    fn from(err: diesel::result::Error) -> Self {
+        EquaterError::LocalDatabase(err.to_string())
+    }
+}
+
+impl From<ConnectionError> for EquaterError {
+    fn from(err: ConnectionError) -> Self {
+        EquaterError::LocalDatabase(err.to_string())
+    }
+}
+
+impl From<diesel::r2d2::PoolError> for EquaterError {
+    fn from(err: diesel::r2d2::PoolError) -> Self {
+        EquaterError::LocalDatabase(err.to_string())
+    }
+}
+
+/// The auth token key couldn't be loaded, e.g. a key file other users can read
+impl From<TokenCipherError> for EquaterError {
+    fn from(err: TokenCipherError) -> Self {
+        EquaterError::Configuration(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for EquaterError {
+    fn from(err: std::io::Error) -> Self {
+        EquaterError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EquaterError;
+    use http::StatusCode;
+
+    #[test]
+    fn it_should_parse_an_error_body() {
+        let error = EquaterError::from_response_body(
+            StatusCode::UNAUTHORIZED,
+            r#"{"error": "Invalid username or password"}"#,
+        );
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid username or password (401 Unauthorized)"
+        );
+        assert!(error.is_unauthorized());
+        assert_eq!(error.exit_code(), 6);
+    }
+
+    #[test]
+    fn it_should_prefer_nest_validation_messages() {
+        let error = EquaterError::from_response_body(
+            StatusCode::BAD_REQUEST,
+            r#"{"statusCode": 400, "message": ["name is required", "ppdId must be a string"], "error": "Bad Request"}"#,
+        );
+
+        assert_eq!(
+            error.to_string(),
+            "name is required, ppdId must be a string (400 Bad Request)"
+        );
+        assert!(!error.is_unauthorized());
+        assert_eq!(error.exit_code(), 7);
+    }
+
+    #[test]
+    fn it_should_fall_back_to_the_raw_body() {
+        let error = EquaterError::from_response_body(StatusCode::BAD_GATEWAY, "<html>502</html>");
+
+        assert!(matches!(error, EquaterError::Http { .. }));
+        assert_eq!(
+            error.to_string(),
+            "The server responded with 502 Bad Gateway: <html>502</html>"
+        );
+    }
+}
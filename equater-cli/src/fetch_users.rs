@@ -1,6 +1,6 @@
 pub mod fetch_user_api;
 #[cfg(test)]
-mod fetch_user_api_fake;
+pub mod fetch_user_api_fake;
 pub mod fetch_users_command;
 pub mod fetch_users_response;
 mod fetch_users_ui;
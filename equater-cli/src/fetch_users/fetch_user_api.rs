@@ -1,38 +1,33 @@
-use std::env;
-
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
 use crate::user::User;
 use async_trait::async_trait;
-use database::models::user::LocalUser;
-use http::{header::AUTHORIZATION, HeaderMap, HeaderValue};
-use reqwest::Result;
 
 use super::{fetch_users_command::FetchesUsers, fetch_users_response::FetchUsersResponse};
 
 pub struct FetchUsersApi {
-    client: reqwest::Client,
+    client: EquaterClient,
 }
 
 impl FetchUsersApi {
-    pub fn new(client: reqwest::Client) -> Self {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
         Self { client }
     }
 }
 
 #[async_trait]
 impl FetchesUsers for FetchUsersApi {
-    async fn fetch_users(&self, local_user: &LocalUser, search_term: &str) -> Result<Vec<User>> {
-        let api_base = env::var("EQUATER_API_BASE").expect("EQUATER_API_BASE must be set");
-        let url = format!(
-            "{}/api/user/search?searchTerm={}&includeAuthenticatedUser={}",
-            api_base, search_term, "true"
-        );
-        let mut header_map = HeaderMap::new();
-        let header_token =
-            HeaderValue::from_str(format!("Bearer {}", local_user.get_auth_token()).as_str())
-                .unwrap();
-        header_map.insert(AUTHORIZATION, header_token);
-        let response = self.client.get(url).headers(header_map).send().await?;
-        let mut fetch_users_response = response.json::<FetchUsersResponse>().await?;
+    async fn fetch_users(&self, search_term: &str) -> Result<Vec<User>> {
+        let mut fetch_users_response = self
+            .client
+            .get("/api/user/search")
+            .query(&[
+                ("searchTerm", search_term),
+                ("includeAuthenticatedUser", "true"),
+            ])
+            .send_json::<FetchUsersResponse>()
+            .await?;
 
         return Ok(fetch_users_response.move_to_single_list());
     }
@@ -1,10 +1,9 @@
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
 use crate::user::User;
 use async_trait::async_trait;
-use database::models::user::LocalUser;
 use fake::Fake;
 use fake::Faker;
-use http::response;
-use reqwest::Result;
 
 use super::fetch_users_command::FetchesUsers;
 
@@ -12,24 +11,26 @@ pub struct FetchUserApiFake {
     pub should_error: bool,
 }
 
+fn make_user(id: u32, first_name: &str, last_name: &str, email: &str) -> User {
+    let mut user: User = Faker.fake();
+    user.id = id;
+    user.first_name = String::from(first_name);
+    user.last_name = String::from(last_name);
+    user.email = String::from(email);
+
+    user
+}
+
 #[async_trait]
 impl FetchesUsers for FetchUserApiFake {
-    async fn fetch_users(&self, _local_user: &LocalUser, _search_term: &str) -> Result<Vec<User>> {
+    async fn fetch_users(&self, _search_term: &str) -> Result<Vec<User>> {
         if self.should_error {
-            let mut builder = response::Builder::new();
-            builder = builder.status(403);
-            let body = r#"{
-                 "error": "Unauthorized",
-            }"#;
-
-            // reqwest supports converting an http::response::Response into a reqwest::Response
-            let response: reqwest::Response = builder.body(body).unwrap().into();
-
-            return response.json().await;
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
         }
 
-        let response: Vec<User> = Faker.fake();
-
-        return Ok(response);
+        Ok(vec![
+            make_user(3, "Robert", "Paulson", "robert.paulson@example.com"),
+            make_user(9, "Roberta", "Diaz", "roberta.diaz@example.com"),
+        ])
     }
 }
@@ -1,13 +1,12 @@
-use crate::user::User;
+use crate::error::Result;
+use crate::{commands::FetchUsersArgs, output::OutputOptions, user::User};
 use async_trait::async_trait;
-use database::models::user::LocalUser;
-use reqwest::Result;
 
 use super::fetch_users_ui::FetchUsersUi;
 
 #[async_trait]
 pub trait FetchesUsers {
-    async fn fetch_users(&self, local_user: &LocalUser, search_term: &str) -> Result<Vec<User>>;
+    async fn fetch_users(&self, search_term: &str) -> Result<Vec<User>>;
 }
 
 pub struct FetchUsersCommand<T: FetchesUsers> {
@@ -19,23 +18,67 @@ impl<T: FetchesUsers> FetchUsersCommand<T> {
         Self { remote_repository }
     }
 
-    pub async fn fetch_and_show_users(&self, local_user: &LocalUser, search_term: &str) {
-        let maybe_users = self
-            .remote_repository
-            .fetch_users(local_user, search_term)
-            .await;
+    pub async fn fetch_and_show_users(
+        &self,
+        args: &FetchUsersArgs,
+        output: &OutputOptions,
+    ) -> Result<()> {
+        let mut users = self.remote_repository.fetch_users(&args.search).await?;
 
-        // TODO: Left off here - need to follow the tui-rs table example
-        // https://github.com/fdehau/tui-rs/blob/v0.18.0/examples/table.rs
-        // to render a table once we have a list of users
-        if let Ok(users) = maybe_users {
-            let ui = FetchUsersUi::new(users);
-            match ui.render() {
-                Ok(_) => { /*do nothing*/ }
-                Err(err) => println!("{}", err),
-            };
-        } else {
-            println!("no users")
+        if let Some(limit) = args.limit {
+            users.truncate(limit);
         }
+
+        let ui = FetchUsersUi::new(users);
+
+        ui.render(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FetchUsersCommand, FetchesUsers};
+    use crate::commands::FetchUsersArgs;
+    use crate::fetch_users::fetch_user_api::FetchUsersApi;
+    use crate::fetch_users::fetch_user_api_fake::FetchUserApiFake;
+    use crate::output::{OutputFormat, OutputOptions};
+    use crate::test_common::make_replay_client;
+
+    #[tokio::test]
+    async fn it_should_render_users_replayed_from_a_cassette() {
+        let remote_repository = FetchUsersApi::new(make_replay_client("fetch_users"));
+        let output = OutputOptions {
+            format: OutputFormat::Csv,
+            columns: None,
+        };
+        let mut rendered = vec![];
+
+        let users = remote_repository.fetch_users("robert").await.unwrap();
+        output.render_to(&users, &mut rendered).unwrap();
+
+        assert_eq!(
+            String::from_utf8(rendered).unwrap(),
+            "id,email,uuid,first_name,last_name
+3,robert.paulson@example.com,6f1d0a8e-1c62-4a43-8a4e-2f7d5c1b9e21,Robert,Paulson
+9,roberta.diaz@example.com,c2a7b5d9-8e3f-4d1a-b6c0-5e9f2a4d7b38,Roberta,Diaz
+14,bobby.tables@example.com,0b5c3e4e-55d4-4b4f-9a51-7f0f3f3b2c10,Robert,Tables
+"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let command = FetchUsersCommand::new(FetchUserApiFake { should_error: true });
+        let args = FetchUsersArgs {
+            search: String::from("robert"),
+            limit: None,
+        };
+        let result = command
+            .fetch_and_show_users(&args, &OutputOptions::default())
+            .await;
+
+        let error = result.unwrap_err();
+        assert_eq!(error.exit_code(), 6);
+        assert_eq!(error.status().map(|status| status.as_u16()), Some(403));
     }
 }
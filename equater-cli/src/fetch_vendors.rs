@@ -1,4 +1,5 @@
 pub mod fetch_vendors_api;
-mod fetch_vendors_api_fake;
+#[cfg(test)]
+pub mod fetch_vendors_api_fake;
 pub mod fetch_vendors_command;
 mod fetch_vendors_ui;
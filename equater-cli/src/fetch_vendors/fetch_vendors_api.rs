@@ -1,74 +1,128 @@
-use std::env;
-
-use crate::vendor::{Vendor, VendorResponse};
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::vendor::{
+    AssociateVendorDto, SingleVendorResponse, Vendor, VendorAssociationResponse, VendorPage,
+    VendorResponse,
+};
 use async_trait::async_trait;
-use database::models::user::LocalUser;
-use http::{header::AUTHORIZATION, HeaderMap, HeaderValue};
-use reqwest::Result;
 
-use super::fetch_vendors_command::FetchesVendors;
+use super::fetch_vendors_command::{FetchesVendors, ManagesVendorAssociations};
 
 pub struct FetchVendorsApi {
-    client: reqwest::Client,
+    client: EquaterClient,
 }
 
 impl FetchVendorsApi {
-    pub fn new(client: reqwest::Client) -> Self {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
         Self { client }
     }
 }
 
 #[async_trait]
 impl FetchesVendors for FetchVendorsApi {
-    async fn search_vendors(
-        &self,
-        local_user: &LocalUser,
-        search_term: &str,
-    ) -> Result<Vec<Vendor>> {
-        let api_base = env::var("EQUATER_API_BASE").expect("EQUATER_API_BASE must be set");
-        let url = format!(
-            "{}/api/vendor/search?searchTerm={}&requiringInternalReview={}",
-            api_base, search_term, "false"
-        );
-        let mut header_map = HeaderMap::new();
-        let header_token =
-            HeaderValue::from_str(format!("Bearer {}", local_user.get_auth_token()).as_str())
-                .unwrap();
-        header_map.insert(AUTHORIZATION, header_token);
-        let response = self.client.get(url).headers(header_map).send().await?;
-        let fetch_vendor_response = response.json::<VendorResponse>().await?;
+    async fn search_vendors(&self, search_term: &str) -> Result<Vec<Vendor>> {
+        let fetch_vendor_response = self
+            .client
+            .get("/api/vendor/search")
+            .query(&[
+                ("searchTerm", search_term),
+                ("requiringInternalReview", "false"),
+            ])
+            .send_json::<VendorResponse>()
+            .await?;
 
         return Ok(fetch_vendor_response.vendors);
     }
 
-    async fn fetch_popular_vendors(&self, local_user: &LocalUser) -> Result<Vec<Vendor>> {
-        let api_base = env::var("EQUATER_API_BASE").expect("EQUATER_API_BASE must be set");
-        let url = format!("{}/api/vendor/popular", api_base);
-        let mut header_map = HeaderMap::new();
-        let header_token =
-            HeaderValue::from_str(format!("Bearer {}", local_user.get_auth_token()).as_str())
-                .unwrap();
-        header_map.insert(AUTHORIZATION, header_token);
-        let response = self.client.get(url).headers(header_map).send().await?;
-        let fetch_vendor_response = response.json::<VendorResponse>().await?;
+    async fn fetch_popular_vendors(&self) -> Result<Vec<Vendor>> {
+        let fetch_vendor_response = self
+            .client
+            .get("/api/vendor/popular")
+            .send_json::<VendorResponse>()
+            .await?;
 
         return Ok(fetch_vendor_response.vendors);
     }
 
-    async fn fetch_vendors_that_require_review(
-        &self,
-        local_user: &LocalUser,
-    ) -> Result<Vec<Vendor>> {
-        let api_base = env::var("EQUATER_API_BASE").expect("EQUATER_API_BASE must be set");
-        let url = format!("{}/api/vendor/requires-internal-review", api_base);
-        let mut header_map = HeaderMap::new();
-        let header_token =
-            HeaderValue::from_str(format!("Bearer {}", local_user.get_auth_token()).as_str())
-                .unwrap();
-        header_map.insert(AUTHORIZATION, header_token);
-        let response = self.client.get(url).headers(header_map).send().await?;
-        let fetch_vendor_response = response.json::<VendorResponse>().await?;
+    async fn fetch_vendors_that_require_review(&self) -> Result<Vec<Vendor>> {
+        let fetch_vendor_response = self
+            .client
+            .get("/api/vendor/requires-internal-review")
+            .send_json::<VendorResponse>()
+            .await?;
 
         return Ok(fetch_vendor_response.vendors);
     }
+
+    async fn fetch_vendor_page(&self, page: u32) -> Result<VendorPage> {
+        self.client
+            .get("/api/vendor")
+            .query(&[("page", page)])
+            .send_json::<VendorPage>()
+            .await
+    }
+}
+
+#[async_trait]
+impl ManagesVendorAssociations for FetchVendorsApi {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor> {
+        let response = self
+            .client
+            .get(&format!("/api/vendor/{}", vendor_id))
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
+
+    async fn fetch_associations(&self, vendor_id: u32) -> Result<Vec<VendorAssociationResponse>> {
+        self.client
+            .get(&format!("/api/vendor/{}/associations", vendor_id))
+            .send_json::<Vec<VendorAssociationResponse>>()
+            .await
+    }
+
+    async fn associate_vendors(
+        &self,
+        vendor_id: u32,
+        associated_vendor_id: u32,
+        dto: &AssociateVendorDto,
+    ) -> Result<VendorAssociationResponse> {
+        self.client
+            .put(&format!(
+                "/api/vendor/{}/associate-with/{}",
+                vendor_id, associated_vendor_id
+            ))
+            .json(dto)
+            .send_json::<VendorAssociationResponse>()
+            .await
+    }
+
+    async fn delete_association(&self, vendor_id: u32, association_id: u32) -> Result<()> {
+        self.client
+            .delete(&format!(
+                "/api/vendor/{}/associations/{}",
+                vendor_id, association_id
+            ))
+            .send_empty()
+            .await
+    }
+
+    async fn assign_to_existing_vendor(
+        &self,
+        vendor_id: u32,
+        existing_vendor_id: u32,
+    ) -> Result<Vendor> {
+        let response = self
+            .client
+            .patch(&format!(
+                "/api/vendor/{}/assign-to-existing-vendor/{}",
+                vendor_id, existing_vendor_id
+            ))
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
 }
@@ -1,35 +1,41 @@
 use async_trait::async_trait;
 
-use crate::vendor::{Vendor, VendorResponse};
-use database::models::user::LocalUser;
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+use crate::vendor::{
+    AssociateVendorDto, Vendor, VendorAssociationResponse, VendorPage, VendorResponse,
+};
 use fake::{Fake, Faker};
-use http::response;
-use reqwest::Result;
 
-use super::fetch_vendors_command::FetchesVendors;
+use super::fetch_vendors_command::{FetchesVendors, ManagesVendorAssociations};
+
+/// The fake catalog has this many pages of `FAKE_VENDORS_PER_PAGE` vendors
+pub const FAKE_PAGE_COUNT: u32 = 3;
+pub const FAKE_VENDORS_PER_PAGE: usize = 4;
+
+/// Vendors in the fake review queue, in the order they're served
+pub const FAKE_REVIEW_QUEUE: [(u32, &str); 2] =
+    [(101, "Icon Central"), (102, "TRG Management Group")];
 
 pub struct FetchVendorsApiFake {
-    should_error: bool,
+    pub should_error: bool,
 }
 
 #[async_trait]
 impl FetchesVendors for FetchVendorsApiFake {
-    async fn search_vendors(
-        &self,
-        _local_user: &LocalUser,
-        _search_term: &str,
-    ) -> Result<Vec<Vendor>> {
+    async fn search_vendors(&self, _search_term: &str) -> Result<Vec<Vendor>> {
         if self.should_error {
-            let mut builder = response::Builder::new();
-            builder = builder.status(403);
-            let body = r#"{
-                 "error": "Unauthorized",
-            }"#;
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
 
-            // reqwest supports converting an http::response::Response into a reqwest::Response
-            let response: reqwest::Response = builder.body(body).unwrap().into();
+        let response: VendorResponse = Faker.fake();
 
-            return response.json().await;
+        return Ok(response.vendors);
+    }
+
+    async fn fetch_popular_vendors(&self) -> Result<Vec<Vendor>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
         }
 
         let response: VendorResponse = Faker.fake();
@@ -37,44 +43,112 @@ impl FetchesVendors for FetchVendorsApiFake {
         return Ok(response.vendors);
     }
 
-    async fn fetch_popular_vendors(&self, _local_user: &LocalUser) -> Result<Vec<Vendor>> {
+    async fn fetch_vendors_that_require_review(&self) -> Result<Vec<Vendor>> {
         if self.should_error {
-            let mut builder = response::Builder::new();
-            builder = builder.status(403);
-            let body = r#"{
-                 "error": "Unauthorized",
-            }"#;
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
 
-            // reqwest supports converting an http::response::Response into a reqwest::Response
-            let response: reqwest::Response = builder.body(body).unwrap().into();
+        Ok(FAKE_REVIEW_QUEUE
+            .iter()
+            .map(|(id, friendly_name)| {
+                let mut vendor: Vendor = Faker.fake();
+                vendor.id = *id;
+                vendor.friendly_name = String::from(*friendly_name);
+                vendor.has_been_reviewed_internally = false;
+
+                vendor
+            })
+            .collect())
+    }
 
-            return response.json().await;
+    async fn fetch_vendor_page(&self, page: u32) -> Result<VendorPage> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
         }
 
-        let response: VendorResponse = Faker.fake();
+        let vendors = if page < FAKE_PAGE_COUNT {
+            (0..FAKE_VENDORS_PER_PAGE).map(|_| Faker.fake()).collect()
+        } else {
+            vec![]
+        };
+        let next_page = Some(format!(
+            "http://localhost:7111/api/vendor?page={}",
+            page + 1
+        ))
+        .filter(|_| page + 1 < FAKE_PAGE_COUNT);
+        let previous_page = page
+            .checked_sub(1)
+            .map(|previous| format!("http://localhost:7111/api/vendor?page={}", previous));
+
+        Ok(VendorPage {
+            vendors,
+            next_page,
+            previous_page,
+        })
+    }
+}
 
-        return Ok(response.vendors);
+#[async_trait]
+impl ManagesVendorAssociations for FetchVendorsApiFake {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = vendor_id;
+
+        Ok(vendor)
+    }
+
+    async fn fetch_associations(&self, vendor_id: u32) -> Result<Vec<VendorAssociationResponse>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let mut associations: Vec<VendorAssociationResponse> = Faker.fake();
+        for association in associations.iter_mut() {
+            association.association.unique_vendor_id = vendor_id;
+            association.vendor.id = vendor_id;
+        }
+
+        Ok(associations)
     }
 
-    async fn fetch_vendors_that_require_review(
+    async fn associate_vendors(
         &self,
-        _local_user: &LocalUser,
-    ) -> Result<Vec<Vendor>> {
+        vendor_id: u32,
+        associated_vendor_id: u32,
+        dto: &AssociateVendorDto,
+    ) -> Result<VendorAssociationResponse> {
         if self.should_error {
-            let mut builder = response::Builder::new();
-            builder = builder.status(403);
-            let body = r#"{
-                 "error": "Unauthorized",
-            }"#;
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
 
-            // reqwest supports converting an http::response::Response into a reqwest::Response
-            let response: reqwest::Response = builder.body(body).unwrap().into();
+        let mut response: VendorAssociationResponse = Faker.fake();
+        response.association.unique_vendor_id = vendor_id;
+        response.association.associated_unique_vendor_id = associated_vendor_id;
+        response.association.association_type = dto.association_type;
+        response.association.notes = Some(dto.notes.clone());
+        response.vendor.id = vendor_id;
+        response.associated_vendor.id = associated_vendor_id;
 
-            return response.json().await;
+        Ok(response)
+    }
+
+    async fn delete_association(&self, _vendor_id: u32, _association_id: u32) -> Result<()> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
         }
 
-        let response: VendorResponse = Faker.fake();
+        Ok(())
+    }
 
-        return Ok(response.vendors);
+    async fn assign_to_existing_vendor(
+        &self,
+        _vendor_id: u32,
+        existing_vendor_id: u32,
+    ) -> Result<Vendor> {
+        self.fetch_vendor(existing_vendor_id).await
     }
 }
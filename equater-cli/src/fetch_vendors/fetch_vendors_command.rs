@@ -1,28 +1,47 @@
-use crate::{flag_command::FlagCommand, vendor::Vendor};
+use std::io::{self, Write};
+
+use crate::error::{EquaterError, Result};
+use crate::vendor::{AssociateVendorDto, VendorAssociationResponse, VendorPage};
+use crate::{commands::FetchVendorsArgs, output::OutputOptions, vendor::Vendor};
 use async_trait::async_trait;
-use database::models::user::LocalUser;
-use reqwest::Result;
 
 use super::fetch_vendors_ui::FetchVendorsUi;
 
 #[async_trait]
 pub trait FetchesVendors {
-    async fn search_vendors(
+    async fn search_vendors(&self, search_term: &str) -> Result<Vec<Vendor>>;
+    async fn fetch_popular_vendors(&self) -> Result<Vec<Vendor>>;
+    async fn fetch_vendors_that_require_review(&self) -> Result<Vec<Vendor>>;
+    async fn fetch_vendor_page(&self, page: u32) -> Result<VendorPage>;
+}
+
+/// Admin operations that link vendors together or fold one vendor into another
+#[async_trait]
+pub trait ManagesVendorAssociations {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor>;
+    async fn fetch_associations(&self, vendor_id: u32) -> Result<Vec<VendorAssociationResponse>>;
+    async fn associate_vendors(
         &self,
-        local_user: &LocalUser,
-        search_term: &str,
-    ) -> Result<Vec<Vendor>>;
-    async fn fetch_popular_vendors(&self, local_user: &LocalUser) -> Result<Vec<Vendor>>;
-    async fn fetch_vendors_that_require_review(
+        vendor_id: u32,
+        associated_vendor_id: u32,
+        dto: &AssociateVendorDto,
+    ) -> Result<VendorAssociationResponse>;
+    async fn delete_association(&self, vendor_id: u32, association_id: u32) -> Result<()>;
+    /// Reassigns every transaction and transaction name from `vendor_id` to `existing_vendor_id`
+    /// and deletes `vendor_id`
+    async fn assign_to_existing_vendor(
         &self,
-        local_user: &LocalUser,
-    ) -> Result<Vec<Vendor>>;
+        vendor_id: u32,
+        existing_vendor_id: u32,
+    ) -> Result<Vendor>;
 }
 
-enum FetchVendorOperationType {
+pub enum FetchVendorOperationType {
     Search(String),
     ReviewRequired,
     Popular,
+    All,
+    Page(u32),
     Default,
 }
 
@@ -35,70 +54,317 @@ impl<T: FetchesVendors> FetchVendorsCommand<T> {
         Self { remote_repository }
     }
 
-    pub async fn fetch_and_show_vendors(&self, local_user: &LocalUser, flag_command: &FlagCommand) {
-        if let Some(operation) = self.map_command_to_operation(flag_command) {
-            let maybe_vendors = self.fetch_vendors(local_user, &operation).await;
-            match maybe_vendors {
-                Ok(vendors) => match FetchVendorsUi::new(vendors).render() {
-                    Ok(_) => {}
-                    Err(err) => println!("Error: {}", err),
-                },
-                Err(err) => {
-                    println!("Error fetching vendors {}", err);
-                }
-            }
-        } else {
-            println!("Could not map those flags to a valid operation");
+    pub async fn fetch_and_show_vendors(
+        &self,
+        args: &FetchVendorsArgs,
+        output: &OutputOptions,
+    ) -> Result<()> {
+        let operation = FetchVendorOperationType::from(args);
+        if let FetchVendorOperationType::All = operation {
+            let mut stdout = io::stdout();
+            self.stream_all_vendors(args.limit, output, &mut stdout)
+                .await?;
+
+            return Ok(());
+        }
+
+        let mut vendors = self.fetch_vendors(&operation).await?;
+        if let Some(limit) = args.limit {
+            vendors.truncate(limit);
         }
+
+        FetchVendorsUi::new(vendors).render(output)
     }
 
-    async fn fetch_vendors(
+    /// Pages are requested one at a time and printed as soon as they arrive, so memory use stays
+    /// flat however large the catalog is. Returns the number of pages that were fetched.
+    ///
+    /// A session that expires after the first page fails rather than signing in again, since
+    /// starting over would print the same vendors twice.
+    async fn stream_all_vendors(
         &self,
-        local_user: &LocalUser,
-        operation: &FetchVendorOperationType,
-    ) -> Result<Vec<Vendor>> {
+        limit: Option<usize>,
+        output: &OutputOptions,
+        out: &mut dyn Write,
+    ) -> Result<u32> {
+        let mut stream = output.stream::<Vendor>(out)?;
+        let mut pages = VendorPages::new(&self.remote_repository);
+        let mut remaining = limit.unwrap_or(usize::MAX);
+
+        while remaining > 0 {
+            let mut vendors = match pages.next().await {
+                Ok(Some(vendors)) => vendors,
+                Ok(None) => break,
+                Err(err) if err.is_unauthorized() && pages.pages_fetched > 0 => {
+                    return Err(EquaterError::SessionExpired(format!(
+                        "The session expired after {} page(s) of vendors were printed, so the list is incomplete -- sign in with equater login and run the command again",
+                        pages.pages_fetched
+                    )));
+                }
+                Err(err) => return Err(err),
+            };
+            vendors.truncate(remaining);
+            remaining -= vendors.len();
+            stream.write(&vendors)?;
+        }
+
+        stream.finish()?;
+
+        Ok(pages.pages_fetched)
+    }
+
+    async fn fetch_vendors(&self, operation: &FetchVendorOperationType) -> Result<Vec<Vendor>> {
         match operation {
             FetchVendorOperationType::Search(search_term) => {
-                self.remote_repository
-                    .search_vendors(local_user, search_term)
-                    .await
+                self.remote_repository.search_vendors(search_term).await
             }
             FetchVendorOperationType::ReviewRequired => {
                 self.remote_repository
-                    .fetch_vendors_that_require_review(local_user)
+                    .fetch_vendors_that_require_review()
                     .await
             }
             FetchVendorOperationType::Popular => {
-                self.remote_repository
-                    .fetch_popular_vendors(local_user)
-                    .await
+                self.remote_repository.fetch_popular_vendors().await
+            }
+            FetchVendorOperationType::Page(page) => self
+                .remote_repository
+                .fetch_vendor_page(*page)
+                .await
+                .map(|response| response.vendors),
+            FetchVendorOperationType::All => {
+                unreachable!("--all is streamed by fetch_and_show_vendors")
             }
             FetchVendorOperationType::Default => {
-                self.remote_repository
-                    .fetch_popular_vendors(local_user)
-                    .await
+                self.remote_repository.fetch_popular_vendors().await
             }
         }
     }
+}
 
-    fn map_command_to_operation(
-        &self,
-        flag_command: &FlagCommand,
-    ) -> Option<FetchVendorOperationType> {
-        return flag_command
-            .flag
-            .as_ref()
-            .map(|value| match value.as_ref() {
-                "--review-required" => FetchVendorOperationType::ReviewRequired,
-                "--search" => {
-                    let search_term = &flag_command.value;
-                    match &search_term {
-                        Some(term) => FetchVendorOperationType::Search(String::from(term)),
-                        None => FetchVendorOperationType::Default,
-                    }
-                }
-                "--popular" => FetchVendorOperationType::Popular,
-                _ => FetchVendorOperationType::Default,
-            });
+/// Walks GET /api/vendor from the first page, only requesting the next page when asked for it
+pub struct VendorPages<'a, T: FetchesVendors> {
+    remote_repository: &'a T,
+    next_page: Option<u32>,
+    pub pages_fetched: u32,
+}
+
+impl<'a, T: FetchesVendors> VendorPages<'a, T> {
+    pub fn new(remote_repository: &'a T) -> Self {
+        Self {
+            remote_repository,
+            next_page: Some(0),
+            pages_fetched: 0,
+        }
+    }
+
+    pub async fn next(&mut self) -> Result<Option<Vec<Vendor>>> {
+        let page = match self.next_page {
+            Some(page) => page,
+            None => return Ok(None),
+        };
+
+        let response = self.remote_repository.fetch_vendor_page(page).await?;
+        self.pages_fetched += 1;
+        verbose!(
+            "Fetched page {} with {} vendor(s)",
+            page,
+            response.vendors.len()
+        );
+
+        // An empty page also ends the listing so that a server that keeps reporting a next page
+        // can't keep us looping forever
+        self.next_page = match response.next_page {
+            Some(_) if !response.vendors.is_empty() => Some(page + 1),
+            _ => None,
+        };
+
+        Ok(Some(response.vendors))
+    }
+}
+
+impl From<&FetchVendorsArgs> for FetchVendorOperationType {
+    fn from(args: &FetchVendorsArgs) -> Self {
+        if let Some(search_term) = &args.search {
+            FetchVendorOperationType::Search(search_term.clone())
+        } else if args.review_required {
+            FetchVendorOperationType::ReviewRequired
+        } else if args.popular {
+            FetchVendorOperationType::Popular
+        } else if args.all {
+            FetchVendorOperationType::All
+        } else if let Some(page) = args.page {
+            FetchVendorOperationType::Page(page)
+        } else {
+            FetchVendorOperationType::Default
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::{FetchVendorOperationType, FetchVendorsCommand, FetchesVendors, VendorPages};
+    use crate::commands::FetchVendorsArgs;
+    use crate::error::Result;
+    use crate::fetch_vendors::fetch_vendors_api_fake::{
+        FetchVendorsApiFake, FAKE_PAGE_COUNT, FAKE_VENDORS_PER_PAGE,
+    };
+    use crate::output::{OutputFormat, OutputOptions};
+    use crate::test_common::make_error_response;
+    use crate::vendor::{Vendor, VendorPage};
+
+    /// The session expires after the first page
+    struct ExpiringSessionFake(FetchVendorsApiFake);
+
+    #[async_trait]
+    impl FetchesVendors for ExpiringSessionFake {
+        async fn search_vendors(&self, search_term: &str) -> Result<Vec<Vendor>> {
+            self.0.search_vendors(search_term).await
+        }
+
+        async fn fetch_popular_vendors(&self) -> Result<Vec<Vendor>> {
+            self.0.fetch_popular_vendors().await
+        }
+
+        async fn fetch_vendors_that_require_review(&self) -> Result<Vec<Vendor>> {
+            self.0.fetch_vendors_that_require_review().await
+        }
+
+        async fn fetch_vendor_page(&self, page: u32) -> Result<VendorPage> {
+            if page > 0 {
+                return make_error_response(
+                    401,
+                    r#"{"statusCode": 401, "message": "Unauthorized"}"#,
+                )
+                .await;
+            }
+
+            self.0.fetch_vendor_page(page).await
+        }
+    }
+
+    fn make_args() -> FetchVendorsArgs {
+        FetchVendorsArgs {
+            search: None,
+            review_required: false,
+            popular: false,
+            all: false,
+            page: None,
+            limit: None,
+        }
+    }
+
+    #[test]
+    fn it_should_map_flags_to_an_operation() {
+        let mut args = make_args();
+        assert!(matches!(
+            FetchVendorOperationType::from(&args),
+            FetchVendorOperationType::Default
+        ));
+
+        args.review_required = true;
+        assert!(matches!(
+            FetchVendorOperationType::from(&args),
+            FetchVendorOperationType::ReviewRequired
+        ));
+
+        args.search = Some(String::from("netflix"));
+        match FetchVendorOperationType::from(&args) {
+            FetchVendorOperationType::Search(term) => assert_eq!(term, "netflix"),
+            _ => panic!("Expected a search operation"),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let command = FetchVendorsCommand::new(FetchVendorsApiFake { should_error: true });
+        let operation = FetchVendorOperationType::Popular;
+        let result = command.fetch_vendors(&operation).await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_stop_after_the_last_page() {
+        let remote_repository = FetchVendorsApiFake {
+            should_error: false,
+        };
+        let mut pages = VendorPages::new(&remote_repository);
+        let mut vendor_count = 0;
+        while let Some(vendors) = pages.next().await.unwrap() {
+            vendor_count += vendors.len();
+        }
+
+        assert_eq!(pages.pages_fetched, FAKE_PAGE_COUNT);
+        assert_eq!(
+            vendor_count,
+            FAKE_PAGE_COUNT as usize * FAKE_VENDORS_PER_PAGE
+        );
+        assert!(pages.next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn it_should_stream_every_page_as_ndjson() {
+        let command = FetchVendorsCommand::new(FetchVendorsApiFake {
+            should_error: false,
+        });
+        let output = OutputOptions {
+            format: OutputFormat::Ndjson,
+            columns: Some(vec![String::from("id")]),
+        };
+        let mut out = Vec::new();
+        command
+            .stream_all_vendors(None, &output, &mut out)
+            .await
+            .unwrap();
+
+        let lines = String::from_utf8(out).unwrap().lines().count();
+        assert_eq!(lines, FAKE_PAGE_COUNT as usize * FAKE_VENDORS_PER_PAGE);
+    }
+
+    #[tokio::test]
+    async fn it_should_not_fetch_more_pages_than_the_limit_needs() {
+        let command = FetchVendorsCommand::new(FetchVendorsApiFake {
+            should_error: false,
+        });
+        let output = OutputOptions {
+            format: OutputFormat::Csv,
+            columns: None,
+        };
+        let mut out = Vec::new();
+        let pages_fetched = command
+            .stream_all_vendors(Some(FAKE_VENDORS_PER_PAGE + 1), &output, &mut out)
+            .await
+            .unwrap();
+
+        assert_eq!(pages_fetched, 2);
+        // The header plus one row per vendor
+        let lines = String::from_utf8(out).unwrap().lines().count();
+        assert_eq!(lines, FAKE_VENDORS_PER_PAGE + 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_fail_instead_of_signing_in_again_partway_through_the_stream() {
+        let command = FetchVendorsCommand::new(ExpiringSessionFake(FetchVendorsApiFake {
+            should_error: false,
+        }));
+        let output = OutputOptions {
+            format: OutputFormat::Ndjson,
+            columns: None,
+        };
+        let mut out = Vec::new();
+
+        let err = command
+            .stream_all_vendors(None, &output, &mut out)
+            .await
+            .unwrap_err();
+
+        assert!(!err.is_unauthorized());
+        assert_eq!(err.exit_code(), 6);
+        assert_eq!(
+            String::from_utf8(out).unwrap().lines().count(),
+            FAKE_VENDORS_PER_PAGE
+        );
     }
 }
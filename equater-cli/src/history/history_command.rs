@@ -0,0 +1,150 @@
+use chrono::Duration;
+use database::models::audit_log::AuditLogEntry;
+use database::repository::audit_log_repository::{AuditLogFilter, AuditLogRepository};
+use serde::Serialize;
+
+use crate::commands::HistoryArgs;
+use crate::error::Result;
+use crate::output::{Columns, OutputOptions};
+
+/// One entry of the audit log
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: i32,
+    /// UTC, e.g. 2022-05-14 17:53:16
+    pub date_time_requested: String,
+    pub profile_name: String,
+    pub api_base: String,
+    pub environment: Option<String>,
+    pub operator_email: Option<String>,
+    pub method: String,
+    pub path: String,
+    pub request_body: Option<String>,
+    pub response_status: Option<i32>,
+}
+
+impl Columns for HistoryEntry {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "date_time_requested",
+            "profile_name",
+            "environment",
+            "operator_email",
+            "method",
+            "path",
+            "response_status",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "date_time_requested",
+            "profile_name",
+            "api_base",
+            "environment",
+            "operator_email",
+            "method",
+            "path",
+            "request_body",
+            "response_status",
+        ]
+    }
+}
+
+impl From<AuditLogEntry> for HistoryEntry {
+    fn from(entry: AuditLogEntry) -> Self {
+        Self {
+            id: entry.id,
+            date_time_requested: entry.date_time_requested,
+            profile_name: entry.profile_name,
+            api_base: entry.api_base,
+            environment: entry.environment,
+            operator_email: entry.operator_email,
+            method: entry.method,
+            path: entry.path,
+            request_body: entry.request_body,
+            response_status: entry.response_status,
+        }
+    }
+}
+
+/// Lists the local audit log. Use `--output csv` or `--output json` to export it.
+pub struct HistoryCommand<'a> {
+    repository: &'a AuditLogRepository<'a>,
+}
+
+impl<'a> HistoryCommand<'a> {
+    pub fn new(repository: &'a AuditLogRepository<'a>) -> Self {
+        Self { repository }
+    }
+
+    /// `profile_name` is the active profile, which entries are limited to unless
+    /// `--all-profiles` is passed
+    pub fn show_history(
+        &self,
+        args: &HistoryArgs,
+        profile_name: &str,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let entries: Vec<HistoryEntry> = self
+            .repository
+            .find_entries(&make_filter(args, profile_name))?
+            .into_iter()
+            .map(HistoryEntry::from)
+            .collect();
+
+        if entries.is_empty() && output.is_table() {
+            return Ok(String::from("No requests match"));
+        }
+
+        output.render(&entries)?;
+
+        Ok(String::from(""))
+    }
+}
+
+fn make_filter(args: &HistoryArgs, profile_name: &str) -> AuditLogFilter {
+    AuditLogFilter {
+        profile_name: Some(String::from(profile_name)).filter(|_| !args.all_profiles),
+        operator_email: args.operator.clone(),
+        method: args.method.clone(),
+        path: args.path.clone(),
+        since: args.since.map(|since| since.to_string()),
+        // Timestamps on the last day sort after the bare date, so compare with the next day
+        until: args
+            .until
+            .map(|until| (until + Duration::days(1)).to_string()),
+        include_reads: args.reads,
+        limit: args.limit.map(|limit| limit as i64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::make_filter;
+    use crate::commands::HistoryArgs;
+
+    #[test]
+    fn it_should_include_the_whole_last_day() {
+        let args = HistoryArgs {
+            all_profiles: false,
+            operator: None,
+            method: Some(String::from("patch")),
+            path: None,
+            since: NaiveDate::from_ymd_opt(2022, 5, 1),
+            until: NaiveDate::from_ymd_opt(2022, 5, 31),
+            reads: false,
+            limit: None,
+        };
+
+        let filter = make_filter(&args, "production");
+
+        assert_eq!(filter.profile_name.as_deref(), Some("production"));
+        assert_eq!(filter.since.as_deref(), Some("2022-05-01"));
+        assert_eq!(filter.until.as_deref(), Some("2022-06-01"));
+    }
+}
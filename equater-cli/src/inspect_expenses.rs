@@ -0,0 +1,5 @@
+pub mod inspect_expenses_api;
+#[cfg(test)]
+pub mod inspect_expenses_api_fake;
+pub mod inspect_expenses_command;
+mod inspect_expenses_ui;
@@ -0,0 +1,59 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::shared_expense::{SharedExpenseStory, TransactionStory, UserAgreementStory};
+use async_trait::async_trait;
+use database::models::shared_expense::SharedExpense;
+
+use super::inspect_expenses_command::InspectsExpenses;
+
+pub struct InspectExpensesApi {
+    client: EquaterClient,
+}
+
+impl InspectExpensesApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl InspectsExpenses for InspectExpensesApi {
+    async fn fetch_shared_expense(&self, shared_expense_id: u32) -> Result<SharedExpense> {
+        self.client
+            .get(&format!("/api/expense/{}", shared_expense_id))
+            .send_json::<SharedExpense>()
+            .await
+    }
+
+    async fn fetch_shared_expenses_for_user(
+        &self,
+        user_id: u32,
+    ) -> Result<Vec<SharedExpenseStory>> {
+        self.client
+            .get(&format!("/api/expense/user/{}", user_id))
+            .send_json::<Vec<SharedExpenseStory>>()
+            .await
+    }
+
+    async fn fetch_agreement(&self, agreement_id: u32) -> Result<UserAgreementStory> {
+        self.client
+            .get(&format!("/api/expense/agreement/{}", agreement_id))
+            .send_json::<UserAgreementStory>()
+            .await
+    }
+
+    async fn fetch_transaction(&self, transaction_id: u32) -> Result<TransactionStory> {
+        self.client
+            .get(&format!("/api/expense/transaction/{}", transaction_id))
+            .send_json::<TransactionStory>()
+            .await
+    }
+
+    async fn fetch_transactions_for_user(&self, user_id: u32) -> Result<Vec<TransactionStory>> {
+        self.client
+            .get(&format!("/api/expense/user/transactions/{}", user_id))
+            .send_json::<Vec<TransactionStory>>()
+            .await
+    }
+}
@@ -0,0 +1,93 @@
+use async_trait::async_trait;
+use database::models::shared_expense::SharedExpense;
+
+use crate::error::Result;
+use crate::shared_expense::{SharedExpenseStory, TransactionStory, UserAgreementStory};
+use crate::test_common::{
+    make_agreement_story, make_error_response, make_transaction_story, UNAUTHORIZED_BODY,
+};
+use serde_json::json;
+
+use super::inspect_expenses_command::InspectsExpenses;
+
+/// Every story is the Netflix bill (shared expense 100, agreement 1000) from `test_common`.
+/// Agreements and transactions from 9000 up don't exist.
+pub struct InspectExpensesApiFake {
+    pub should_error: bool,
+}
+
+#[async_trait]
+impl InspectsExpenses for InspectExpensesApiFake {
+    async fn fetch_shared_expense(&self, shared_expense_id: u32) -> Result<SharedExpense> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let story = make_agreement_story(
+            10,
+            "Netflix",
+            shared_expense_id,
+            1000,
+            "2022-05-10T12:00:00.000Z",
+        );
+
+        Ok(serde_json::from_value(story["sharedExpense"].clone()).unwrap())
+    }
+
+    async fn fetch_shared_expenses_for_user(
+        &self,
+        _user_id: u32,
+    ) -> Result<Vec<SharedExpenseStory>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let story = make_agreement_story(10, "Netflix", 100, 1000, "2022-05-10T12:00:00.000Z");
+
+        Ok(vec![serde_json::from_value(story).unwrap()])
+    }
+
+    async fn fetch_agreement(&self, agreement_id: u32) -> Result<UserAgreementStory> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        if agreement_id >= 9000 {
+            return make_error_response(404, r#"{"statusCode": 404, "message": "Not Found"}"#)
+                .await;
+        }
+
+        let story =
+            make_agreement_story(10, "Netflix", 100, agreement_id, "2022-05-10T12:00:00.000Z");
+
+        Ok(serde_json::from_value(story).unwrap())
+    }
+
+    async fn fetch_transaction(&self, transaction_id: u32) -> Result<TransactionStory> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        if transaction_id >= 9000 {
+            return make_error_response(404, r#"{"statusCode": 404, "message": "Not Found"}"#)
+                .await;
+        }
+
+        Ok(serde_json::from_value(make_transaction_story(transaction_id, 2, "processed")).unwrap())
+    }
+
+    async fn fetch_transactions_for_user(&self, user_id: u32) -> Result<Vec<TransactionStory>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        // The second transaction belongs to a different bill
+        let mut other = make_transaction_story(501, user_id, "pending");
+        other["transaction"]["sharedExpenseId"] = json!(101);
+        other["transaction"]["sharedExpenseUserAgreementId"] = json!(1001);
+        let transactions = vec![make_transaction_story(500, user_id, "processed"), other];
+
+        Ok(transactions
+            .into_iter()
+            .map(|transaction| serde_json::from_value(transaction).unwrap())
+            .collect())
+    }
+}
@@ -0,0 +1,477 @@
+use async_trait::async_trait;
+use database::models::shared_expense::{
+    SharedExpense, SharedExpenseType, SharedExpenseUserAgreement, UserInvite,
+};
+use serde::Serialize;
+
+use crate::error::{EquaterError, Result};
+use crate::output::{Columns, OutputOptions};
+use crate::shared_expense::{SharedExpenseStory, TransactionStory, UserAgreementStory};
+
+use super::inspect_expenses_ui::InspectExpensesUi;
+
+#[async_trait]
+pub trait InspectsExpenses {
+    async fn fetch_shared_expense(&self, shared_expense_id: u32) -> Result<SharedExpense>;
+    async fn fetch_shared_expenses_for_user(&self, user_id: u32)
+        -> Result<Vec<SharedExpenseStory>>;
+    async fn fetch_agreement(&self, agreement_id: u32) -> Result<UserAgreementStory>;
+    async fn fetch_transaction(&self, transaction_id: u32) -> Result<TransactionStory>;
+    async fn fetch_transactions_for_user(&self, user_id: u32) -> Result<Vec<TransactionStory>>;
+}
+
+/// Something that happened to a shared expense, one of its agreements or one of its transactions
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleEvent {
+    /// ISO8601 String
+    pub date: String,
+    pub event: String,
+}
+
+impl Columns for LifecycleEvent {
+    fn default_columns() -> &'static [&'static str] {
+        &["date", "event"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["date", "event"]
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpenseDetails {
+    #[serde(flatten)]
+    pub story: SharedExpenseStory,
+    pub transactions: Vec<TransactionStory>,
+    pub lifecycle: Vec<LifecycleEvent>,
+}
+
+impl Columns for ExpenseDetails {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "shared_expense.id",
+            "shared_expense.expense_nick_name",
+            "vendor.friendly_name",
+            "initiating_user.email",
+            "shared_expense.is_active",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "shared_expense",
+            "agreements",
+            "vendor",
+            "initiating_user",
+            "active_users",
+            "prospective_users",
+            "transactions",
+            "lifecycle",
+        ]
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgreementDetails {
+    #[serde(flatten)]
+    pub story: UserAgreementStory,
+    pub transactions: Vec<TransactionStory>,
+    pub lifecycle: Vec<LifecycleEvent>,
+}
+
+impl Columns for AgreementDetails {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "user_agreement.id",
+            "shared_expense.expense_nick_name",
+            "user_agreement.user_id",
+            "user_agreement.contribution_value",
+            "user_agreement.is_active",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "shared_expense",
+            "agreements",
+            "vendor",
+            "initiating_user",
+            "active_users",
+            "prospective_users",
+            "user_agreement",
+            "transactions",
+            "lifecycle",
+        ]
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDetails {
+    #[serde(flatten)]
+    pub story: TransactionStory,
+    pub lifecycle: Vec<LifecycleEvent>,
+}
+
+impl Columns for TransactionDetails {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "transaction.id",
+            "payer.email",
+            "recipient.email",
+            "transaction.total_transaction_amount",
+            "transaction.dwolla_status",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "transaction",
+            "vendor",
+            "payer",
+            "recipient",
+            "shared_expense",
+            "shared_expense_agreement",
+            "lifecycle",
+        ]
+    }
+}
+
+pub struct InspectExpensesCommand<T: InspectsExpenses> {
+    remote_repository: T,
+}
+
+impl<T: InspectsExpenses> InspectExpensesCommand<T> {
+    pub fn new(remote_repository: T) -> Self {
+        Self { remote_repository }
+    }
+
+    pub async fn show_expense(&self, id: u32, output: &OutputOptions) -> Result<String> {
+        let details = self.fetch_expense_details(id).await?;
+        InspectExpensesUi::new(output).render_expense(&details)?;
+
+        Ok(String::from(""))
+    }
+
+    pub async fn show_agreement(&self, id: u32, output: &OutputOptions) -> Result<String> {
+        let details = self.fetch_agreement_details(id).await?;
+        InspectExpensesUi::new(output).render_agreement(&details)?;
+
+        Ok(String::from(""))
+    }
+
+    pub async fn show_transaction(&self, id: u32, output: &OutputOptions) -> Result<String> {
+        let details = self.fetch_transaction_details(id).await?;
+        InspectExpensesUi::new(output).render_transaction(&details)?;
+
+        Ok(String::from(""))
+    }
+
+    /// The expense endpoint only returns the expense itself, so its agreements, users and
+    /// transactions are pieced together from the owner's expenses and transaction history
+    pub async fn fetch_expense_details(&self, id: u32) -> Result<ExpenseDetails> {
+        let shared_expense = self.remote_repository.fetch_shared_expense(id).await?;
+        let owner_id = shared_expense.expense_owner_user_id;
+        let (stories, transactions) = tokio::try_join!(
+            self.remote_repository
+                .fetch_shared_expenses_for_user(owner_id),
+            self.remote_repository.fetch_transactions_for_user(owner_id),
+        )?;
+
+        let story = stories
+            .into_iter()
+            .find(|story| story.shared_expense.id == id)
+            .ok_or_else(|| {
+                EquaterError::Validation(format!(
+                    "Shared expense {} wasn't found among the expenses of its owner (user {})",
+                    id, owner_id
+                ))
+            })?;
+        let transactions: Vec<TransactionStory> = transactions
+            .into_iter()
+            .filter(|transaction| transaction.transaction.shared_expense_id == id)
+            .collect();
+
+        let mut lifecycle = expense_events(&story);
+        for agreement in &story.agreements {
+            lifecycle.extend(agreement_events(agreement, &story));
+        }
+        for invite in &story.prospective_users {
+            lifecycle.extend(invite_events(invite));
+        }
+        for transaction in &transactions {
+            lifecycle.extend(transaction_events(transaction));
+        }
+
+        Ok(ExpenseDetails {
+            story,
+            transactions,
+            lifecycle: in_order(lifecycle),
+        })
+    }
+
+    pub async fn fetch_agreement_details(&self, id: u32) -> Result<AgreementDetails> {
+        let story = self.remote_repository.fetch_agreement(id).await?;
+        let transactions: Vec<TransactionStory> = self
+            .remote_repository
+            .fetch_transactions_for_user(story.user_agreement.user_id)
+            .await?
+            .into_iter()
+            .filter(|transaction| transaction.transaction.shared_expense_user_agreement_id == id)
+            .collect();
+
+        let mut lifecycle = expense_events(&story.story);
+        lifecycle.extend(agreement_events(&story.user_agreement, &story.story));
+        for transaction in &transactions {
+            lifecycle.extend(transaction_events(transaction));
+        }
+
+        Ok(AgreementDetails {
+            story,
+            transactions,
+            lifecycle: in_order(lifecycle),
+        })
+    }
+
+    pub async fn fetch_transaction_details(&self, id: u32) -> Result<TransactionDetails> {
+        let story = self.remote_repository.fetch_transaction(id).await?;
+        let lifecycle = in_order(transaction_events(&story));
+
+        Ok(TransactionDetails { story, lifecycle })
+    }
+}
+
+/// ISO8601 strings sort chronologically, and the sort is stable so events that share a
+/// timestamp keep the order they happened in
+fn in_order(mut events: Vec<LifecycleEvent>) -> Vec<LifecycleEvent> {
+    events.sort_by(|a, b| a.date.cmp(&b.date));
+
+    events
+}
+
+fn event(date: &Option<String>, describe: impl FnOnce() -> String) -> Option<LifecycleEvent> {
+    date.as_ref().map(|date| LifecycleEvent {
+        date: date.clone(),
+        event: describe(),
+    })
+}
+
+fn expense_events(story: &SharedExpenseStory) -> Vec<LifecycleEvent> {
+    let expense = &story.shared_expense;
+    let kind = match expense.shared_expense_type {
+        SharedExpenseType::SharedBill => "shared bill",
+        SharedExpenseType::RecurringPayment => "recurring payment",
+    };
+    let created = Some(expense.date_time_created.clone());
+
+    [
+        event(&created, || {
+            format!(
+                "{} created the {} {}",
+                story.initiating_user.email, kind, expense.expense_nick_name
+            )
+        }),
+        event(&expense.target_date_of_first_charge, || {
+            String::from("First recurring payment scheduled")
+        }),
+        event(&expense.date_last_charged, || String::from("Last charged")),
+        event(&expense.date_next_payment_scheduled, || {
+            String::from("Next payment scheduled")
+        }),
+        event(&expense.recurring_payment_end_date, || {
+            String::from("Recurring payments end")
+        }),
+        event(&expense.date_time_deactivated, || {
+            String::from("Shared expense deactivated")
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn agreement_events(
+    agreement: &SharedExpenseUserAgreement,
+    story: &SharedExpenseStory,
+) -> Vec<LifecycleEvent> {
+    let user = story
+        .find_user(agreement.user_id)
+        .map(|user| user.email.clone())
+        .unwrap_or_else(|| format!("user {}", agreement.user_id));
+    let contribution = agreement
+        .contribution()
+        .map(|contribution| contribution.to_string())
+        .unwrap_or_default();
+    let created = Some(agreement.date_time_created.clone());
+
+    [
+        event(&created, || {
+            format!(
+                "Agreement {} sent to {} for {}",
+                agreement.id, user, contribution
+            )
+        }),
+        event(&agreement.date_time_became_active, || {
+            format!("{} accepted agreement {}", user, agreement.id)
+        }),
+        event(&agreement.date_time_became_inactive, || {
+            format!("Agreement {} became inactive", agreement.id)
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn invite_events(invite: &UserInvite) -> Vec<LifecycleEvent> {
+    let contribution = invite
+        .contribution()
+        .map(|contribution| contribution.to_string())
+        .unwrap_or_default();
+    let created = Some(invite.date_time_created.clone());
+
+    [
+        event(&created, || {
+            format!("{} was invited for {}", invite.email, contribution)
+        }),
+        event(&invite.date_time_became_user, || {
+            format!("{} signed up", invite.email)
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+fn transaction_events(story: &TransactionStory) -> Vec<LifecycleEvent> {
+    let transaction = &story.transaction;
+    let initiated = Some(transaction.date_time_initiated.clone());
+
+    [
+        event(&transaction.date_time_transaction_scheduled, || {
+            format!("Transaction {} scheduled", transaction.id)
+        }),
+        event(&initiated, || {
+            format!(
+                "Transaction {} initiated: {} pays {} {}",
+                transaction.id,
+                story.payer.email,
+                story.recipient.email,
+                transaction.total_transaction_amount
+            )
+        }),
+        transaction.dwolla_status.as_ref().and_then(|status| {
+            event(&transaction.date_time_dwolla_status_updated, || {
+                format!("Transaction {} transfer is {}", transaction.id, status)
+            })
+        }),
+        event(&transaction.date_time_transferred_to_destination, || {
+            format!(
+                "Transaction {} transferred to {}",
+                transaction.id, story.recipient.email
+            )
+        }),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InspectExpensesCommand, LifecycleEvent};
+    use crate::inspect_expenses::inspect_expenses_api_fake::InspectExpensesApiFake;
+    use database::models::money::Money;
+
+    fn make_command(should_error: bool) -> InspectExpensesCommand<InspectExpensesApiFake> {
+        InspectExpensesCommand::new(InspectExpensesApiFake { should_error })
+    }
+
+    fn make_event(date: &str, event: &str) -> LifecycleEvent {
+        LifecycleEvent {
+            date: String::from(date),
+            event: String::from(event),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_put_the_expense_lifecycle_in_order() {
+        let details = make_command(false)
+            .fetch_expense_details(100)
+            .await
+            .unwrap();
+
+        assert_eq!(details.transactions.len(), 1);
+        assert_eq!(
+            details.lifecycle,
+            vec![
+                make_event(
+                    "2022-04-30T00:00:00.000Z",
+                    "owner@example.com created the shared bill Netflix bill"
+                ),
+                make_event(
+                    "2022-04-30T00:00:00.000Z",
+                    "Agreement 1000 sent to member@example.com for Split evenly"
+                ),
+                make_event(
+                    "2022-05-10T12:00:00.000Z",
+                    "member@example.com accepted agreement 1000"
+                ),
+                make_event(
+                    "2022-05-11T12:00:00.000Z",
+                    "Transaction 500 initiated: member@example.com pays owner@example.com $7.99"
+                ),
+                make_event(
+                    "2022-05-12T12:00:00.000Z",
+                    "Transaction 500 transfer is processed"
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_only_include_transactions_for_the_agreement() {
+        let details = make_command(false)
+            .fetch_agreement_details(1000)
+            .await
+            .unwrap();
+
+        let transaction_ids: Vec<u32> = details
+            .transactions
+            .iter()
+            .map(|story| story.transaction.id)
+            .collect();
+        assert_eq!(transaction_ids, vec![500]);
+    }
+
+    #[tokio::test]
+    async fn it_should_read_amounts_as_money() {
+        let details = make_command(false)
+            .fetch_transaction_details(500)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            details.story.transaction.total_transaction_amount,
+            Money::from_cents(799)
+        );
+        assert_eq!(details.story.status(), "Transferred");
+    }
+
+    #[tokio::test]
+    async fn it_should_report_expenses_missing_from_their_owner() {
+        let result = make_command(false).fetch_expense_details(404).await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let result = make_command(true).fetch_transaction_details(500).await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+    }
+}
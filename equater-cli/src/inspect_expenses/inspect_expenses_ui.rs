@@ -0,0 +1,316 @@
+use std::slice;
+
+use cli_table::{print_stdout, Cell, CellStruct, Style, Table};
+use database::models::shared_expense::{
+    RecurringExpenseInterval, SharedExpense, SharedExpenseType, SharedExpenseUserAgreement,
+};
+
+use crate::error::Result;
+use crate::output::OutputOptions;
+use crate::shared_expense::{status_label, SharedExpenseStory, TransactionStory};
+
+use super::inspect_expenses_command::{
+    AgreementDetails, ExpenseDetails, LifecycleEvent, TransactionDetails,
+};
+
+/// Prints shared expenses, agreements and transactions as a summary followed by a section for
+/// each related list. Any format other than table prints the details as a single record.
+pub struct InspectExpensesUi<'a> {
+    output: &'a OutputOptions,
+}
+
+impl<'a> InspectExpensesUi<'a> {
+    pub fn new(output: &'a OutputOptions) -> Self {
+        Self { output }
+    }
+
+    pub fn render_expense(&self, details: &ExpenseDetails) -> Result<()> {
+        if !self.output.is_table() {
+            return self.output.render(slice::from_ref(details));
+        }
+
+        let story = &details.story;
+        self.render_expense_summary(story)?;
+
+        println!();
+        println!("Agreements");
+        if story.agreements.is_empty() {
+            println!("No agreements");
+        } else {
+            self.render_agreements(&story.agreements, story)?;
+        }
+
+        if !story.prospective_users.is_empty() {
+            println!();
+            println!("Invites");
+            self.render_invites(story)?;
+        }
+
+        self.render_transactions(&details.transactions)?;
+        self.render_lifecycle(&details.lifecycle)
+    }
+
+    pub fn render_agreement(&self, details: &AgreementDetails) -> Result<()> {
+        if !self.output.is_table() {
+            return self.output.render(slice::from_ref(details));
+        }
+
+        let agreement = &details.story.user_agreement;
+        println!("Agreement {}", agreement.id);
+        self.render_agreements(slice::from_ref(agreement), &details.story.story)?;
+
+        println!();
+        self.render_expense_summary(&details.story.story)?;
+        self.render_transactions(&details.transactions)?;
+        self.render_lifecycle(&details.lifecycle)
+    }
+
+    pub fn render_transaction(&self, details: &TransactionDetails) -> Result<()> {
+        if !self.output.is_table() {
+            return self.output.render(slice::from_ref(details));
+        }
+
+        let story = &details.story;
+        let transaction = &story.transaction;
+        println!("Transaction {}", transaction.id);
+        let data: Vec<Vec<CellStruct>> = vec![
+            vec!["UUID".cell(), transaction.uuid.clone().cell()],
+            vec![
+                "Expense".cell(),
+                format!(
+                    "{} (ID {})",
+                    story.shared_expense.expense_nick_name, story.shared_expense.id
+                )
+                .cell(),
+            ],
+            vec![
+                "Vendor".cell(),
+                story
+                    .vendor
+                    .as_ref()
+                    .map(|vendor| vendor.friendly_name.clone())
+                    .unwrap_or_default()
+                    .cell(),
+            ],
+            vec![
+                "Agreement ID".cell(),
+                transaction.shared_expense_user_agreement_id.cell(),
+            ],
+            vec!["Payer".cell(), story.payer.email.clone().cell()],
+            vec!["Recipient".cell(), story.recipient.email.clone().cell()],
+            vec![
+                "Amount".cell(),
+                transaction.total_transaction_amount.to_string().cell(),
+            ],
+            vec![
+                "Fee".cell(),
+                transaction.total_fee_amount.to_string().cell(),
+            ],
+            vec!["Status".cell(), story.status().cell()],
+            vec![
+                "Attempts".cell(),
+                transaction.number_of_times_attempted.cell(),
+            ],
+            vec![
+                "Plaid Transaction ID".cell(),
+                optional(transaction.plaid_transaction_id).cell(),
+            ],
+            vec![
+                "Dwolla Transfer ID".cell(),
+                transaction
+                    .dwolla_transfer_id
+                    .clone()
+                    .unwrap_or_default()
+                    .cell(),
+            ],
+        ];
+        print_stdout(data.table().bold(true))?;
+
+        self.render_lifecycle(&details.lifecycle)
+    }
+
+    fn render_expense_summary(&self, story: &SharedExpenseStory) -> Result<()> {
+        let expense = &story.shared_expense;
+        println!(
+            "{} (shared expense {})",
+            expense.expense_nick_name, expense.id
+        );
+        let data: Vec<Vec<CellStruct>> = vec![
+            vec!["UUID".cell(), expense.uuid.clone().cell()],
+            vec!["Type".cell(), describe_type(expense).cell()],
+            vec![
+                "Vendor".cell(),
+                story
+                    .vendor
+                    .as_ref()
+                    .map(|vendor| format!("{} (ID {})", vendor.friendly_name, vendor.id))
+                    .unwrap_or_default()
+                    .cell(),
+            ],
+            vec![
+                "Owner".cell(),
+                format!(
+                    "{} (ID {})",
+                    story.initiating_user.email, story.initiating_user.id
+                )
+                .cell(),
+            ],
+            vec![
+                "Status".cell(),
+                status_label(expense.is_active, expense.is_pending).cell(),
+            ],
+            vec!["Created".cell(), expense.date_time_created.clone().cell()],
+        ];
+
+        Ok(print_stdout(data.table().bold(true))?)
+    }
+
+    fn render_agreements(
+        &self,
+        agreements: &[SharedExpenseUserAgreement],
+        story: &SharedExpenseStory,
+    ) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = agreements
+            .iter()
+            .map(|agreement| {
+                vec![
+                    agreement.id.cell(),
+                    story
+                        .find_user(agreement.user_id)
+                        .map(|user| user.email.clone())
+                        .unwrap_or_else(|| format!("user {}", agreement.user_id))
+                        .cell(),
+                    agreement
+                        .contribution()
+                        .map(|contribution| contribution.to_string())
+                        .unwrap_or_default()
+                        .cell(),
+                    optional(agreement.payment_account_id).cell(),
+                    status_label(agreement.is_active, agreement.is_pending).cell(),
+                    agreement
+                        .date_time_became_active
+                        .clone()
+                        .unwrap_or_default()
+                        .cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "User".cell().bold(true),
+                "Contribution".cell().bold(true),
+                "Payment Account ID".cell().bold(true),
+                "Status".cell().bold(true),
+                "Became Active".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+
+    fn render_invites(&self, story: &SharedExpenseStory) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = story
+            .prospective_users
+            .iter()
+            .map(|invite| {
+                vec![
+                    invite.email.clone().cell(),
+                    invite
+                        .contribution()
+                        .map(|contribution| contribution.to_string())
+                        .unwrap_or_default()
+                        .cell(),
+                    invite.date_time_created.clone().cell(),
+                    invite.is_converted.cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "Email".cell().bold(true),
+                "Contribution".cell().bold(true),
+                "Invited".cell().bold(true),
+                "Signed Up".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+
+    fn render_transactions(&self, transactions: &[TransactionStory]) -> Result<()> {
+        println!();
+        println!("Transactions");
+        if transactions.is_empty() {
+            println!("No transactions");
+            return Ok(());
+        }
+
+        let data: Vec<Vec<CellStruct>> = transactions
+            .iter()
+            .map(|story| {
+                let transaction = &story.transaction;
+
+                vec![
+                    transaction.id.cell(),
+                    transaction.date_time_initiated.clone().cell(),
+                    story.payer.email.clone().cell(),
+                    story.recipient.email.clone().cell(),
+                    transaction.total_transaction_amount.to_string().cell(),
+                    transaction.total_fee_amount.to_string().cell(),
+                    story.status().cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "Initiated".cell().bold(true),
+                "Payer".cell().bold(true),
+                "Recipient".cell().bold(true),
+                "Amount".cell().bold(true),
+                "Fee".cell().bold(true),
+                "Status".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+
+    fn render_lifecycle(&self, lifecycle: &[LifecycleEvent]) -> Result<()> {
+        println!();
+        println!("Lifecycle");
+
+        self.output.render(lifecycle)
+    }
+}
+
+/// e.g. Shared bill or Recurring payment every 2 months
+fn describe_type(expense: &SharedExpense) -> String {
+    match expense.shared_expense_type {
+        SharedExpenseType::SharedBill => String::from("Shared bill"),
+        SharedExpenseType::RecurringPayment => {
+            let frequency = expense.expense_recurrence_frequency.unwrap_or(1);
+            let interval = match expense.expense_recurrence_interval {
+                Some(RecurringExpenseInterval::Months) => "month",
+                _ => "day",
+            };
+            let plural = if frequency == 1 { "" } else { "s" };
+
+            format!(
+                "Recurring payment every {} {}{}",
+                frequency, interval, plural
+            )
+        }
+    }
+}
+
+fn optional(value: Option<u32>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,89 @@
+use std::slice;
+
+use database::connection::{schema_version, supported_schema_version};
+use diesel::SqliteConnection;
+use serde::Serialize;
+
+use crate::commands::DatabaseCommand;
+use crate::error::Result;
+use crate::output::{Columns, OutputOptions};
+
+/// Where the local database lives and which migrations it's been through
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatus {
+    pub database_url: String,
+    /// None for a database that has never been migrated
+    pub schema_version: Option<String>,
+    /// The newest migration compiled into this version of the CLI
+    pub supported_schema_version: String,
+}
+
+impl Columns for DatabaseStatus {
+    fn default_columns() -> &'static [&'static str] {
+        &["database_url", "schema_version", "supported_schema_version"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["database_url", "schema_version", "supported_schema_version"]
+    }
+}
+
+/// Migrations are applied when the CLI connects, so by the time this runs the database is
+/// always up to date
+pub struct LocalDatabaseCommand<'a> {
+    connection: &'a SqliteConnection,
+    database_url: &'a str,
+}
+
+impl<'a> LocalDatabaseCommand<'a> {
+    pub fn new(connection: &'a SqliteConnection, database_url: &'a str) -> Self {
+        Self {
+            connection,
+            database_url,
+        }
+    }
+
+    pub fn execute(&self, command: &DatabaseCommand, output: &OutputOptions) -> Result<String> {
+        match command {
+            DatabaseCommand::Status => {
+                output.render(slice::from_ref(&self.status()?))?;
+
+                Ok(String::from(""))
+            }
+        }
+    }
+
+    fn status(&self) -> Result<DatabaseStatus> {
+        Ok(DatabaseStatus {
+            database_url: self.database_url.to_string(),
+            schema_version: schema_version(self.connection)?,
+            supported_schema_version: supported_schema_version().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalDatabaseCommand;
+    use database::connection::{connect_to, supported_schema_version};
+
+    #[test]
+    fn it_should_report_an_up_to_date_schema() {
+        let database_url = std::env::temp_dir()
+            .join(format!("equater-status-{}.db", std::process::id()))
+            .display()
+            .to_string();
+        let connection = connect_to(&database_url).unwrap().get().unwrap();
+
+        let status = LocalDatabaseCommand::new(&connection, &database_url)
+            .status()
+            .unwrap();
+        std::fs::remove_file(&database_url).unwrap();
+
+        assert_eq!(
+            status.schema_version.as_deref(),
+            Some(supported_schema_version())
+        );
+    }
+}
@@ -1,122 +1,694 @@
-use std::env;
+use std::future::Future;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+use std::sync::Arc;
 
-use async_recursion::async_recursion;
-use authentication::authentication_api::{AuthenticationApi, SignInStrategy};
+use audit_trail::AuditedRequest;
+use authentication::authentication_api::{AuthenticationApi, RemoteAuthentication, SignInStrategy};
 use authentication::authentication_controller::AuthenticationController;
-use database::connection::connect;
-use database::repository::user_repository::UserRepository;
-use dotenv::dotenv;
+use cassette::Cassette;
+use clap::Parser;
+use config::config_command::ManageConfigCommand;
+use config::config_settings::Config;
+use database::connection::connect_to;
+use database::models::audit_log::{is_read_method, AuditLogEntryData};
+use database::models::profile::Profile;
+use database::models::user::LocalUser;
+use database::repository::audit_log_repository::AuditLogRepository;
+use database::repository::mirrored_user_repository::MirroredUserRepository;
+use database::repository::mirrored_vendor_repository::MirroredVendorRepository;
+use database::repository::profile_repository::ProfileRepository;
+use database::repository::user_repository::{UserRepository, UserSessionRepository};
+use database::repository::vendor_review_repository::VendorReviewRepository;
+use dev_notify::dev_notify_api::DevNotifyApi;
+use dev_notify::dev_notify_command::DevNotifyCommand;
+use environment::environment_api::EnvironmentApi;
+use environment::environment_guard::{ApiEnvironment, ConfirmationPolicy, EnvironmentGuard};
+use equater_client::{EquaterClient, Transport};
+use error::{EquaterError, Result};
 use fetch_users::fetch_user_api::FetchUsersApi;
 use fetch_users::fetch_users_command::FetchUsersCommand;
 use fetch_vendors::fetch_vendors_api::FetchVendorsApi;
 use fetch_vendors::fetch_vendors_command::FetchVendorsCommand;
+use history::history_command::HistoryCommand;
+use inspect_expenses::inspect_expenses_api::InspectExpensesApi;
+use inspect_expenses::inspect_expenses_command::InspectExpensesCommand;
+use local_database::local_database_command::LocalDatabaseCommand;
+use mirror::mirror_fetch_command::MirrorFetchCommand;
+use mirror::mirror_sync_command::MirrorSyncCommand;
+use output::OutputOptions;
+use profile::profile_command::{resolve_profile, ManageProfilesCommand};
+use review_vendors::review_vendors_api::ReviewVendorsApi;
+use review_vendors::review_vendors_command::ReviewVendorsCommand;
+use simulate::simulate_api::SimulateApi;
+use simulate::simulate_command::{self, SimulateTransactionsCommand};
+use tui::tui_command::TuiCommand;
+use user_dossier::user_dossier_api::UserDossierApi;
+use user_dossier::user_dossier_command::UserDossierCommand;
+use vendor_apply::vendor_apply_api::VendorApplyApi;
+use vendor_apply::vendor_apply_command::VendorApplyCommand;
+use vendor_associations::vendor_associations_command::VendorAssociationsCommand;
+use vendor_logo::vendor_logo_api::VendorLogoApi;
+use vendor_logo::vendor_logo_command::VendorLogoCommand;
+use vendor_transactions::vendor_transactions_command::VendorTransactionsCommand;
+use watchlist::watchlist_api::WatchlistApi;
+use watchlist::watchlist_command::WatchlistCommand;
 
-use crate::commands::Command;
-use crate::commands::FetchCommand;
+use crate::commands::{
+    AgreementCommand, AssociationsCommand, Cli, Command, DevCommand, ExpenseCommand, FetchCommand,
+    FetchUsersArgs, FetchVendorsArgs, GlobalArgs, LoginArgs, LogoCommand, LogoutArgs,
+    ReviewCommand, SimulateCommand, TransactionCommand, UsersCommand, VendorsCommand,
+};
 
+#[macro_use]
+mod verbose;
+
+mod audit_trail;
 mod authentication;
+mod cassette;
 mod commands;
+mod config;
+mod dev_notify;
+mod environment;
+mod equater_client;
+mod error;
 mod fetch_users;
 mod fetch_vendors;
-pub mod flag_command;
+mod history;
+mod inspect_expenses;
+mod local_database;
+mod mirror;
+mod output;
+mod profile;
+mod review_vendors;
+mod shared_expense;
+mod simulate;
 #[cfg(test)]
 mod test_common;
+mod transaction;
+mod tui;
 mod user;
+mod user_dossier;
 pub mod vendor;
+mod vendor_apply;
+mod vendor_associations;
+mod vendor_logo;
+mod vendor_transactions;
+mod watchlist;
 
 #[tokio::main]
-async fn main() -> Result<(), String> {
-    dotenv().ok();
-    let args: Vec<String> = env::args().collect();
-    let command = parse_input(&args);
-    // TODO: Revisit when async closures are stable
-    let result = match command {
-        Ok(cmd) => Ok(execute_command(&cmd).await),
-        Err(err) => Err(err),
-    };
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    verbose::set_verbose(cli.global.verbose);
 
-    match result {
-        Ok(future) => match future {
-            Ok(success) => {
+    match run(&cli).await {
+        Ok(success) => {
+            if !success.is_empty() {
                 println!("{}", success);
-                Ok(())
             }
-            Err(err) => {
-                println!("{}", err);
-                Err(err)
-            }
-        },
+            ExitCode::SUCCESS
+        }
         Err(err) => {
-            println!("{}", err);
-            Err(err)
+            eprintln!("{}", err);
+            ExitCode::from(err.exit_code())
         }
     }
 }
 
-fn parse_input(input: &Vec<String>) -> Result<Command, String> {
-    if input.len() == 1 {
-        println!("Welcome to the Equater CLI. Since you didn't pass a command the --help (-h) menu will be displayed");
-        return Err(String::from(Command::make_help_menu()));
-    }
+async fn run(cli: &Cli) -> Result<String> {
+    // An invalid value mustn't stop config set or config edit from fixing it
+    let config = match &cli.command {
+        Command::Config(_) => {
+            let config = Config::load_leniently(&cli.global);
+            for problem in config.problems() {
+                eprintln!("{}", problem);
+            }
+
+            config
+        }
+        _ => Config::load(&cli.global)?,
+    };
+    output::set_color_mode(config.color());
 
-    Ok(Command::from(input)?)
+    execute_command(&cli.command, &cli.global, &config).await
 }
 
-#[async_recursion(?Send)]
-async fn execute_command(command: &Command) -> Result<String, String> {
-    let database_connection = connect().get().unwrap();
-    let local_user_repository = UserRepository::new(&database_connection);
-    let client = reqwest::Client::new();
+async fn execute_command(
+    command: &Command,
+    global: &GlobalArgs,
+    config: &Config,
+) -> Result<String> {
+    let output = OutputOptions {
+        format: config.output(),
+        columns: global.columns.clone(),
+    };
+    if let Command::Config(subcommand) = command {
+        return ManageConfigCommand::new(config).execute(subcommand, &output);
+    }
 
-    match command {
-        Command::Login => {
-            let remote_repository = AuthenticationApi::new(client);
-            let controller =
-                AuthenticationController::new(&local_user_repository, remote_repository);
-            let response = controller.sign_in(SignInStrategy::CommandLineInput).await?;
-
-            Ok(format!("{} is signed in", response.email))
-        }
-        Command::Logout => {
-            match local_user_repository.truncate() {
-                Ok(_rows_deleted) => Ok(String::from("You are now signed out of the CLI")),
-                Err(err) => Err(format!("Error signing out: {}", err)),
+    let database_url = config.database_url()?;
+    let database_connection = connect_to(&database_url)?.get()?;
+    let profile_repository = ProfileRepository::new(&database_connection);
+    if let Command::Profile(subcommand) = command {
+        return ManageProfilesCommand::new(&profile_repository).execute(subcommand, &output);
+    }
+    if let Command::Database(subcommand) = command {
+        return LocalDatabaseCommand::new(&database_connection, &database_url)
+            .execute(subcommand, &output);
+    }
+
+    let profile = resolve_profile(
+        &profile_repository,
+        global.profile.as_deref(),
+        config.api_base(),
+    )?;
+    if let Command::History(args) = command {
+        let repository = AuditLogRepository::new(&database_connection);
+        return HistoryCommand::new(&repository).show_history(args, profile.get_name(), &output);
+    }
+    let mirrored_vendor_repository =
+        MirroredVendorRepository::new(&database_connection, profile.get_id());
+    let mirrored_user_repository =
+        MirroredUserRepository::new(&database_connection, profile.get_id());
+    if let (true, Command::Fetch(subcommand)) = (global.offline, command) {
+        let executor =
+            MirrorFetchCommand::new(&mirrored_vendor_repository, &mirrored_user_repository);
+
+        return match subcommand {
+            FetchCommand::Users(args) => executor
+                .fetch_and_show_users(&with_default_user_limit(args, config.page_size()), &output),
+            FetchCommand::Vendors(args) => executor.fetch_and_show_vendors(
+                &with_default_vendor_limit(args, config.page_size()),
+                &output,
+            ),
+        };
+    }
+    // Signing out only deletes tokens, so it works even when the token key can't be loaded
+    if let Command::Logout(LogoutArgs { all }) = command {
+        let sessions = UserSessionRepository::new(&database_connection, profile.get_id());
+
+        if *all {
+            let wiped = sessions.truncate()?;
+
+            return Ok(format!(
+                "You are now signed out of every profile -- wiped {} cached auth token(s)",
+                wiped
+            ));
+        }
+        sessions.delete_user()?;
+
+        return Ok(String::from("You are now signed out of the CLI"));
+    }
+    let local_user_repository = UserRepository::new(
+        &database_connection,
+        profile.get_id(),
+        config.token_cipher()?,
+    );
+    let encrypted = local_user_repository.encrypt_plaintext_tokens()?;
+    if encrypted > 0 {
+        verbose!("Encrypted {} cached auth token(s)", encrypted);
+    }
+    let client = EquaterClient::with_timeouts(
+        profile.get_api_base(),
+        config.connect_timeout(),
+        config.request_timeout(),
+    )
+    .map_err(|err| {
+        EquaterError::Configuration(format!("Could not create an HTTP client: {}", err))
+    })?
+    .with_transport(cassette_transport(global)?);
+    let controller = AuthenticationController::new(
+        &local_user_repository,
+        AuthenticationApi::new(client.clone()),
+    );
+    let environment_guard =
+        EnvironmentGuard::new(EnvironmentApi::new(client.clone()), config.confirmation());
+    let _audit_trail_writer = AuditTrailWriter {
+        repository: AuditLogRepository::new(&database_connection),
+        profile: &profile,
+        environment_guard: &environment_guard,
+        local_user_repository: &local_user_repository,
+        include_reads: global.audit_reads,
+    };
+    let _cassette_writer = match client.transport() {
+        Transport::Record(cassette) => {
+            if environment_guard.environment().await?.is_production() {
+                return Err(EquaterError::Validation(String::from(
+                    "Refusing to record a cassette against production -- point the profile at a development server",
+                )));
             }
+
+            Some(CassetteWriter(cassette.clone()))
+        }
+        _ => None,
+    };
+    environment_guard.show_banner(profile.get_api_base()).await;
+    if let Some(action) = describe_mutation(command) {
+        if !environment_guard.confirm_mutation(&action).await? {
+            return Ok(String::from("Cancelled -- nothing was changed"));
+        }
+    }
+
+    match command {
+        Command::Login(args) => {
+            let response = controller.sign_in(login_strategy(args)?).await?;
+
+            Ok(format!(
+                "{} is signed in to the {} profile",
+                response.email,
+                profile.get_name()
+            ))
         }
         Command::Fetch(subcommand) => {
-            let remote_repository = AuthenticationApi::new(client);
-            let controller =
-                AuthenticationController::new(&local_user_repository, remote_repository);
-            if let Ok(local_user) = &controller.find_and_verify_local_user().await {
-                match subcommand {
-                    FetchCommand::Users(search_term) => {
-                        let client = reqwest::Client::new();
-                        let fetch_users_repository = FetchUsersApi::new(client);
-                        let executor = FetchUsersCommand::new(fetch_users_repository);
-                        executor.fetch_and_show_users(local_user, search_term).await;
-
-                        Ok(String::from(""))
-                    }
-                    FetchCommand::Vendors(flag_command) => {
-                        let client = reqwest::Client::new();
-                        let fetch_vendor_repository = FetchVendorsApi::new(client);
-                        let executor = FetchVendorsCommand::new(fetch_vendor_repository);
-                        executor
-                            .fetch_and_show_vendors(local_user, flag_command)
-                            .await;
-
-                        Ok(String::from(""))
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+
+                execute_fetch_command(subcommand, &output, client, config.page_size())
+            })
+            .await
+        }
+        Command::Users(UsersCommand::Show { user }) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = UserDossierCommand::new(
+                    FetchUsersApi::new(client.clone()),
+                    UserDossierApi::new(client),
+                );
+                let output = &output;
+
+                async move { executor.show_user(user, output).await }
+            })
+            .await
+        }
+        Command::Expense(ExpenseCommand::Show { id }) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = InspectExpensesCommand::new(InspectExpensesApi::new(client));
+                let output = &output;
+
+                async move { executor.show_expense(*id, output).await }
+            })
+            .await
+        }
+        Command::Agreement(AgreementCommand::Show { id }) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = InspectExpensesCommand::new(InspectExpensesApi::new(client));
+                let output = &output;
+
+                async move { executor.show_agreement(*id, output).await }
+            })
+            .await
+        }
+        Command::Transaction(TransactionCommand::Show { id }) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = InspectExpensesCommand::new(InspectExpensesApi::new(client));
+                let output = &output;
+
+                async move { executor.show_transaction(*id, output).await }
+            })
+            .await
+        }
+        Command::Vendors(VendorsCommand::Associations(subcommand)) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = VendorAssociationsCommand::new(
+                    FetchVendorsApi::new(client),
+                    config.confirmation() == ConfirmationPolicy::Never,
+                );
+                let output = &output;
+
+                async move { executor.execute(subcommand, output).await }
+            })
+            .await
+        }
+        Command::Vendors(VendorsCommand::Apply(args)) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = VendorApplyCommand::new(
+                    VendorApplyApi::new(client),
+                    config.confirmation() == ConfirmationPolicy::Never,
+                );
+                let output = &output;
+
+                async move { executor.apply(args, output).await }
+            })
+            .await
+        }
+        Command::Vendors(VendorsCommand::Logo(LogoCommand::Upload(args))) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = VendorLogoCommand::new(VendorLogoApi::new(client));
+
+                async move { executor.upload_logo(args).await }
+            })
+            .await
+        }
+        Command::Vendors(VendorsCommand::Transactions(args)) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = VendorTransactionsCommand::new(
+                    FetchVendorsApi::new(client.clone()),
+                    ReviewVendorsApi::new(client),
+                );
+                let output = &output;
+
+                async move { executor.show_transactions(args, output).await }
+            })
+            .await
+        }
+        Command::Review(ReviewCommand::Vendors(args)) => {
+            let local_repository =
+                VendorReviewRepository::new(&database_connection, profile.get_id());
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = ReviewVendorsCommand::new(
+                    FetchVendorsApi::new(client.clone()),
+                    ReviewVendorsApi::new(client),
+                    &local_repository,
+                );
+
+                async move { executor.review_vendors(args).await }
+            })
+            .await
+        }
+        Command::Watchlist(args) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = WatchlistCommand::new(WatchlistApi::new(client));
+                let output = &output;
+
+                async move { executor.show_watchlist(args, output).await }
+            })
+            .await
+        }
+        Command::Tui => {
+            let reviews = VendorReviewRepository::new(&database_connection, profile.get_id())
+                .find_reviews()?;
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = TuiCommand::new(
+                    FetchUsersApi::new(client.clone()),
+                    FetchVendorsApi::new(client.clone()),
+                    WatchlistApi::new(client),
+                    reviews.clone(),
+                );
+
+                async move { executor.run().await }
+            })
+            .await
+        }
+        Command::Sync(args) => {
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = MirrorSyncCommand::new(
+                    FetchVendorsApi::new(client.clone()),
+                    FetchUsersApi::new(client.clone()),
+                    UserDossierApi::new(client),
+                    &mirrored_vendor_repository,
+                    &mirrored_user_repository,
+                );
+
+                async move { executor.sync(args).await }
+            })
+            .await
+        }
+        Command::Simulate(subcommand) => {
+            let environment = environment_guard.environment().await?;
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = SimulateTransactionsCommand::new(
+                    SimulateApi::new(client.clone()),
+                    InspectExpensesApi::new(client),
+                    environment.clone(),
+                    simulate_command::POLL_INTERVAL,
+                );
+                let output = &output;
+
+                async move {
+                    match subcommand {
+                        SimulateCommand::Transaction(args) => {
+                            executor.simulate_transaction(args, output).await
+                        }
+                        SimulateCommand::Expense(args) => {
+                            executor.simulate_expense(args, output).await
+                        }
                     }
                 }
-            } else {
-                // prevent an unnecessary duplicate request to the server to verify the auth token that's already been
-                // unverified by truncating the local user
-                let _ = local_user_repository.truncate();
-                execute_command(&Command::Login).await
+            })
+            .await
+        }
+        Command::Dev(DevCommand::Notify(args)) => {
+            let environment = environment_guard.environment().await?;
+            with_authentication(&controller, |local_user| {
+                let client = client.with_auth_token(local_user.get_auth_token());
+                let executor = DevNotifyCommand::new(
+                    DevNotifyApi::new(client.clone()),
+                    InspectExpensesApi::new(client),
+                    environment.clone(),
+                );
+                let output = &output;
+
+                async move { executor.notify(args, output).await }
+            })
+            .await
+        }
+        Command::Profile(_)
+        | Command::Database(_)
+        | Command::Config(_)
+        | Command::History(_)
+        | Command::Logout(_) => {
+            unreachable!(
+                "Profile, database, config, history and logout commands are handled before connecting to the API"
+            )
+        }
+    }
+}
+
+/// Writes the requests sent while running a command to the audit log when it's dropped, so the
+/// trail is written however the command ends. The operator is whoever is signed in at that point.
+/// A failure is reported but doesn't fail the command, which has already run.
+struct AuditTrailWriter<'a> {
+    repository: AuditLogRepository<'a>,
+    profile: &'a Profile,
+    environment_guard: &'a EnvironmentGuard<EnvironmentApi>,
+    local_user_repository: &'a UserRepository<'a>,
+    include_reads: bool,
+}
+
+impl Drop for AuditTrailWriter<'_> {
+    fn drop(&mut self) {
+        let requests: Vec<AuditedRequest> = audit_trail::take()
+            .into_iter()
+            .filter(|request| self.include_reads || !is_read_method(&request.method))
+            .collect();
+        if requests.is_empty() {
+            return;
+        }
+
+        let environment = self
+            .environment_guard
+            .cached_environment()
+            .map(ApiEnvironment::server_name);
+        let operator_email = self
+            .local_user_repository
+            .find_user()
+            .ok()
+            .map(|local_user| local_user.get_email().to_string());
+        let entries: Vec<AuditLogEntryData> = requests
+            .into_iter()
+            .map(|request| AuditLogEntryData {
+                date_time_requested: request.date_time_requested,
+                profile_name: self.profile.get_name().to_string(),
+                api_base: self.profile.get_api_base().to_string(),
+                environment: environment.clone(),
+                operator_email: operator_email.clone(),
+                method: request.method,
+                path: request.path,
+                request_body: request.request_body,
+                response_status: request.response_status.map(i32::from),
+            })
+            .collect();
+
+        if let Err(err) = self.repository.record(&entries) {
+            eprintln!(
+                "Could not write {} request(s) to the audit log: {}",
+                entries.len(),
+                err
+            );
+        }
+    }
+}
+
+/// Requests go to the network unless --record or --replay names a cassette
+fn cassette_transport(global: &GlobalArgs) -> Result<Transport> {
+    match (&global.record, &global.replay) {
+        (Some(path), _) => Ok(Transport::Record(Arc::new(Cassette::new(path)))),
+        (None, Some(path)) => Ok(Transport::Replay(Arc::new(Cassette::load(path)?))),
+        (None, None) => Ok(Transport::Network),
+    }
+}
+
+/// Saves the recorded cassette when it's dropped, so that the requests sent before a command
+/// fails are kept too
+struct CassetteWriter(Arc<Cassette>);
+
+impl Drop for CassetteWriter {
+    fn drop(&mut self) {
+        match self.0.save() {
+            Ok(()) => verbose!("Saved the cassette to {}", self.0.path().display()),
+            Err(err) => eprintln!(
+                "Could not save the cassette to {}: {}",
+                self.0.path().display(),
+                err
+            ),
+        }
+    }
+}
+
+/// Describes commands that change data on the server, so they can be confirmed before they run
+/// against production. Simulations aren't listed because they refuse to run against production
+/// at all.
+fn describe_mutation(command: &Command) -> Option<String> {
+    match command {
+        Command::Vendors(VendorsCommand::Associations(subcommand)) => match subcommand {
+            AssociationsCommand::List { .. } => None,
+            AssociationsCommand::Create(args) => Some(format!(
+                "associate vendor {} with vendor {}",
+                args.associated_vendor_id, args.vendor_id
+            )),
+            AssociationsCommand::Delete {
+                vendor_id,
+                association_id,
+            } => Some(format!(
+                "delete association {} from vendor {}",
+                association_id, vendor_id
+            )),
+            AssociationsCommand::Merge(args) => Some(format!(
+                "merge vendor {} into vendor {}",
+                args.vendor_id, args.into
+            )),
+        },
+        Command::Vendors(VendorsCommand::Logo(LogoCommand::Upload(args))) => {
+            Some(format!("upload a logo for vendor {}", args.vendor_id))
+        }
+        Command::Vendors(VendorsCommand::Apply(args)) if !args.dry_run => Some(format!(
+            "apply the vendor changes in {}",
+            args.file.display()
+        )),
+        Command::Review(ReviewCommand::Vendors(_)) => {
+            Some(String::from("edit vendors in the review queue"))
+        }
+        _ => None,
+    }
+}
+
+/// Runs a command that requires a signed in user. When there's no cached session, or the server
+/// responds with a 401 part way through, the user signs in again and the command is retried once.
+/// A command that already sent a change isn't retried, since running it again would repeat the
+/// change, e.g. simulate a second transaction.
+async fn with_authentication<'a, T, F, Fut>(
+    controller: &AuthenticationController<'a, T>,
+    run: F,
+) -> Result<String>
+where
+    T: RemoteAuthentication,
+    F: Fn(LocalUser) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let changes_before = audit_trail::changes_sent();
+    let result = match controller.find_local_user() {
+        Ok(local_user) => run(local_user).await,
+        Err(err) => Err(err),
+    };
+
+    match result {
+        Err(err) if err.is_unauthorized() => {
+            verbose!("{}", err);
+            let changes = audit_trail::changes_sent() - changes_before;
+            if changes > 0 {
+                return Err(EquaterError::SessionExpired(format!(
+                    "The session expired after {} change(s) were sent, so the command wasn't retried -- sign in with equater login and check equater history before running it again",
+                    changes
+                )));
+            }
+            // Scripts have nothing to prompt, so they have to run equater login again
+            if !io::stdin().is_terminal() {
+                return Err(EquaterError::NotSignedIn);
             }
+            eprintln!("Your session has expired or you aren't signed in -- sign in to continue");
+            let local_user = controller.sign_in(SignInStrategy::CommandLineInput).await?;
+
+            run(local_user).await
         }
-        Command::Help => {
-            Ok(String::from(Command::make_help_menu()))
+        result => result,
+    }
+}
+
+/// `--password-stdin` reads the password from the first line of stdin, otherwise both the email
+/// and password are prompted for
+fn login_strategy(args: &LoginArgs) -> Result<SignInStrategy> {
+    let email = match (&args.email, args.password_stdin) {
+        (Some(email), true) => email.clone(),
+        _ => return Ok(SignInStrategy::CommandLineInput),
+    };
+
+    let mut password = String::new();
+    io::stdin().read_line(&mut password).map_err(|err| {
+        EquaterError::Validation(format!("Could not read the password from stdin: {}", err))
+    })?;
+    let password = password.trim_end_matches(['\r', '\n']).to_string();
+    if password.is_empty() {
+        return Err(EquaterError::Validation(String::from(
+            "--password-stdin expects the password on the first line of stdin",
+        )));
+    }
+
+    Ok(SignInStrategy::StoredValues { email, password })
+}
+
+async fn execute_fetch_command(
+    command: &FetchCommand,
+    output: &OutputOptions,
+    client: EquaterClient,
+    page_size: Option<usize>,
+) -> Result<String> {
+    match command {
+        FetchCommand::Users(args) => {
+            let fetch_users_repository = FetchUsersApi::new(client);
+            let executor = FetchUsersCommand::new(fetch_users_repository);
+            executor
+                .fetch_and_show_users(&with_default_user_limit(args, page_size), output)
+                .await?;
+        }
+        FetchCommand::Vendors(args) => {
+            let fetch_vendor_repository = FetchVendorsApi::new(client);
+            let executor = FetchVendorsCommand::new(fetch_vendor_repository);
+            executor
+                .fetch_and_show_vendors(&with_default_vendor_limit(args, page_size), output)
+                .await?;
         }
     }
+
+    Ok(String::from(""))
+}
+
+/// The page_size setting is the default `--limit`
+fn with_default_user_limit(args: &FetchUsersArgs, page_size: Option<usize>) -> FetchUsersArgs {
+    FetchUsersArgs {
+        limit: args.limit.or(page_size),
+        ..args.clone()
+    }
+}
+
+/// `--all` streams the whole catalog, so page_size only applies to the other listings
+fn with_default_vendor_limit(
+    args: &FetchVendorsArgs,
+    page_size: Option<usize>,
+) -> FetchVendorsArgs {
+    FetchVendorsArgs {
+        limit: args.limit.or(page_size.filter(|_| !args.all)),
+        ..args.clone()
+    }
 }
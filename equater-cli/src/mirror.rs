@@ -0,0 +1,3 @@
+pub mod mirror_fetch_command;
+pub mod mirror_sync_command;
+mod mirrored;
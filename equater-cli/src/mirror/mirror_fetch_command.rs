@@ -0,0 +1,106 @@
+use database::repository::mirrored_user_repository::MirroredUserRepository;
+use database::repository::mirrored_vendor_repository::MirroredVendorRepository;
+
+use crate::commands::{FetchUsersArgs, FetchVendorsArgs};
+use crate::error::Result;
+use crate::fetch_vendors::fetch_vendors_command::FetchVendorOperationType;
+use crate::output::OutputOptions;
+use crate::user::User;
+use crate::vendor::Vendor;
+
+use super::mirrored::Mirrored;
+
+/// Matches the page size of GET /api/vendor so --page means the same thing offline
+const VENDORS_PER_PAGE: i64 = 100;
+/// Matches the number of results the API returns for a search
+const SEARCH_LIMIT: i64 = 50;
+
+/// Serves `fetch users` and `fetch vendors` from the mirror that `equater sync` maintains
+pub struct MirrorFetchCommand<'a> {
+    vendor_repository: &'a MirroredVendorRepository<'a>,
+    user_repository: &'a MirroredUserRepository<'a>,
+}
+
+impl<'a> MirrorFetchCommand<'a> {
+    pub fn new(
+        vendor_repository: &'a MirroredVendorRepository<'a>,
+        user_repository: &'a MirroredUserRepository<'a>,
+    ) -> Self {
+        Self {
+            vendor_repository,
+            user_repository,
+        }
+    }
+
+    pub fn fetch_and_show_users(
+        &self,
+        args: &FetchUsersArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let mut users = self.find_users(&args.search)?;
+        if let Some(limit) = args.limit {
+            users.truncate(limit);
+        }
+
+        self.render(&users, output)
+    }
+
+    pub fn fetch_and_show_vendors(
+        &self,
+        args: &FetchVendorsArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let mut vendors = self.find_vendors(&FetchVendorOperationType::from(args))?;
+        if let Some(limit) = args.limit {
+            vendors.truncate(limit);
+        }
+
+        self.render(&vendors, output)
+    }
+
+    pub fn find_users(&self, search_term: &str) -> Result<Vec<Mirrored<User>>> {
+        self.user_repository
+            .search(search_term, SEARCH_LIMIT)?
+            .into_iter()
+            .map(Mirrored::try_from)
+            .collect()
+    }
+
+    pub fn find_vendors(
+        &self,
+        operation: &FetchVendorOperationType,
+    ) -> Result<Vec<Mirrored<Vendor>>> {
+        let vendors = match operation {
+            FetchVendorOperationType::Search(search_term) => {
+                self.vendor_repository.search(search_term, SEARCH_LIMIT)?
+            }
+            FetchVendorOperationType::ReviewRequired => {
+                self.vendor_repository.find_vendors_that_require_review()?
+            }
+            FetchVendorOperationType::Popular | FetchVendorOperationType::Default => self
+                .vendor_repository
+                .find_popular_vendors(VENDORS_PER_PAGE)?,
+            FetchVendorOperationType::All => self.vendor_repository.find_vendors(0, i64::MAX)?,
+            FetchVendorOperationType::Page(page) => self
+                .vendor_repository
+                .find_vendors(*page as i64 * VENDORS_PER_PAGE, VENDORS_PER_PAGE)?,
+        };
+
+        vendors.into_iter().map(Mirrored::try_from).collect()
+    }
+
+    fn render<T>(&self, records: &[Mirrored<T>], output: &OutputOptions) -> Result<String>
+    where
+        Mirrored<T>: crate::output::Columns,
+    {
+        if records.is_empty() && output.is_table() {
+            return Ok(String::from(
+                "Nothing in the local mirror matched -- run equater sync to refresh it",
+            ));
+        }
+
+        output.render(records)?;
+
+        Ok(String::from(""))
+    }
+}
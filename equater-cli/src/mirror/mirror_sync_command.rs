@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+use database::models::mirror::MirrorWrite;
+use database::repository::mirrored_user_repository::MirroredUserRepository;
+use database::repository::mirrored_vendor_repository::MirroredVendorRepository;
+use http::StatusCode;
+
+use crate::commands::SyncArgs;
+use crate::error::Result;
+use crate::fetch_users::fetch_users_command::FetchesUsers;
+use crate::fetch_vendors::fetch_vendors_command::{FetchesVendors, VendorPages};
+use crate::user_dossier::user_dossier_command::FetchesUserDossiers;
+
+use super::mirrored::{to_mirrored_user, to_mirrored_vendor};
+
+/// What a sync did to one of the mirror tables
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncCounts {
+    pub inserted: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+impl SyncCounts {
+    fn record(&mut self, write: MirrorWrite) {
+        match write {
+            MirrorWrite::Inserted => self.inserted += 1,
+            MirrorWrite::Updated => self.updated += 1,
+            MirrorWrite::Unchanged => self.unchanged += 1,
+        }
+    }
+}
+
+impl Display for SyncCounts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} new, {} updated, {} unchanged, {} removed",
+            self.inserted, self.updated, self.unchanged, self.removed
+        )
+    }
+}
+
+/// Refreshes the local mirror. The whole vendor catalog is walked on every sync since the API
+/// can't list vendors modified since a point in time, but only vendors that changed are
+/// rewritten. The API can't list every user either, so users are added to the mirror by searching
+/// for them and every mirrored user is refreshed one at a time.
+pub struct MirrorSyncCommand<'a, V: FetchesVendors, U: FetchesUsers, D: FetchesUserDossiers> {
+    vendor_api: V,
+    user_api: U,
+    dossier_api: D,
+    vendor_repository: &'a MirroredVendorRepository<'a>,
+    user_repository: &'a MirroredUserRepository<'a>,
+}
+
+impl<'a, V: FetchesVendors, U: FetchesUsers, D: FetchesUserDossiers>
+    MirrorSyncCommand<'a, V, U, D>
+{
+    pub fn new(
+        vendor_api: V,
+        user_api: U,
+        dossier_api: D,
+        vendor_repository: &'a MirroredVendorRepository<'a>,
+        user_repository: &'a MirroredUserRepository<'a>,
+    ) -> Self {
+        Self {
+            vendor_api,
+            user_api,
+            dossier_api,
+            vendor_repository,
+            user_repository,
+        }
+    }
+
+    pub async fn sync(&self, args: &SyncArgs) -> Result<String> {
+        let vendors = self.sync_vendors().await?;
+        let users = self.sync_users(&args.search_users).await?;
+
+        Ok(format!("Vendors: {}\nUsers: {}", vendors, users))
+    }
+
+    /// The whole catalog is fetched before anything is written so that the mirror is replaced in
+    /// one transaction
+    pub async fn sync_vendors(&self) -> Result<SyncCounts> {
+        let profile_id = self.vendor_repository.get_profile_id();
+        let mut mirrored = vec![];
+        let mut seen: HashSet<u32> = HashSet::new();
+
+        // The catalog only lists reviewed vendors, so the review queue is mirrored as well
+        let mut pages = VendorPages::new(&self.vendor_api);
+        while let Some(vendors) = pages.next().await? {
+            for vendor in &vendors {
+                if seen.insert(vendor.id) {
+                    mirrored.push(to_mirrored_vendor(profile_id, vendor)?);
+                }
+            }
+        }
+        let review_queue = self.vendor_api.fetch_vendors_that_require_review().await?;
+        for vendor in &review_queue {
+            if seen.insert(vendor.id) {
+                mirrored.push(to_mirrored_vendor(profile_id, vendor)?);
+            }
+        }
+
+        let (writes, removed) = self.vendor_repository.replace_vendors(&mirrored)?;
+        let mut counts = SyncCounts {
+            removed,
+            ..SyncCounts::default()
+        };
+        for write in writes {
+            counts.record(write);
+        }
+
+        Ok(counts)
+    }
+
+    pub async fn sync_users(&self, search_terms: &[String]) -> Result<SyncCounts> {
+        let profile_id = self.user_repository.get_profile_id();
+        let mut counts = SyncCounts::default();
+        let mut seen: HashSet<i32> = HashSet::new();
+
+        for search_term in search_terms {
+            for user in self.user_api.fetch_users(search_term).await? {
+                if !seen.insert(user.id as i32) {
+                    continue;
+                }
+                counts.record(
+                    self.user_repository
+                        .save_user(&to_mirrored_user(profile_id, &user)?)?,
+                );
+            }
+        }
+
+        for user_id in self.user_repository.find_user_ids()? {
+            if seen.contains(&user_id) {
+                continue;
+            }
+
+            match self.dossier_api.fetch_user(user_id as u32).await {
+                Ok(user) => counts.record(
+                    self.user_repository
+                        .save_user(&to_mirrored_user(profile_id, &user)?)?,
+                ),
+                Err(err) if err.status() == Some(StatusCode::NOT_FOUND) => {
+                    counts.removed += self.user_repository.remove_user(user_id)?;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MirrorSyncCommand;
+    use crate::fetch_users::fetch_user_api_fake::FetchUserApiFake;
+    use crate::fetch_vendors::fetch_vendors_api_fake::{
+        FetchVendorsApiFake, FAKE_PAGE_COUNT, FAKE_VENDORS_PER_PAGE,
+    };
+    use crate::fetch_vendors::fetch_vendors_command::FetchVendorOperationType;
+    use crate::mirror::mirror_fetch_command::MirrorFetchCommand;
+    use crate::test_common::{connect_to_test_database, make_test_profile};
+    use crate::user_dossier::user_dossier_api_fake::UserDossierApiFake;
+    use database::repository::mirrored_user_repository::MirroredUserRepository;
+    use database::repository::mirrored_vendor_repository::MirroredVendorRepository;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn it_should_mirror_vendors_and_serve_them_offline() {
+        let connection = connect_to_test_database().get().unwrap();
+        let profile = make_test_profile(&connection);
+        let vendor_repository = MirroredVendorRepository::new(&connection, profile.get_id());
+        let user_repository = MirroredUserRepository::new(&connection, profile.get_id());
+        vendor_repository.clear().unwrap();
+        let command = MirrorSyncCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            FetchUserApiFake {
+                should_error: false,
+            },
+            UserDossierApiFake {
+                should_error: false,
+            },
+            &vendor_repository,
+            &user_repository,
+        );
+
+        let counts = command.sync_vendors().await.unwrap();
+        let catalog_size = FAKE_PAGE_COUNT as usize * FAKE_VENDORS_PER_PAGE;
+        assert!(counts.inserted >= catalog_size);
+        assert_eq!(counts.updated + counts.unchanged + counts.removed, 0);
+
+        let offline = MirrorFetchCommand::new(&vendor_repository, &user_repository);
+        let vendors = offline
+            .find_vendors(&FetchVendorOperationType::Page(0))
+            .unwrap();
+        assert_eq!(vendors.len(), counts.inserted.min(100));
+        assert!(vendors[0].synced.ends_with("ago"));
+
+        let name = vendors[0].record.friendly_name.clone();
+        let found = offline
+            .find_vendors(&FetchVendorOperationType::Search(name.clone()))
+            .unwrap();
+        assert!(found
+            .iter()
+            .any(|vendor| vendor.record.friendly_name == name));
+
+        vendor_repository.clear().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn it_should_add_searched_users_and_refresh_mirrored_users() {
+        let connection = connect_to_test_database().get().unwrap();
+        let profile = make_test_profile(&connection);
+        let vendor_repository = MirroredVendorRepository::new(&connection, profile.get_id());
+        let user_repository = MirroredUserRepository::new(&connection, profile.get_id());
+        user_repository.clear().unwrap();
+        let command = MirrorSyncCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            UserDossierApiFake {
+                should_error: false,
+            },
+            UserDossierApiFake {
+                should_error: false,
+            },
+            &vendor_repository,
+            &user_repository,
+        );
+
+        let counts = command.sync_users(&[String::from("member")]).await.unwrap();
+        assert_eq!(counts.inserted, 2);
+
+        // Users that aren't part of a search are refreshed by id
+        let counts = command.sync_users(&[]).await.unwrap();
+        assert_eq!(counts.inserted + counts.removed, 0);
+        assert_eq!(counts.updated + counts.unchanged, 2);
+
+        let offline = MirrorFetchCommand::new(&vendor_repository, &user_repository);
+        assert_eq!(offline.find_users("member@example").unwrap().len(), 2);
+
+        user_repository.clear().unwrap();
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn it_should_leave_the_mirror_alone_when_the_api_errors() {
+        let connection = connect_to_test_database().get().unwrap();
+        let profile = make_test_profile(&connection);
+        let vendor_repository = MirroredVendorRepository::new(&connection, profile.get_id());
+        let user_repository = MirroredUserRepository::new(&connection, profile.get_id());
+        let command = MirrorSyncCommand::new(
+            FetchVendorsApiFake { should_error: true },
+            FetchUserApiFake { should_error: true },
+            UserDossierApiFake { should_error: true },
+            &vendor_repository,
+            &user_repository,
+        );
+
+        let result = command.sync_vendors().await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+    }
+}
@@ -0,0 +1,178 @@
+use chrono::{NaiveDateTime, Utc};
+use database::models::mirror::{
+    MirroredUser, MirroredUserData, MirroredVendor, MirroredVendorData,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{EquaterError, Result};
+use crate::output::{format_duration, Columns};
+use crate::user::User;
+use crate::vendor::Vendor;
+
+/// A record served from the local mirror along with how stale it is
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mirrored<T> {
+    #[serde(flatten)]
+    pub record: T,
+    /// Set by sqlite, e.g. 2022-05-14 17:53:16
+    pub date_time_synced: String,
+    /// e.g. 3d 4h ago
+    pub synced: String,
+}
+
+impl<T> Mirrored<T> {
+    pub fn new(record: T, date_time_synced: String) -> Self {
+        let synced = NaiveDateTime::parse_from_str(&date_time_synced, "%Y-%m-%d %H:%M:%S")
+            .map(|synced| format!("{} ago", format_duration(Utc::now().naive_utc() - synced)))
+            .unwrap_or_default();
+
+        Self {
+            record,
+            date_time_synced,
+            synced,
+        }
+    }
+}
+
+impl Columns for Mirrored<Vendor> {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "friendly_name",
+            "uuid",
+            "has_been_reviewed_internally",
+            "vendor_identity_cannot_be_determined",
+            "synced",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "uuid",
+            "ppd_id",
+            "date_time_added",
+            "date_time_modified",
+            "total_number_of_expense_sharing_agreements",
+            "has_been_reviewed_internally",
+            "vendor_identity_cannot_be_determined",
+            "friendly_name",
+            "logo_s3_bucket",
+            "logo_s3_key",
+            "logo_url",
+            "logo_upload_completed",
+            "logo_sha256_hash",
+            "date_time_synced",
+            "synced",
+        ]
+    }
+}
+
+impl Columns for Mirrored<User> {
+    fn default_columns() -> &'static [&'static str] {
+        &["id", "email", "uuid", "first_name", "last_name", "synced"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "uuid",
+            "email",
+            "first_name",
+            "last_name",
+            "email_is_confirmed",
+            "can_receive_funds",
+            "profile_photo_url",
+            "profile_photo_upload_completed",
+            "profile_photo_sha256_hash",
+            "cover_photo_upload_completed",
+            "cover_photo_sha256_hash",
+            "date_time_created",
+            "address_one",
+            "address_two",
+            "city",
+            "state",
+            "postal_code",
+            "pre_signed_photo_download_url",
+            "pre_signed_cover_photo_download_url",
+            "accepted_terms_of_service",
+            "accepted_privacy_policy",
+            "link_tokens",
+            "dwolla_reverification_needed",
+            "date_time_synced",
+            "synced",
+        ]
+    }
+}
+
+impl TryFrom<MirroredVendor> for Mirrored<Vendor> {
+    type Error = EquaterError;
+
+    fn try_from(mirrored: MirroredVendor) -> Result<Self> {
+        let vendor = serde_json::from_str(&mirrored.data).map_err(|err| {
+            EquaterError::LocalDatabase(format!(
+                "Mirrored vendor {} couldn't be read ({}) -- run equater sync to refresh it",
+                mirrored.vendor_id, err
+            ))
+        })?;
+
+        Ok(Mirrored::new(vendor, mirrored.date_time_synced))
+    }
+}
+
+impl TryFrom<MirroredUser> for Mirrored<User> {
+    type Error = EquaterError;
+
+    fn try_from(mirrored: MirroredUser) -> Result<Self> {
+        let user = serde_json::from_str(&mirrored.data).map_err(|err| {
+            EquaterError::LocalDatabase(format!(
+                "Mirrored user {} couldn't be read ({}) -- run equater sync to refresh it",
+                mirrored.user_id, err
+            ))
+        })?;
+
+        Ok(Mirrored::new(user, mirrored.date_time_synced))
+    }
+}
+
+pub fn to_mirrored_vendor(profile_id: i32, vendor: &Vendor) -> Result<MirroredVendorData> {
+    Ok(MirroredVendorData {
+        profile_id,
+        vendor_id: vendor.id as i32,
+        friendly_name: vendor.friendly_name.clone(),
+        ppd_id: vendor.ppd_id.clone(),
+        has_been_reviewed_internally: vendor.has_been_reviewed_internally,
+        total_number_of_expense_sharing_agreements: vendor
+            .total_number_of_expense_sharing_agreements
+            as i32,
+        data: to_json(vendor)?,
+    })
+}
+
+pub fn to_mirrored_user(profile_id: i32, user: &User) -> Result<MirroredUserData> {
+    // Pre-signed urls are different on every response and expire long before a sync goes stale,
+    // so they're dropped rather than making every user look like it changed
+    let mut json =
+        serde_json::to_value(user).map_err(|err| EquaterError::LocalDatabase(err.to_string()))?;
+    for field in [
+        "preSignedPhotoDownloadUrl",
+        "preSignedCoverPhotoDownloadUrl",
+    ] {
+        json[field] = Value::Null;
+    }
+
+    Ok(MirroredUserData {
+        profile_id,
+        user_id: user.id as i32,
+        email: user.email.clone(),
+        first_name: user.first_name.clone(),
+        last_name: user.last_name.clone(),
+        data: to_json(&json)?,
+    })
+}
+
+fn to_json<T: Serialize>(record: &T) -> Result<String> {
+    serde_json::to_string(record).map_err(|err| EquaterError::LocalDatabase(err.to_string()))
+}
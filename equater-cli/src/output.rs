@@ -0,0 +1,742 @@
+use std::io::{self, IsTerminal, Write};
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+use chrono::Duration;
+use clap::ValueEnum;
+use cli_table::{Cell, CellStruct, ColorChoice, Style, Table};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{EquaterError, Result};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable table
+    #[default]
+    Table,
+    /// Pretty printed JSON array
+    Json,
+    /// One compact JSON object per line
+    Ndjson,
+    /// Comma separated values with a header row
+    Csv,
+    /// YAML sequence
+    Yaml,
+}
+
+impl OutputFormat {
+    pub fn renderer(&self) -> Box<dyn Renderer> {
+        match self {
+            OutputFormat::Table => Box::new(TableRenderer),
+            OutputFormat::Json => Box::<JsonRenderer>::default(),
+            OutputFormat::Ndjson => Box::new(NdjsonRenderer),
+            OutputFormat::Csv => Box::new(CsvRenderer),
+            OutputFormat::Yaml => Box::<YamlRenderer>::default(),
+        }
+    }
+
+    /// Tables and CSV need a flat list of columns. The other formats print whole records unless
+    /// columns are requested.
+    fn is_tabular(&self) -> bool {
+        matches!(self, OutputFormat::Table | OutputFormat::Csv)
+    }
+}
+
+/// When tables and banners use color. Set from the `color` setting or `--color`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorMode {
+    /// Only when printing to a terminal
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR_MODE: OnceLock<ColorMode> = OnceLock::new();
+
+/// Set once from the configuration before any command runs
+pub fn set_color_mode(color_mode: ColorMode) {
+    let _ = COLOR_MODE.set(color_mode);
+}
+
+/// Escape codes only make sense when a person is looking at the output, so `auto` leaves them
+/// out when the stream isn't a terminal
+pub fn color_choice(is_terminal: bool) -> ColorChoice {
+    match COLOR_MODE.get().copied().unwrap_or_default() {
+        ColorMode::Auto if is_terminal => ColorChoice::Auto,
+        ColorMode::Auto | ColorMode::Never => ColorChoice::Never,
+        ColorMode::Always => ColorChoice::Always,
+    }
+}
+
+/// Models that can be printed by any renderer. Columns are the snake_case names of the model's
+/// fields, and nested fields are addressed with a dot, e.g. `vendor.friendly_name`.
+pub trait Columns: Serialize {
+    /// Printed by table and CSV output when `--columns` isn't supplied
+    fn default_columns() -> &'static [&'static str];
+
+    /// Every top level field, including flattened ones, in the order they're serialized.
+    /// `--columns` is checked against these before anything is fetched or printed.
+    fn available_columns() -> &'static [&'static str];
+
+    /// Text shown in a table cell instead of the serialized value, e.g. `Parent Company` for
+    /// `PARENT_COMPANY`. Other formats always print the serialized value.
+    fn table_label(_column: &str, _value: &Value) -> Option<String> {
+        None
+    }
+}
+
+impl<T: Columns> Columns for &T {
+    fn default_columns() -> &'static [&'static str] {
+        T::default_columns()
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        T::available_columns()
+    }
+
+    fn table_label(column: &str, value: &Value) -> Option<String> {
+        T::table_label(column, value)
+    }
+}
+
+/// A record that's been selected down to the columns that will be printed
+pub type Record = Vec<(String, Value)>;
+
+/// Renderers receive records in batches so that long listings can be printed as they're fetched.
+/// `begin` and `finish` are called exactly once, with any number of `write_records` in between.
+pub trait Renderer {
+    fn begin(&mut self, _columns: &[String], _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+
+    fn write_records(
+        &mut self,
+        columns: &[String],
+        records: &[Record],
+        out: &mut dyn Write,
+    ) -> Result<()>;
+
+    fn finish(&mut self, _out: &mut dyn Write) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Output settings shared by every command, taken from the `output` setting (or `--output`) and
+/// `--columns`
+#[derive(Clone, Debug, Default)]
+pub struct OutputOptions {
+    pub format: OutputFormat,
+    pub columns: Option<Vec<String>>,
+}
+
+impl OutputOptions {
+    pub fn is_table(&self) -> bool {
+        self.format == OutputFormat::Table
+    }
+
+    /// Prints `items` to stdout
+    pub fn render<T: Columns>(&self, items: &[T]) -> Result<()> {
+        let mut stdout = io::stdout();
+
+        self.render_to(items, &mut stdout)
+    }
+
+    pub fn render_to<T: Columns>(&self, items: &[T], out: &mut dyn Write) -> Result<()> {
+        let mut stream = self.stream::<T>(out)?;
+        stream.write(items)?;
+
+        stream.finish()
+    }
+
+    /// Starts printing a listing that will arrive in batches
+    pub fn stream<'a, T: Columns>(&self, out: &'a mut dyn Write) -> Result<OutputStream<'a, T>> {
+        let columns: Vec<String> = match (&self.columns, self.format.is_tabular()) {
+            (Some(columns), _) => columns.clone(),
+            (None, true) => T::default_columns()
+                .iter()
+                .map(|column| String::from(*column))
+                .collect(),
+            (None, false) => vec![],
+        };
+        if let Some(column) = columns.iter().find(|column| !is_available::<T>(column)) {
+            return Err(unknown_column(column, T::available_columns().iter()));
+        }
+        let mut renderer = self.format.renderer();
+        renderer.begin(&columns, out)?;
+
+        Ok(OutputStream {
+            renderer,
+            columns,
+            is_table: self.is_table(),
+            out,
+            marker: PhantomData,
+        })
+    }
+}
+
+pub struct OutputStream<'a, T: Columns> {
+    renderer: Box<dyn Renderer>,
+    /// Empty when whole records are printed
+    columns: Vec<String>,
+    is_table: bool,
+    out: &'a mut dyn Write,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: Columns> OutputStream<'a, T> {
+    pub fn write(&mut self, items: &[T]) -> Result<()> {
+        let values = items
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<std::result::Result<Vec<Value>, _>>()
+            .map_err(|err| EquaterError::Decode(err.to_string()))?;
+        debug_assert!(
+            values.iter().all(|value| whole_record(value.clone())
+                .iter()
+                .all(|(key, _)| is_available::<T>(&camel_to_snake_case(key)))),
+            "a serialized field is missing from available_columns"
+        );
+        let mut records = if self.columns.is_empty() {
+            values.into_iter().map(whole_record).collect()
+        } else {
+            select_columns(&self.columns, &values)?
+        };
+        if self.is_table {
+            for (column, cell) in records.iter_mut().flatten() {
+                if let Some(label) = T::table_label(column, cell) {
+                    *cell = Value::String(label);
+                }
+            }
+        }
+
+        self.renderer
+            .write_records(&self.columns, &records, self.out)?;
+        self.out.flush()?;
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        self.renderer.finish(self.out)?;
+        self.out.flush()?;
+
+        Ok(())
+    }
+}
+
+fn whole_record(value: Value) -> Record {
+    match value {
+        Value::Object(map) => map.into_iter().collect(),
+        value => vec![(String::from("value"), value)],
+    }
+}
+
+/// The first segment of a column is a top level field, e.g. `vendor` in `vendor.friendly_name`
+fn is_available<T: Columns>(column: &str) -> bool {
+    let field = column.split('.').next().unwrap_or_default();
+
+    T::available_columns().contains(&field)
+}
+
+fn select_columns(columns: &[String], values: &[Value]) -> Result<Vec<Record>> {
+    values
+        .iter()
+        .map(|value| {
+            columns
+                .iter()
+                .map(|column| {
+                    lookup(value, column)
+                        .map(|cell| (column.clone(), cell.clone()))
+                        .map_err(|parent| {
+                            let keys = parent.as_object().into_iter().flat_map(|map| map.keys());
+                            let prefix = &column[..column.rfind('.').unwrap_or(0)];
+
+                            unknown_column(
+                                column,
+                                keys.map(|key| format!("{}.{}", prefix, camel_to_snake_case(key))),
+                            )
+                        })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Finds `first_name` in `{"firstName": ...}` and `vendor.id` in `{"vendor": {"id": ...}}`.
+/// Anything under a null is null, e.g. `vendor.id` for a recurring payment that has no vendor.
+/// Fails with the value that doesn't have the next segment.
+fn lookup<'a>(value: &'a Value, column: &str) -> std::result::Result<&'a Value, &'a Value> {
+    column.split('.').try_fold(value, |value, segment| {
+        if value.is_null() {
+            return Ok(value);
+        }
+
+        value
+            .as_object()
+            .and_then(|map| {
+                map.get(segment)
+                    .or_else(|| map.get(&snake_to_camel_case(segment)))
+            })
+            .ok_or(value)
+    })
+}
+
+fn unknown_column<S: ToString>(column: &str, available: impl Iterator<Item = S>) -> EquaterError {
+    let available = available
+        .map(|column| column.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    EquaterError::Validation(format!(
+        "Unknown column {} -- available columns are {}",
+        column, available
+    ))
+}
+
+fn snake_to_camel_case(column: &str) -> String {
+    let mut camel_case = String::with_capacity(column.len());
+    let mut uppercase_next = false;
+    for character in column.chars() {
+        if character == '_' {
+            uppercase_next = true;
+        } else if uppercase_next {
+            camel_case.extend(character.to_uppercase());
+            uppercase_next = false;
+        } else {
+            camel_case.push(character);
+        }
+    }
+
+    camel_case
+}
+
+fn camel_to_snake_case(key: &str) -> String {
+    let mut snake_case = String::with_capacity(key.len() + 4);
+    for character in key.chars() {
+        if character.is_uppercase() {
+            snake_case.push('_');
+            snake_case.extend(character.to_lowercase());
+        } else {
+            snake_case.push(character);
+        }
+    }
+
+    snake_case
+}
+
+/// Every top level field of a record as a title and the text a table cell would show, in the
+/// order they're serialized
+pub fn describe<T: Serialize>(item: &T) -> Result<Vec<(String, String)>> {
+    let value = serde_json::to_value(item).map_err(|err| EquaterError::Decode(err.to_string()))?;
+
+    Ok(whole_record(value)
+        .iter()
+        .map(|(key, value)| (column_title(&camel_to_snake_case(key)), cell_text(value)))
+        .collect())
+}
+
+/// Formats a duration as its two largest units, e.g. 3d 4h, 5h 12m or 12m
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, hours) => format!("{}h {}m", hours, minutes),
+        (days, hours) => format!("{}d {}h", days, hours),
+    }
+}
+
+/// `vendor.friendly_name` becomes `Vendor Friendly Name` and `ppd_id` becomes `PPD ID`
+fn column_title(column: &str) -> String {
+    column
+        .split(['_', '.'])
+        .filter(|word| !word.is_empty())
+        .map(|word| match word {
+            "id" | "uuid" | "ppd" | "api" | "url" | "sha256" | "s3" => word.to_uppercase(),
+            word => {
+                let mut characters = word.chars();
+                match characters.next() {
+                    Some(first) => first.to_uppercase().chain(characters).collect(),
+                    None => String::new(),
+                }
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Scalars print as-is, nulls print as an empty cell and nested values print as compact JSON
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Bool(_) | Value::Number(_) => value.to_string(),
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+fn to_object(record: &Record) -> Value {
+    Value::Object(record.iter().cloned().collect::<Map<String, Value>>())
+}
+
+/// Column widths depend on every row, so each batch is printed as its own table
+pub struct TableRenderer;
+
+impl Renderer for TableRenderer {
+    fn write_records(
+        &mut self,
+        columns: &[String],
+        records: &[Record],
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        let color_choice = color_choice(io::stdout().is_terminal());
+        let data: Vec<Vec<CellStruct>> = records
+            .iter()
+            .map(|record| {
+                record
+                    .iter()
+                    .map(|(_, value)| cell_text(value).cell())
+                    .collect()
+            })
+            .collect();
+        let title: Vec<CellStruct> = columns
+            .iter()
+            .map(|column| column_title(column).cell().bold(true))
+            .collect();
+
+        let table = data
+            .table()
+            .title(title)
+            .bold(true)
+            .color_choice(color_choice);
+        writeln!(out, "{}", table.display()?)?;
+
+        Ok(())
+    }
+}
+
+/// Prints the same document as `serde_json::to_string_pretty` on the whole list, one record at
+/// a time
+#[derive(Default)]
+pub struct JsonRenderer {
+    records_written: usize,
+}
+
+impl Renderer for JsonRenderer {
+    fn write_records(
+        &mut self,
+        _columns: &[String],
+        records: &[Record],
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        for record in records {
+            let json = serde_json::to_string_pretty(&to_object(record))
+                .map_err(|err| EquaterError::Io(err.into()))?;
+            let separator = if self.records_written == 0 { "[" } else { "," };
+            writeln!(out, "{}", separator)?;
+            write!(out, "  {}", json.replace('\n', "\n  "))?;
+            self.records_written += 1;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut dyn Write) -> Result<()> {
+        if self.records_written == 0 {
+            writeln!(out, "[]")?;
+        } else {
+            write!(out, "\n]\n")?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct NdjsonRenderer;
+
+impl Renderer for NdjsonRenderer {
+    fn write_records(
+        &mut self,
+        _columns: &[String],
+        records: &[Record],
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        for record in records {
+            serde_json::to_writer(&mut *out, &to_object(record))
+                .map_err(|err| EquaterError::Io(err.into()))?;
+            writeln!(out)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct CsvRenderer;
+
+impl Renderer for CsvRenderer {
+    fn begin(&mut self, columns: &[String], out: &mut dyn Write) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer
+            .write_record(columns)
+            .map_err(|err| EquaterError::Io(err.into()))?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn write_records(
+        &mut self,
+        _columns: &[String],
+        records: &[Record],
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(out);
+        for record in records {
+            writer
+                .write_record(record.iter().map(|(_, value)| cell_text(value)))
+                .map_err(|err| EquaterError::Io(err.into()))?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
+/// Consecutive YAML sequences concatenate into a single sequence
+#[derive(Default)]
+pub struct YamlRenderer {
+    records_written: usize,
+}
+
+impl Renderer for YamlRenderer {
+    fn write_records(
+        &mut self,
+        _columns: &[String],
+        records: &[Record],
+        out: &mut dyn Write,
+    ) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let objects: Vec<Value> = records.iter().map(to_object).collect();
+        serde_yaml::to_writer(out, &objects)
+            .map_err(|err| EquaterError::Io(io::Error::other(err)))?;
+        self.records_written += records.len();
+
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut dyn Write) -> Result<()> {
+        if self.records_written == 0 {
+            writeln!(out, "[]")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column_title, format_duration, Columns, OutputFormat, OutputOptions};
+    use chrono::Duration;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Row {
+        id: u32,
+        first_name: String,
+        ppd_id: Option<String>,
+        vendor: Option<Nested>,
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Nested {
+        friendly_name: String,
+    }
+
+    impl Columns for Row {
+        fn default_columns() -> &'static [&'static str] {
+            &["id", "first_name"]
+        }
+
+        fn available_columns() -> &'static [&'static str] {
+            &["id", "first_name", "ppd_id", "vendor"]
+        }
+    }
+
+    fn make_rows() -> Vec<Row> {
+        vec![
+            Row {
+                id: 1,
+                first_name: String::from("Robert"),
+                ppd_id: None,
+                vendor: Some(Nested {
+                    friendly_name: String::from("AT&T, Inc."),
+                }),
+            },
+            Row {
+                id: 2,
+                first_name: String::from("Sam"),
+                ppd_id: Some(String::from("123")),
+                vendor: Some(Nested {
+                    friendly_name: String::from("Netflix"),
+                }),
+            },
+        ]
+    }
+
+    fn render(format: OutputFormat, columns: Option<&[&str]>) -> String {
+        let options = OutputOptions {
+            format,
+            columns: columns.map(|columns| columns.iter().map(|c| c.to_string()).collect()),
+        };
+        let mut out = Vec::new();
+        options.render_to(&make_rows(), &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn it_should_print_default_columns_as_csv() {
+        assert_eq!(
+            render(OutputFormat::Csv, None),
+            "id,first_name\n1,Robert\n2,Sam\n"
+        );
+    }
+
+    #[test]
+    fn it_should_select_nested_columns() {
+        assert_eq!(
+            render(OutputFormat::Csv, Some(&["vendor.friendly_name", "ppd_id"])),
+            "vendor.friendly_name,ppd_id\n\"AT&T, Inc.\",\nNetflix,123\n"
+        );
+    }
+
+    #[test]
+    fn it_should_print_one_object_per_line_as_ndjson() {
+        assert_eq!(
+            render(OutputFormat::Ndjson, Some(&["id", "first_name"])),
+            "{\"id\":1,\"first_name\":\"Robert\"}\n{\"id\":2,\"first_name\":\"Sam\"}\n"
+        );
+    }
+
+    #[test]
+    fn it_should_print_whole_records_as_json_unless_columns_are_selected() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render(OutputFormat::Json, None)).unwrap();
+
+        assert_eq!(json[1]["ppdId"], "123");
+        assert_eq!(json[0]["vendor"]["friendlyName"], "AT&T, Inc.");
+    }
+
+    #[test]
+    fn it_should_print_yaml() {
+        assert_eq!(
+            render(OutputFormat::Yaml, Some(&["id"])),
+            "- id: 1\n- id: 2\n"
+        );
+    }
+
+    #[test]
+    fn it_should_reject_unknown_columns_before_printing_anything() {
+        let options = OutputOptions {
+            format: OutputFormat::Table,
+            columns: Some(vec![String::from("email")]),
+        };
+        let mut out = Vec::new();
+        let error = options.render_to::<Row>(&[], &mut out).unwrap_err();
+
+        assert_eq!(error.exit_code(), 2);
+        assert_eq!(
+            error.to_string(),
+            "Unknown column email -- available columns are id, first_name, ppd_id, vendor"
+        );
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn it_should_reject_unknown_nested_columns() {
+        let options = OutputOptions {
+            format: OutputFormat::Csv,
+            columns: Some(vec![String::from("vendor.name")]),
+        };
+        let error = options
+            .render_to(&make_rows(), &mut Vec::new())
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Unknown column vendor.name -- available columns are vendor.friendly_name"
+        );
+    }
+
+    #[test]
+    fn it_should_print_columns_nested_under_a_null_as_empty() {
+        let mut rows = make_rows();
+        rows[0].vendor = None;
+        let options = OutputOptions {
+            format: OutputFormat::Csv,
+            columns: Some(vec![
+                String::from("id"),
+                String::from("vendor.friendly_name"),
+            ]),
+        };
+        let mut out = Vec::new();
+        options.render_to(&rows, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "id,vendor.friendly_name\n1,\n2,Netflix\n"
+        );
+    }
+
+    #[test]
+    fn it_should_stream_the_same_json_as_a_single_batch() {
+        let rows = make_rows();
+        let options = OutputOptions {
+            format: OutputFormat::Json,
+            columns: None,
+        };
+        let mut out = Vec::new();
+        let mut stream = options.stream::<Row>(&mut out).unwrap();
+        stream.write(&rows[..1]).unwrap();
+        stream.write(&[]).unwrap();
+        stream.write(&rows[1..]).unwrap();
+        stream.finish().unwrap();
+
+        let expected = serde_json::to_string_pretty(&rows).unwrap() + "\n";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn it_should_print_an_empty_list() {
+        let options = OutputOptions {
+            format: OutputFormat::Yaml,
+            columns: None,
+        };
+        let mut out = Vec::new();
+        options.render_to::<Row>(&[], &mut out).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "[]\n");
+    }
+
+    #[test]
+    fn it_should_title_columns() {
+        assert_eq!(column_title("ppd_id"), "PPD ID");
+        assert_eq!(column_title("vendor.friendly_name"), "Vendor Friendly Name");
+    }
+
+    #[test]
+    fn it_should_format_durations() {
+        assert_eq!(format_duration(Duration::minutes(12)), "12m");
+        assert_eq!(format_duration(Duration::minutes(5 * 60 + 12)), "5h 12m");
+        assert_eq!(format_duration(Duration::hours(3 * 24 + 4)), "3d 4h");
+        assert_eq!(format_duration(Duration::minutes(-5)), "0m");
+    }
+}
@@ -0,0 +1,2 @@
+pub mod profile_command;
+mod profile_ui;
@@ -0,0 +1,131 @@
+use crate::commands::{AddProfileArgs, ProfileCommand};
+use crate::error::{EquaterError, Result};
+use crate::output::OutputOptions;
+use database::{
+    models::profile::{Profile, ProfileData},
+    repository::profile_repository::ProfileRepository,
+};
+use diesel::result::Error;
+
+use super::profile_ui::ProfileUi;
+
+const DEFAULT_PROFILE_NAME: &str = "default";
+
+pub struct ManageProfilesCommand<'a> {
+    local_repository: &'a ProfileRepository<'a>,
+}
+
+impl<'a> ManageProfilesCommand<'a> {
+    pub fn new(local_repository: &'a ProfileRepository<'a>) -> Self {
+        Self { local_repository }
+    }
+
+    pub fn execute(&self, command: &ProfileCommand, output: &OutputOptions) -> Result<String> {
+        match command {
+            ProfileCommand::Add(args) => self.add_profile(args),
+            ProfileCommand::List => self.list_profiles(output),
+            ProfileCommand::Use { name } => self
+                .local_repository
+                .set_active_profile(name)
+                .map(|profile| format!("Now using the {} profile", profile.name))
+                .map_err(|err| map_profile_error(name, err)),
+            ProfileCommand::Remove { name } => self
+                .local_repository
+                .remove_profile(name)
+                .map(|profile| format!("Removed the {} profile", profile.name))
+                .map_err(|err| map_profile_error(name, err)),
+        }
+    }
+
+    fn add_profile(&self, args: &AddProfileArgs) -> Result<String> {
+        if self
+            .local_repository
+            .find_profile_by_name(&args.name)
+            .is_ok()
+        {
+            return Err(EquaterError::Validation(format!(
+                "A profile named {} already exists",
+                args.name
+            )));
+        }
+
+        // The first profile is always active so that commands work without `profile use`
+        let is_first_profile = self
+            .local_repository
+            .find_profiles()
+            .map(|profiles| profiles.is_empty())
+            .unwrap_or(false);
+        let data = ProfileData {
+            name: args.name.clone(),
+            api_base: args.api_base.clone(),
+            is_active: args.activate || is_first_profile,
+        };
+
+        let profile = self.local_repository.create_profile(&data)?;
+
+        Ok(format!("Added the {} profile", profile.name))
+    }
+
+    fn list_profiles(&self, output: &OutputOptions) -> Result<String> {
+        let profiles = self.local_repository.find_profiles_with_users()?;
+
+        if profiles.is_empty() && output.is_table() {
+            return Ok(String::from(
+                "No profiles yet -- add one with equater profile add <name> --api-base <url>",
+            ));
+        }
+
+        ProfileUi::new(profiles).render(output)?;
+
+        Ok(String::from(""))
+    }
+}
+
+/// `--profile` takes precedence over the active profile. When there are no profiles at all a
+/// default profile is created from the api_base setting so that existing set ups keep working.
+pub fn resolve_profile(
+    local_repository: &ProfileRepository,
+    profile_name: Option<&str>,
+    default_api_base: Option<&str>,
+) -> Result<Profile> {
+    if let Some(name) = profile_name {
+        return local_repository
+            .find_profile_by_name(name)
+            .map_err(|err| map_profile_error(name, err));
+    }
+
+    match local_repository.find_active_profile() {
+        Ok(profile) => Ok(profile),
+        Err(Error::NotFound) => bootstrap_default_profile(local_repository, default_api_base),
+        Err(err) => Err(EquaterError::from(err)),
+    }
+}
+
+fn bootstrap_default_profile(
+    local_repository: &ProfileRepository,
+    default_api_base: Option<&str>,
+) -> Result<Profile> {
+    let no_active_profile =
+        "No active profile -- add one with equater profile add <name> --api-base <url> --activate";
+    let has_profiles = !local_repository.find_profiles()?.is_empty();
+
+    if has_profiles {
+        return Err(EquaterError::Configuration(String::from(no_active_profile)));
+    }
+
+    let api_base = default_api_base
+        .ok_or_else(|| EquaterError::Configuration(String::from(no_active_profile)))?;
+
+    Ok(local_repository.create_profile(&ProfileData {
+        name: String::from(DEFAULT_PROFILE_NAME),
+        api_base: String::from(api_base),
+        is_active: true,
+    })?)
+}
+
+fn map_profile_error(name: &str, err: Error) -> EquaterError {
+    match err {
+        Error::NotFound => EquaterError::Configuration(format!("No profile named {}", name)),
+        err => EquaterError::from(err),
+    }
+}
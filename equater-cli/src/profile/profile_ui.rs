@@ -0,0 +1,48 @@
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::output::{Columns, OutputOptions};
+use database::models::{profile::Profile, user::LocalUser};
+
+/// A profile along with the user that's signed in to it
+#[derive(Serialize)]
+struct ProfileRow {
+    active: bool,
+    name: String,
+    api_base: String,
+    signed_in_as: Option<String>,
+}
+
+impl Columns for ProfileRow {
+    fn default_columns() -> &'static [&'static str] {
+        &["active", "name", "api_base", "signed_in_as"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["active", "name", "api_base", "signed_in_as"]
+    }
+}
+
+pub struct ProfileUi {
+    items: Vec<ProfileRow>,
+}
+
+impl ProfileUi {
+    pub fn new(profiles: Vec<(Profile, Option<LocalUser>)>) -> ProfileUi {
+        let items = profiles
+            .into_iter()
+            .map(|(profile, user)| ProfileRow {
+                active: profile.is_active,
+                name: profile.name,
+                api_base: profile.api_base,
+                signed_in_as: user.map(|user| user.email),
+            })
+            .collect();
+
+        Self { items }
+    }
+
+    pub fn render(&self, output: &OutputOptions) -> Result<()> {
+        output.render(&self.items)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod review_vendors_api;
+#[cfg(test)]
+pub mod review_vendors_api_fake;
+pub mod review_vendors_command;
+mod review_vendors_ui;
@@ -0,0 +1,42 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::transaction::TransactionWithContext;
+use crate::vendor::{PatchVendorDto, SingleVendorResponse, Vendor};
+use async_trait::async_trait;
+
+use super::review_vendors_command::ReviewsVendors;
+
+pub struct ReviewVendorsApi {
+    client: EquaterClient,
+}
+
+impl ReviewVendorsApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl ReviewsVendors for ReviewVendorsApi {
+    async fn fetch_transactions_for_vendor(
+        &self,
+        vendor_id: u32,
+    ) -> Result<Vec<TransactionWithContext>> {
+        self.client
+            .get(&format!("/api/transaction/vendor/{}", vendor_id))
+            .send_json::<Vec<TransactionWithContext>>()
+            .await
+    }
+
+    async fn patch_vendor(&self, vendor_id: u32, dto: &PatchVendorDto) -> Result<Vendor> {
+        let response = self
+            .client
+            .patch(&format!("/api/vendor/{}", vendor_id))
+            .json(dto)
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
+}
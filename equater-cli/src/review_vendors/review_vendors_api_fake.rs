@@ -0,0 +1,50 @@
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use fake::{Fake, Faker};
+
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+use crate::transaction::TransactionWithContext;
+use crate::vendor::{PatchVendorDto, Vendor};
+
+use super::review_vendors_command::ReviewsVendors;
+
+#[derive(Default)]
+pub struct ReviewVendorsApiFake {
+    pub should_error: bool,
+    /// Patching this vendor fails, while every other request succeeds
+    pub rejected_vendor_id: Option<u32>,
+    /// Every patch that was sent, in order
+    pub patches: Mutex<Vec<(u32, PatchVendorDto)>>,
+}
+
+#[async_trait]
+impl ReviewsVendors for ReviewVendorsApiFake {
+    async fn fetch_transactions_for_vendor(
+        &self,
+        _vendor_id: u32,
+    ) -> Result<Vec<TransactionWithContext>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        Ok(Faker.fake())
+    }
+
+    async fn patch_vendor(&self, vendor_id: u32, dto: &PatchVendorDto) -> Result<Vendor> {
+        if self.should_error || self.rejected_vendor_id == Some(vendor_id) {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        self.patches.lock().unwrap().push((vendor_id, dto.clone()));
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = vendor_id;
+        vendor.friendly_name = dto.friendly_name.clone();
+        vendor.ppd_id = dto.ppd_id.clone();
+        vendor.vendor_identity_cannot_be_determined = dto.vendor_identity_cannot_be_determined;
+        vendor.has_been_reviewed_internally = true;
+
+        Ok(vendor)
+    }
+}
@@ -0,0 +1,313 @@
+use crate::{
+    commands::ReviewVendorsArgs,
+    error::Result,
+    fetch_vendors::fetch_vendors_command::FetchesVendors,
+    transaction::TransactionWithContext,
+    vendor::{PatchVendorDto, Vendor},
+};
+use async_trait::async_trait;
+use database::{
+    models::vendor_review::{VendorReview, STATUS_REVIEWED, STATUS_SKIPPED},
+    repository::vendor_review_repository::VendorReviewRepository,
+};
+
+use super::review_vendors_ui::ReviewVendorsPrompt;
+
+#[async_trait]
+pub trait ReviewsVendors {
+    async fn fetch_transactions_for_vendor(
+        &self,
+        vendor_id: u32,
+    ) -> Result<Vec<TransactionWithContext>>;
+    async fn patch_vendor(&self, vendor_id: u32, dto: &PatchVendorDto) -> Result<Vendor>;
+}
+
+pub enum ReviewDecision {
+    Patch(PatchVendorDto),
+    Skip,
+    Quit,
+}
+
+/// Decides what happens to each vendor in the queue. `ReviewVendorsPrompt` shows the vendor and
+/// asks the operator.
+pub trait DecidesReviews {
+    fn decide(
+        &self,
+        vendor: &Vendor,
+        transactions: &[TransactionWithContext],
+        position: usize,
+        total: usize,
+    ) -> Result<ReviewDecision>;
+}
+
+/// Steps through the vendors that require internal review one at a time. Every decision is
+/// recorded locally so that quitting part way through picks back up on the next vendor.
+pub struct ReviewVendorsCommand<'a, F: FetchesVendors, R: ReviewsVendors, D: DecidesReviews> {
+    vendor_repository: F,
+    remote_repository: R,
+    local_repository: &'a VendorReviewRepository<'a>,
+    decisions: D,
+}
+
+impl<'a, F: FetchesVendors, R: ReviewsVendors> ReviewVendorsCommand<'a, F, R, ReviewVendorsPrompt> {
+    pub fn new(
+        vendor_repository: F,
+        remote_repository: R,
+        local_repository: &'a VendorReviewRepository<'a>,
+    ) -> Self {
+        Self {
+            vendor_repository,
+            remote_repository,
+            local_repository,
+            decisions: ReviewVendorsPrompt,
+        }
+    }
+}
+
+impl<'a, F: FetchesVendors, R: ReviewsVendors, D: DecidesReviews>
+    ReviewVendorsCommand<'a, F, R, D>
+{
+    /// Takes decisions from somewhere other than the terminal
+    #[cfg(test)]
+    pub fn with_decisions<E: DecidesReviews>(
+        self,
+        decisions: E,
+    ) -> ReviewVendorsCommand<'a, F, R, E> {
+        ReviewVendorsCommand {
+            vendor_repository: self.vendor_repository,
+            remote_repository: self.remote_repository,
+            local_repository: self.local_repository,
+            decisions,
+        }
+    }
+
+    pub async fn review_vendors(&self, args: &ReviewVendorsArgs) -> Result<String> {
+        if args.restart {
+            self.local_repository.clear()?;
+        }
+
+        let queue = self.find_vendors_left_to_review().await?;
+        if queue.is_empty() {
+            self.local_repository.clear()?;
+            return Ok(String::from("No vendors left to review"));
+        }
+
+        let mut reviewed = 0;
+        let mut skipped = 0;
+        for (index, vendor) in queue.iter().enumerate() {
+            let mut transactions = self
+                .remote_repository
+                .fetch_transactions_for_vendor(vendor.id)
+                .await?;
+            transactions.sort_by(|a, b| b.transaction.date.cmp(&a.transaction.date));
+            transactions.truncate(args.transactions);
+
+            let decision = self
+                .decisions
+                .decide(vendor, &transactions, index + 1, queue.len())?;
+
+            match decision {
+                ReviewDecision::Patch(dto) => {
+                    let patched = self.remote_repository.patch_vendor(vendor.id, &dto).await?;
+                    self.local_repository
+                        .record_review(vendor.id as i32, STATUS_REVIEWED)?;
+                    println!("Saved {}", patched.friendly_name);
+                    reviewed += 1;
+                }
+                ReviewDecision::Skip => {
+                    self.local_repository
+                        .record_review(vendor.id as i32, STATUS_SKIPPED)?;
+                    skipped += 1;
+                }
+                ReviewDecision::Quit => {
+                    return Ok(format!(
+                        "Reviewed {} and skipped {} vendor(s). Progress is saved -- run equater review vendors to continue",
+                        reviewed, skipped
+                    ));
+                }
+            }
+        }
+
+        // Once the queue is exhausted start over, so skipped vendors come back around next time
+        self.local_repository.clear()?;
+
+        Ok(format!(
+            "Review complete -- reviewed {} and skipped {} vendor(s)",
+            reviewed, skipped
+        ))
+    }
+
+    async fn find_vendors_left_to_review(&self) -> Result<Vec<Vendor>> {
+        let reviews = self.local_repository.find_reviews()?;
+        let vendors = self
+            .vendor_repository
+            .fetch_vendors_that_require_review()
+            .await?;
+
+        Ok(remove_reviewed_vendors(vendors, &reviews))
+    }
+}
+
+/// Leaves out vendors that were reviewed or skipped earlier in the current pass through the queue
+pub fn remove_reviewed_vendors(vendors: Vec<Vendor>, reviews: &[VendorReview]) -> Vec<Vendor> {
+    vendors
+        .into_iter()
+        .filter(|vendor| {
+            !reviews
+                .iter()
+                .any(|review| review.vendor_id == vendor.id as i32)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    use super::{remove_reviewed_vendors, DecidesReviews, ReviewDecision, ReviewVendorsCommand};
+    use crate::commands::ReviewVendorsArgs;
+    use crate::error::Result;
+    use crate::fetch_vendors::fetch_vendors_api_fake::FetchVendorsApiFake;
+    use crate::review_vendors::review_vendors_api_fake::ReviewVendorsApiFake;
+    use crate::test_common::{connect_to_test_database, make_test_profile};
+    use crate::transaction::TransactionWithContext;
+    use crate::vendor::{PatchVendorDto, Vendor};
+    use database::models::vendor_review::{VendorReview, STATUS_REVIEWED, STATUS_SKIPPED};
+    use database::repository::vendor_review_repository::VendorReviewRepository;
+    use diesel::SqliteConnection;
+    use fake::{Fake, Faker};
+    use serial_test::serial;
+
+    /// Answers for the operator, one vendor at a time
+    struct ScriptedDecisions(Mutex<VecDeque<ReviewDecision>>);
+
+    impl ScriptedDecisions {
+        fn new(decisions: Vec<ReviewDecision>) -> Self {
+            Self(Mutex::new(decisions.into()))
+        }
+    }
+
+    impl DecidesReviews for ScriptedDecisions {
+        fn decide(
+            &self,
+            _vendor: &Vendor,
+            _transactions: &[TransactionWithContext],
+            _position: usize,
+            _total: usize,
+        ) -> Result<ReviewDecision> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or(ReviewDecision::Quit))
+        }
+    }
+
+    fn make_local_repository(connection: &SqliteConnection) -> VendorReviewRepository<'_> {
+        let profile = make_test_profile(connection);
+
+        VendorReviewRepository::new(connection, profile.get_id())
+    }
+
+    fn make_args() -> ReviewVendorsArgs {
+        ReviewVendorsArgs {
+            restart: true,
+            transactions: 10,
+        }
+    }
+
+    fn make_patch(friendly_name: &str) -> PatchVendorDto {
+        PatchVendorDto {
+            friendly_name: String::from(friendly_name),
+            pre_processed_logo_was_uploaded: false,
+            ppd_id: None,
+            vendor_identity_cannot_be_determined: false,
+        }
+    }
+
+    #[test]
+    fn it_should_resume_after_the_last_recorded_vendor() {
+        let vendors: Vec<Vendor> = (1..=3)
+            .map(|id| {
+                let mut vendor: Vendor = Faker.fake();
+                vendor.id = id;
+                vendor
+            })
+            .collect();
+        let reviews = vec![VendorReview {
+            id: 1,
+            profile_id: 1,
+            vendor_id: 1,
+            status: String::from(STATUS_SKIPPED),
+            date_time_reviewed: String::from("2022-05-14 17:53:16"),
+        }];
+
+        let remaining = remove_reviewed_vendors(vendors, &reviews);
+        let ids: Vec<u32> = remaining.iter().map(|vendor| vendor.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn it_should_send_the_patch_and_save_progress() {
+        let connection = connect_to_test_database().get().unwrap();
+        let local_repository = make_local_repository(&connection);
+        let dto = make_patch("Icon Central Apartments");
+        let command = ReviewVendorsCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            ReviewVendorsApiFake::default(),
+            &local_repository,
+        )
+        .with_decisions(ScriptedDecisions::new(vec![
+            ReviewDecision::Patch(dto.clone()),
+            ReviewDecision::Quit,
+        ]));
+
+        let message = command.review_vendors(&make_args()).await.unwrap();
+        let reviews = local_repository.find_reviews().unwrap();
+
+        assert_eq!(
+            *command.remote_repository.patches.lock().unwrap(),
+            vec![(101, dto)]
+        );
+        assert!(message.starts_with("Reviewed 1 and skipped 0 vendor(s)"));
+        assert_eq!(
+            reviews
+                .iter()
+                .map(|review| (review.vendor_id, review.status.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(101, STATUS_REVIEWED)]
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn it_should_stop_without_saving_progress_when_the_patch_fails() {
+        let connection = connect_to_test_database().get().unwrap();
+        let local_repository = make_local_repository(&connection);
+        let command = ReviewVendorsCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            ReviewVendorsApiFake {
+                rejected_vendor_id: Some(101),
+                ..Default::default()
+            },
+            &local_repository,
+        )
+        .with_decisions(ScriptedDecisions::new(vec![
+            ReviewDecision::Patch(make_patch("Icon Central Apartments")),
+            ReviewDecision::Skip,
+        ]));
+
+        let result = command.review_vendors(&make_args()).await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+        assert!(command.remote_repository.patches.lock().unwrap().is_empty());
+        assert!(local_repository.find_reviews().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use cli_table::{print_stdout, Cell, CellStruct, Style, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+
+use super::review_vendors_command::{DecidesReviews, ReviewDecision};
+use crate::transaction::{format_cents, TransactionWithContext};
+use crate::vendor::{PatchVendorDto, Vendor};
+use std::io::Result;
+
+/// Asks the operator at the terminal
+pub struct ReviewVendorsPrompt;
+
+impl DecidesReviews for ReviewVendorsPrompt {
+    fn decide(
+        &self,
+        vendor: &Vendor,
+        transactions: &[TransactionWithContext],
+        position: usize,
+        total: usize,
+    ) -> crate::error::Result<ReviewDecision> {
+        let ui = ReviewVendorsUi::new(vendor, transactions);
+        ui.render(position, total)?;
+
+        Ok(ui.prompt_decision()?)
+    }
+}
+
+/// Shows a single vendor from the review queue along with its most recent transactions
+pub struct ReviewVendorsUi<'a> {
+    vendor: &'a Vendor,
+    transactions: &'a [TransactionWithContext],
+}
+
+impl<'a> ReviewVendorsUi<'a> {
+    pub fn new(vendor: &'a Vendor, transactions: &'a [TransactionWithContext]) -> Self {
+        Self {
+            vendor,
+            transactions,
+        }
+    }
+
+    pub fn render(&self, position: usize, total: usize) -> Result<()> {
+        println!();
+        println!(
+            "Vendor {} of {}: {}",
+            position, total, self.vendor.friendly_name
+        );
+        self.render_vendor()?;
+
+        if self.transactions.is_empty() {
+            println!("No transactions have been matched to this vendor yet");
+            return Ok(());
+        }
+
+        self.render_transactions()
+    }
+
+    pub fn prompt_decision(&self) -> Result<ReviewDecision> {
+        loop {
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("What would you like to do?")
+                .items(&[
+                    "Edit and mark as reviewed",
+                    "Skip",
+                    "Save progress and quit",
+                ])
+                .default(0)
+                .interact()?;
+
+            match selection {
+                0 => {
+                    let dto = self.prompt_patch()?;
+                    let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt(format!("Save {}?", dto.friendly_name))
+                        .default(true)
+                        .interact()?;
+
+                    if confirmed {
+                        return Ok(ReviewDecision::Patch(dto));
+                    }
+                }
+                1 => return Ok(ReviewDecision::Skip),
+                _ => return Ok(ReviewDecision::Quit),
+            }
+        }
+    }
+
+    fn prompt_patch(&self) -> Result<PatchVendorDto> {
+        let current = PatchVendorDto::from(self.vendor);
+        let friendly_name: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("Friendly name")
+            .with_initial_text(current.friendly_name)
+            .interact_text()?;
+        let ppd_id: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt("PPD ID (leave blank for none)")
+            .with_initial_text(current.ppd_id.unwrap_or_default())
+            .allow_empty(true)
+            .interact_text()?;
+        let vendor_identity_cannot_be_determined = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Vendor identity cannot be determined?")
+            .default(current.vendor_identity_cannot_be_determined)
+            .interact()?;
+        let pre_processed_logo_was_uploaded = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Was a logo uploaded for processing?")
+            .default(current.pre_processed_logo_was_uploaded)
+            .interact()?;
+
+        Ok(PatchVendorDto {
+            friendly_name: friendly_name.trim().to_string(),
+            pre_processed_logo_was_uploaded,
+            ppd_id: Some(ppd_id.trim().to_string()).filter(|ppd_id| !ppd_id.is_empty()),
+            vendor_identity_cannot_be_determined,
+        })
+    }
+
+    fn render_vendor(&self) -> Result<()> {
+        let vendor = self.vendor;
+        let data: Vec<Vec<CellStruct>> = vec![
+            vec!["ID".cell(), vendor.id.cell()],
+            vec!["UUID".cell(), vendor.uuid.clone().cell()],
+            vec![
+                "PPD ID".cell(),
+                vendor.ppd_id.clone().unwrap_or_default().cell(),
+            ],
+            vec![
+                "Added".cell(),
+                vendor.date_time_added.clone().unwrap_or_default().cell(),
+            ],
+            vec![
+                "Bills Split".cell(),
+                vendor.total_number_of_expense_sharing_agreements.cell(),
+            ],
+            vec![
+                "Identity Can't Be Determined".cell(),
+                vendor.vendor_identity_cannot_be_determined.cell(),
+            ],
+            vec!["Logo Uploaded".cell(), vendor.logo_upload_completed.cell()],
+        ];
+
+        print_stdout(data.table().bold(true))
+    }
+
+    fn render_transactions(&self) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = self
+            .transactions
+            .iter()
+            .map(|context| self.transaction_to_cell(context))
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "Date".cell().bold(true),
+                "Name".cell().bold(true),
+                "Amount".cell().bold(true),
+                "PPD ID".cell().bold(true),
+                "User".cell().bold(true),
+                "Account".cell().bold(true),
+            ])
+            .bold(true);
+
+        print_stdout(table)
+    }
+
+    fn transaction_to_cell(&self, context: &TransactionWithContext) -> Vec<CellStruct> {
+        let transaction = &context.transaction;
+
+        vec![
+            transaction.date.clone().cell(),
+            transaction.display_name().cell(),
+            format_cents(transaction.amount).cell(),
+            transaction.ppd_id.clone().unwrap_or_default().cell(),
+            context.user.email.clone().cell(),
+            context.account.account_name.clone().cell(),
+        ]
+    }
+}
@@ -0,0 +1,122 @@
+use database::models::shared_expense::{
+    SharedExpense, SharedExpenseTransaction, SharedExpenseUserAgreement, UserInvite,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::output::Columns;
+use crate::user::User;
+use crate::vendor::Vendor;
+
+/// Everything needed to describe a shared expense: the expense itself, who's involved and which
+/// vendor it's matched against
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedExpenseStory {
+    pub shared_expense: SharedExpense,
+    pub agreements: Vec<SharedExpenseUserAgreement>,
+    /// Null for recurring payments, which aren't tied to a vendor
+    pub vendor: Option<Vendor>,
+    pub initiating_user: User,
+    pub active_users: Vec<User>,
+    pub prospective_users: Vec<UserInvite>,
+}
+
+/// A shared expense story told from the point of view of one of its agreements
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAgreementStory {
+    #[serde(flatten)]
+    pub story: SharedExpenseStory,
+    pub user_agreement: SharedExpenseUserAgreement,
+}
+
+impl SharedExpenseStory {
+    /// Looks a user up among the owner and the users that have accepted an agreement
+    pub fn find_user(&self, user_id: u32) -> Option<&User> {
+        self.active_users
+            .iter()
+            .chain(std::iter::once(&self.initiating_user))
+            .find(|user| user.id == user_id)
+    }
+}
+
+impl UserAgreementStory {
+    /// The user who signed the agreement, if they're part of the story
+    pub fn agreement_user(&self) -> Option<&User> {
+        self.story.find_user(self.user_agreement.user_id)
+    }
+}
+
+/// Response of GET /api/expense/ops/agreement-watchlist. Both lists hold agreements that have
+/// never been matched to a transaction.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgreementWatchlist {
+    pub new_agreements: Vec<UserAgreementStory>,
+    /// Agreements for vendors that have never been matched to any shared bill transaction. These
+    /// are the most likely to need a vendor association or a fix to the vendor.
+    pub new_agreements_with_new_vendors: Vec<UserAgreementStory>,
+}
+
+/// Everything needed to describe a payment between two users. Response item of
+/// GET /api/expense/user/transactions/:userId
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStory {
+    pub transaction: SharedExpenseTransaction,
+    pub vendor: Option<Vendor>,
+    pub payer: User,
+    pub recipient: User,
+    pub shared_expense: SharedExpense,
+    pub shared_expense_agreement: SharedExpenseUserAgreement,
+}
+
+impl TransactionStory {
+    /// Where the money is, e.g. Transferred, failed or Initiated
+    pub fn status(&self) -> String {
+        let transaction = &self.transaction;
+
+        if transaction.has_been_transferred_to_destination {
+            String::from("Transferred")
+        } else {
+            transaction
+                .dwolla_status
+                .clone()
+                .unwrap_or_else(|| String::from("Initiated"))
+        }
+    }
+}
+
+impl Columns for TransactionStory {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "transaction.id",
+            "payer.email",
+            "recipient.email",
+            "transaction.total_transaction_amount",
+            "transaction.dwolla_status",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "transaction",
+            "vendor",
+            "payer",
+            "recipient",
+            "shared_expense",
+            "shared_expense_agreement",
+        ]
+    }
+}
+
+/// Active, Pending or Inactive
+pub fn status_label(is_active: bool, is_pending: bool) -> &'static str {
+    if is_pending {
+        "Pending"
+    } else if is_active {
+        "Active"
+    } else {
+        "Inactive"
+    }
+}
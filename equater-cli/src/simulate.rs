@@ -0,0 +1,5 @@
+pub mod simulate_api;
+#[cfg(test)]
+mod simulate_api_fake;
+pub mod simulate_command;
+mod simulate_ui;
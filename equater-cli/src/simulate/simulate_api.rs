@@ -0,0 +1,71 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::transaction::{
+    SimulatedArbitraryTransactionDto, SimulatedTransactionDto, TransactionWithContext,
+};
+use crate::vendor::{Vendor, VendorResponse};
+use async_trait::async_trait;
+
+use super::simulate_command::SimulatesTransactions;
+
+pub struct SimulateApi {
+    client: EquaterClient,
+}
+
+impl SimulateApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl SimulatesTransactions for SimulateApi {
+    async fn simulate_transaction(&self, dto: &SimulatedArbitraryTransactionDto) -> Result<()> {
+        self.client
+            .put("/api/expense/ops/transaction/simulate")
+            .json(dto)
+            .send_empty()
+            .await
+    }
+
+    async fn search_vendors(&self, search_term: &str) -> Result<Vec<Vendor>> {
+        let mut vendors = vec![];
+        for requiring_internal_review in ["false", "true"] {
+            let response = self
+                .client
+                .get("/api/vendor/search")
+                .query(&[
+                    ("searchTerm", search_term),
+                    ("requiringInternalReview", requiring_internal_review),
+                ])
+                .send_json::<VendorResponse>()
+                .await?;
+            vendors.extend(response.vendors);
+        }
+
+        Ok(vendors)
+    }
+
+    async fn fetch_transactions_for_vendor(
+        &self,
+        vendor_id: u32,
+    ) -> Result<Vec<TransactionWithContext>> {
+        self.client
+            .get(&format!("/api/transaction/vendor/{}", vendor_id))
+            .send_json::<Vec<TransactionWithContext>>()
+            .await
+    }
+
+    async fn simulate_expense(
+        &self,
+        shared_expense_id: u32,
+        dto: &SimulatedTransactionDto,
+    ) -> Result<()> {
+        self.client
+            .post(&format!("/api/expense/ops/{}/simulate", shared_expense_id))
+            .json(dto)
+            .send_empty()
+            .await
+    }
+}
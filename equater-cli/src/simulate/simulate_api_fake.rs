@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use async_trait::async_trait;
+use fake::{Fake, Faker};
+
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+use crate::transaction::{
+    SimulatedArbitraryTransactionDto, SimulatedTransactionDto, TransactionWithContext,
+};
+use crate::vendor::Vendor;
+
+use super::simulate_command::SimulatesTransactions;
+
+/// Every search finds vendor 12 except searches for "UNKNOWN ...". The vendor has a NETFLIX.COM
+/// transaction 600 on account 3, and once a transaction has been simulated it also has a
+/// NETFLIX.COM transaction 601 on account 3.
+#[derive(Default)]
+pub struct SimulateApiFake {
+    pub should_error: bool,
+    pub simulated: AtomicBool,
+}
+
+#[async_trait]
+impl SimulatesTransactions for SimulateApiFake {
+    async fn simulate_transaction(&self, _dto: &SimulatedArbitraryTransactionDto) -> Result<()> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        self.simulated.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    async fn search_vendors(&self, search_term: &str) -> Result<Vec<Vendor>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        if search_term.starts_with("UNKNOWN") {
+            return Ok(vec![]);
+        }
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = 12;
+        vendor.friendly_name = String::from("Netflix");
+
+        Ok(vec![vendor])
+    }
+
+    async fn fetch_transactions_for_vendor(
+        &self,
+        vendor_id: u32,
+    ) -> Result<Vec<TransactionWithContext>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let mut ids = vec![600];
+        if self.simulated.load(Ordering::SeqCst) {
+            ids.push(601);
+        }
+
+        Ok(ids
+            .into_iter()
+            .map(|id| {
+                let mut context: TransactionWithContext = Faker.fake();
+                context.transaction.id = id;
+                context.transaction.account_id = 3;
+                context.transaction.unique_vendor_id = vendor_id;
+                context.transaction.transaction_name = Some(String::from("NETFLIX.COM"));
+                context.transaction.amount = 1599;
+
+                context
+            })
+            .collect())
+    }
+
+    async fn simulate_expense(
+        &self,
+        _shared_expense_id: u32,
+        _dto: &SimulatedTransactionDto,
+    ) -> Result<()> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        Ok(())
+    }
+}
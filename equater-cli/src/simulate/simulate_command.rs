@@ -0,0 +1,531 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use database::models::money::Money;
+use database::models::shared_expense::SharedExpenseType;
+use serde::Serialize;
+
+use crate::commands::{SimulateExpenseArgs, SimulateTransactionArgs};
+use crate::environment::environment_guard::ApiEnvironment;
+use crate::error::{EquaterError, Result};
+use crate::inspect_expenses::inspect_expenses_command::InspectsExpenses;
+use crate::output::{Columns, OutputOptions};
+use crate::shared_expense::TransactionStory;
+use crate::transaction::{
+    SimulatedArbitraryTransactionDto, SimulatedTransactionDto, Transaction, TransactionWithContext,
+};
+use crate::vendor::Vendor;
+
+use super::simulate_ui::SimulateUi;
+
+/// Simulated transactions are processed in the background, so the API is checked this many
+/// times before giving up
+const POLL_ATTEMPTS: u32 = 10;
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[async_trait]
+pub trait SimulatesTransactions {
+    async fn simulate_transaction(&self, dto: &SimulatedArbitraryTransactionDto) -> Result<()>;
+    /// Searches vendors whether or not they've been reviewed, since a simulated transaction from
+    /// an unknown merchant creates a vendor that needs review
+    async fn search_vendors(&self, search_term: &str) -> Result<Vec<Vendor>>;
+    async fn fetch_transactions_for_vendor(
+        &self,
+        vendor_id: u32,
+    ) -> Result<Vec<TransactionWithContext>>;
+    async fn simulate_expense(
+        &self,
+        shared_expense_id: u32,
+        dto: &SimulatedTransactionDto,
+    ) -> Result<()>;
+}
+
+/// The Plaid transaction that was stored by `simulate transaction`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTransaction {
+    pub server_environment: String,
+    pub id: u32,
+    pub account_id: u32,
+    pub vendor_id: u32,
+    pub vendor_name: String,
+    pub transaction_name: Option<String>,
+    pub merchant_name: Option<String>,
+    pub amount: Money,
+    pub ppd_id: Option<String>,
+    /// ISO8601 String
+    pub date_time_captured: String,
+}
+
+impl SimulatedTransaction {
+    fn new(server_environment: String, vendor_name: String, transaction: Transaction) -> Self {
+        Self {
+            server_environment,
+            id: transaction.id,
+            account_id: transaction.account_id,
+            vendor_id: transaction.unique_vendor_id,
+            vendor_name,
+            transaction_name: transaction.transaction_name,
+            merchant_name: transaction.merchant_name,
+            amount: Money::from_cents(transaction.amount),
+            ppd_id: transaction.ppd_id,
+            date_time_captured: transaction.date_time_captured,
+        }
+    }
+}
+
+impl Columns for SimulatedTransaction {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "server_environment",
+            "id",
+            "account_id",
+            "vendor_name",
+            "transaction_name",
+            "merchant_name",
+            "amount",
+            "ppd_id",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "server_environment",
+            "id",
+            "account_id",
+            "vendor_id",
+            "vendor_name",
+            "transaction_name",
+            "merchant_name",
+            "amount",
+            "ppd_id",
+            "date_time_captured",
+        ]
+    }
+}
+
+/// Dev tools for QA. The API only accepts simulations on development and staging servers, and
+/// the CLI checks the same thing up front so that production is never sent a simulation.
+pub struct SimulateTransactionsCommand<S: SimulatesTransactions, I: InspectsExpenses> {
+    remote_repository: S,
+    expense_repository: I,
+    /// The server `remote_repository` points at
+    environment: ApiEnvironment,
+    poll_interval: Duration,
+}
+
+impl<S: SimulatesTransactions, I: InspectsExpenses> SimulateTransactionsCommand<S, I> {
+    /// `poll_interval` is how long to wait between checks for the transactions a simulation
+    /// created, normally `POLL_INTERVAL`
+    pub fn new(
+        remote_repository: S,
+        expense_repository: I,
+        environment: ApiEnvironment,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            remote_repository,
+            expense_repository,
+            environment,
+            poll_interval,
+        }
+    }
+
+    /// The stored transaction is found through its vendor, which the API matches or creates
+    /// from the merchant name, so that what's shown is what the API actually stored
+    pub async fn simulate_transaction(
+        &self,
+        args: &SimulateTransactionArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let dto = make_transaction_dto(args)?;
+        self.ensure_simulation_is_allowed()?;
+
+        let existing: HashSet<u32> = self
+            .fetch_candidate_transactions(&dto)
+            .await?
+            .iter()
+            .map(|(_, context)| context.transaction.id)
+            .collect();
+        self.remote_repository.simulate_transaction(&dto).await?;
+
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(self.poll_interval).await;
+            let stored = self
+                .fetch_candidate_transactions(&dto)
+                .await?
+                .into_iter()
+                .find(|(_, context)| {
+                    is_simulated_transaction(&context.transaction, &dto, &existing)
+                });
+
+            if let Some((vendor_name, context)) = stored {
+                let simulated = SimulatedTransaction::new(
+                    self.environment.server_name(),
+                    vendor_name,
+                    context.transaction,
+                );
+                SimulateUi::new(output).render_transaction(&simulated)?;
+
+                return Ok(String::from(""));
+            }
+        }
+
+        Ok(format!(
+            "The simulation was accepted, but the transaction hasn't shown up yet. Check again with equater fetch vendors --search \"{}\" or --review-required",
+            dto.merchant_name
+        ))
+    }
+
+    /// Transactions of every vendor the simulated transaction could be stored under, along with
+    /// the vendor's name
+    async fn fetch_candidate_transactions(
+        &self,
+        dto: &SimulatedArbitraryTransactionDto,
+    ) -> Result<Vec<(String, TransactionWithContext)>> {
+        let mut vendors: Vec<Vendor> = vec![];
+        let mut vendor_ids = HashSet::new();
+        for search_term in [&dto.merchant_name, &dto.transaction_name] {
+            for vendor in self.remote_repository.search_vendors(search_term).await? {
+                if vendor_ids.insert(vendor.id) {
+                    vendors.push(vendor);
+                }
+            }
+        }
+
+        let mut transactions = vec![];
+        for vendor in vendors {
+            for context in self
+                .remote_repository
+                .fetch_transactions_for_vendor(vendor.id)
+                .await?
+            {
+                transactions.push((vendor.friendly_name.clone(), context));
+            }
+        }
+
+        Ok(transactions)
+    }
+
+    pub async fn simulate_expense(
+        &self,
+        args: &SimulateExpenseArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let shared_expense = self
+            .expense_repository
+            .fetch_shared_expense(args.id)
+            .await?;
+        if !shared_expense.is_active {
+            return Err(EquaterError::Validation(format!(
+                "{} (shared expense {}) isn't active, so a simulated charge wouldn't be split",
+                shared_expense.expense_nick_name, shared_expense.id
+            )));
+        }
+        let dto = make_expense_dto(shared_expense.shared_expense_type, args.amount)?;
+        self.ensure_simulation_is_allowed()?;
+
+        let owner_id = shared_expense.expense_owner_user_id;
+        let existing: HashSet<u32> = self
+            .expense_repository
+            .fetch_transactions_for_user(owner_id)
+            .await?
+            .iter()
+            .map(|story| story.transaction.id)
+            .collect();
+        self.remote_repository
+            .simulate_expense(args.id, &dto)
+            .await?;
+
+        for _ in 0..POLL_ATTEMPTS {
+            tokio::time::sleep(self.poll_interval).await;
+            let transactions = self
+                .expense_repository
+                .fetch_transactions_for_user(owner_id)
+                .await?;
+            let created = find_new_transactions(transactions, &existing, args.id);
+
+            if !created.is_empty() {
+                SimulateUi::new(output).render_expense_transactions(&created)?;
+                return Ok(String::from(""));
+            }
+        }
+
+        Ok(format!(
+            "The simulation was accepted, but no transaction has shown up yet. Transactions are created in the background -- check again with equater expense show {}",
+            args.id
+        ))
+    }
+
+    fn ensure_simulation_is_allowed(&self) -> Result<()> {
+        if !self.environment.allows_simulation() {
+            return Err(EquaterError::Configuration(format!(
+                "Simulations only run against development and staging servers, and this server is {}",
+                self.environment.server_name()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+fn make_transaction_dto(
+    args: &SimulateTransactionArgs,
+) -> Result<SimulatedArbitraryTransactionDto> {
+    let transaction_name = args.name.trim().to_string();
+    if transaction_name.is_empty() {
+        return Err(EquaterError::Validation(String::from(
+            "--name can't be blank",
+        )));
+    }
+    if args.amount == Money::ZERO {
+        return Err(EquaterError::Validation(String::from(
+            "--amount can't be zero",
+        )));
+    }
+
+    let merchant_name = args
+        .merchant
+        .as_deref()
+        .map(str::trim)
+        .filter(|merchant| !merchant.is_empty())
+        .unwrap_or(&transaction_name)
+        .to_string();
+
+    Ok(SimulatedArbitraryTransactionDto {
+        amount: args.amount.dollars(),
+        transaction_name,
+        merchant_name,
+        account_id: args.account,
+        ppd_id: args
+            .ppd_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|ppd_id| !ppd_id.is_empty())
+            .map(String::from),
+    })
+}
+
+/// The API silently does nothing for a shared bill without an amount, so that's caught here
+fn make_expense_dto(
+    shared_expense_type: SharedExpenseType,
+    amount: Option<Money>,
+) -> Result<SimulatedTransactionDto> {
+    match (shared_expense_type, amount) {
+        (SharedExpenseType::SharedBill, None) => Err(EquaterError::Validation(String::from(
+            "--amount is required to simulate a charge to a shared bill",
+        ))),
+        (SharedExpenseType::SharedBill, Some(amount)) if amount <= Money::ZERO => Err(
+            EquaterError::Validation(String::from("--amount must be more than $0.00")),
+        ),
+        (SharedExpenseType::SharedBill, Some(amount)) => Ok(SimulatedTransactionDto {
+            amount: amount.dollars(),
+        }),
+        (SharedExpenseType::RecurringPayment, Some(_)) => Err(EquaterError::Validation(
+            String::from(
+                "Recurring payments charge each agreement's contribution, so --amount can't be used",
+            ),
+        )),
+        (SharedExpenseType::RecurringPayment, None) => Ok(SimulatedTransactionDto { amount: 0.0 }),
+    }
+}
+
+fn is_simulated_transaction(
+    transaction: &Transaction,
+    dto: &SimulatedArbitraryTransactionDto,
+    existing: &HashSet<u32>,
+) -> bool {
+    transaction.account_id == dto.account_id
+        && transaction.transaction_name.as_deref() == Some(dto.transaction_name.as_str())
+        && !existing.contains(&transaction.id)
+}
+
+fn find_new_transactions(
+    transactions: Vec<TransactionStory>,
+    existing: &HashSet<u32>,
+    shared_expense_id: u32,
+) -> Vec<TransactionStory> {
+    transactions
+        .into_iter()
+        .filter(|story| {
+            story.transaction.shared_expense_id == shared_expense_id
+                && !existing.contains(&story.transaction.id)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use super::{
+        find_new_transactions, is_simulated_transaction, make_expense_dto, make_transaction_dto,
+        SimulateTransactionsCommand,
+    };
+    use crate::commands::{SimulateExpenseArgs, SimulateTransactionArgs};
+    use crate::environment::environment_guard::ApiEnvironment;
+    use crate::inspect_expenses::inspect_expenses_api_fake::InspectExpensesApiFake;
+    use crate::output::{OutputFormat, OutputOptions};
+    use crate::shared_expense::TransactionStory;
+    use crate::simulate::simulate_api_fake::SimulateApiFake;
+    use crate::test_common::make_transaction_story;
+    use database::models::money::Money;
+    use database::models::shared_expense::SharedExpenseType;
+
+    fn make_command(
+        server_environment: &'static str,
+    ) -> SimulateTransactionsCommand<SimulateApiFake, InspectExpensesApiFake> {
+        SimulateTransactionsCommand::new(
+            SimulateApiFake::default(),
+            InspectExpensesApiFake {
+                should_error: false,
+            },
+            ApiEnvironment {
+                plaid_environment: Some(String::from("sandbox")),
+                server_environment: Some(String::from(server_environment)),
+            },
+            Duration::ZERO,
+        )
+    }
+
+    fn make_transaction_args(name: &str, amount: i64) -> SimulateTransactionArgs {
+        SimulateTransactionArgs {
+            account: 3,
+            name: String::from(name),
+            merchant: None,
+            amount: Money::from_cents(amount),
+            ppd_id: None,
+        }
+    }
+
+    fn make_output() -> OutputOptions {
+        OutputOptions {
+            format: OutputFormat::Json,
+            columns: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_refuse_to_simulate_against_production() {
+        let command = make_command("production");
+
+        let result = command
+            .simulate_transaction(&make_transaction_args("NETFLIX.COM", 1599), &make_output())
+            .await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 3);
+    }
+
+    #[tokio::test]
+    async fn it_should_find_the_stored_transaction_on_staging() {
+        let command = make_command("staging");
+        let dto = make_transaction_dto(&make_transaction_args("NETFLIX.COM", 1599)).unwrap();
+
+        let message = command
+            .simulate_transaction(&make_transaction_args("NETFLIX.COM", 1599), &make_output())
+            .await
+            .unwrap();
+        let stored: Vec<u32> = command
+            .fetch_candidate_transactions(&dto)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|(_, context)| {
+                is_simulated_transaction(&context.transaction, &dto, &HashSet::from([600]))
+            })
+            .map(|(_, context)| context.transaction.id)
+            .collect();
+
+        assert_eq!(message, "");
+        assert_eq!(stored, vec![601]);
+    }
+
+    #[tokio::test]
+    async fn it_should_report_when_the_simulated_transaction_does_not_show_up() {
+        let command = make_command("staging");
+
+        let message = command
+            .simulate_transaction(&make_transaction_args("UNKNOWN CAFE", 450), &make_output())
+            .await
+            .unwrap();
+
+        assert!(message.contains("equater fetch vendors --search \"UNKNOWN CAFE\""));
+    }
+
+    #[tokio::test]
+    async fn it_should_validate_the_transaction_before_calling_the_api() {
+        let command = make_command("staging");
+
+        let blank_name = command
+            .simulate_transaction(&make_transaction_args("  ", 1599), &make_output())
+            .await;
+        let zero_amount = command
+            .simulate_transaction(&make_transaction_args("NETFLIX.COM", 0), &make_output())
+            .await;
+
+        assert_eq!(blank_name.unwrap_err().exit_code(), 2);
+        assert_eq!(zero_amount.unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_require_an_amount_to_simulate_a_shared_bill() {
+        let command = make_command("staging");
+
+        let result = command
+            .simulate_expense(
+                &SimulateExpenseArgs {
+                    id: 100,
+                    amount: None,
+                },
+                &make_output(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+        assert_eq!(
+            make_expense_dto(SharedExpenseType::RecurringPayment, None)
+                .unwrap()
+                .amount,
+            0.0
+        );
+        assert!(make_expense_dto(
+            SharedExpenseType::RecurringPayment,
+            Some(Money::from_cents(100))
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn it_should_report_when_no_transaction_shows_up() {
+        let command = make_command("development");
+
+        let message = command
+            .simulate_expense(
+                &SimulateExpenseArgs {
+                    id: 100,
+                    amount: Some(Money::from_cents(1599)),
+                },
+                &make_output(),
+            )
+            .await
+            .unwrap();
+
+        assert!(message.contains("equater expense show 100"));
+    }
+
+    #[test]
+    fn it_should_find_transactions_created_by_the_simulation() {
+        let transactions: Vec<TransactionStory> = [500, 502]
+            .into_iter()
+            .map(|id| serde_json::from_value(make_transaction_story(id, 2, "pending")).unwrap())
+            .collect();
+        let existing = HashSet::from([500]);
+
+        let created = find_new_transactions(transactions, &existing, 100);
+        let ids: Vec<u32> = created.iter().map(|story| story.transaction.id).collect();
+
+        assert_eq!(ids, vec![502]);
+    }
+}
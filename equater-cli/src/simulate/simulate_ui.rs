@@ -0,0 +1,100 @@
+use std::slice;
+
+use cli_table::{print_stdout, Cell, CellStruct, Style, Table};
+
+use crate::error::Result;
+use crate::output::OutputOptions;
+use crate::shared_expense::TransactionStory;
+
+use super::simulate_command::SimulatedTransaction;
+
+pub struct SimulateUi<'a> {
+    output: &'a OutputOptions,
+}
+
+impl<'a> SimulateUi<'a> {
+    pub fn new(output: &'a OutputOptions) -> Self {
+        Self { output }
+    }
+
+    pub fn render_transaction(&self, simulated: &SimulatedTransaction) -> Result<()> {
+        if !self.output.is_table() {
+            return self.output.render(slice::from_ref(simulated));
+        }
+
+        println!(
+            "Simulated transaction {} on the {} server",
+            simulated.id, simulated.server_environment
+        );
+        let data: Vec<Vec<CellStruct>> = vec![
+            vec!["Account ID".cell(), simulated.account_id.cell()],
+            vec![
+                "Vendor".cell(),
+                format!("{} ({})", simulated.vendor_name, simulated.vendor_id).cell(),
+            ],
+            vec![
+                "Transaction Name".cell(),
+                simulated
+                    .transaction_name
+                    .clone()
+                    .unwrap_or_default()
+                    .cell(),
+            ],
+            vec![
+                "Merchant Name".cell(),
+                simulated.merchant_name.clone().unwrap_or_default().cell(),
+            ],
+            vec!["Amount".cell(), simulated.amount.to_string().cell()],
+            vec![
+                "PPD ID".cell(),
+                simulated.ppd_id.clone().unwrap_or_default().cell(),
+            ],
+            vec![
+                "Captured".cell(),
+                simulated.date_time_captured.clone().cell(),
+            ],
+        ];
+
+        Ok(print_stdout(data.table().bold(true))?)
+    }
+
+    pub fn render_expense_transactions(&self, transactions: &[TransactionStory]) -> Result<()> {
+        if !self.output.is_table() {
+            return self.output.render(transactions);
+        }
+
+        println!(
+            "The simulation created {} transaction(s)",
+            transactions.len()
+        );
+        let data: Vec<Vec<CellStruct>> = transactions
+            .iter()
+            .map(|story| {
+                let transaction = &story.transaction;
+
+                vec![
+                    transaction.id.cell(),
+                    story.payer.email.clone().cell(),
+                    story.recipient.email.clone().cell(),
+                    transaction.total_transaction_amount.to_string().cell(),
+                    transaction.total_fee_amount.to_string().cell(),
+                    story.status().cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "Payer".cell().bold(true),
+                "Recipient".cell().bold(true),
+                "Amount".cell().bold(true),
+                "Fee".cell().bold(true),
+                "Status".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+}
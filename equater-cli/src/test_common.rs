@@ -1 +1,167 @@
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
+use database::connection::connect_to;
+use database::models::profile::Profile;
+use database::repository::profile_repository::ProfileRepository;
+use diesel::r2d2::{ConnectionManager, Pool};
+use diesel::SqliteConnection;
+use fake::{Fake, Faker};
+use http::response;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::cassette::Cassette;
+use crate::equater_client::{read_json, EquaterClient, Transport};
+use crate::error::Result;
+use crate::user::User;
+use crate::vendor::Vendor;
+
+/// Tests share one database in the temp directory per test process, created fresh on first use,
+/// so they never read or write the operator's real database
+pub fn connect_to_test_database() -> Pool<ConnectionManager<SqliteConnection>> {
+    static TEST_DATABASE: OnceLock<String> = OnceLock::new();
+    let database_url = TEST_DATABASE.get_or_init(|| {
+        let path = std::env::temp_dir()
+            .join(format!("equater-cli-tests-{}", std::process::id()))
+            .join("test.db");
+        let _ = std::fs::remove_file(&path);
+
+        path.display().to_string()
+    });
+
+    connect_to(database_url).unwrap()
+}
+
+/// Profile that every test that touches the local database signs in to
+pub fn make_test_profile(connection: &SqliteConnection) -> Profile {
+    ProfileRepository::new(connection)
+        .find_or_create_profile("test", "http://localhost:7111")
+        .unwrap()
+}
+
+/// What the API responds with when the auth token isn't an admin's
+pub const UNAUTHORIZED_BODY: &str = r#"{"error": "Unauthorized"}"#;
+
+/// The error the client surfaces for a `status` response with `body`, for fakes to return
+/// wherever the real API would fail
+pub async fn make_error_response<T: DeserializeOwned>(status: u16, body: &str) -> Result<T> {
+    let builder = response::Builder::new().status(status);
+
+    // reqwest supports converting an http::response::Response into a reqwest::Response
+    let response: reqwest::Response = builder.body(String::from(body)).unwrap().into();
+
+    read_json(response).await
+}
+
+/// A client that answers from `tests/cassettes/<name>.json`, recorded with `--record`
+pub fn make_replay_client(name: &str) -> EquaterClient {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/cassettes")
+        .join(format!("{}.json", name));
+    let cassette = Cassette::load(&path).unwrap();
+
+    EquaterClient::new("http://localhost:7111")
+        .unwrap()
+        .with_transport(Transport::Replay(Arc::new(cassette)))
+}
+
+/// A two person shared bill where the initiating user (id 1) is still waiting on their own
+/// agreement to be matched, and user 2 has accepted
+pub fn make_agreement_story(
+    vendor_id: u32,
+    vendor_name: &str,
+    shared_expense_id: u32,
+    agreement_id: u32,
+    date_time_became_active: &str,
+) -> Value {
+    let mut vendor: Vendor = Faker.fake();
+    vendor.id = vendor_id;
+    vendor.friendly_name = String::from(vendor_name);
+    let mut initiating_user: User = Faker.fake();
+    initiating_user.id = 1;
+    initiating_user.email = String::from("owner@example.com");
+    let mut active_user: User = Faker.fake();
+    active_user.id = 2;
+    active_user.email = String::from("member@example.com");
+
+    let agreement = json!({
+        "id": agreement_id,
+        "uuid": format!("agreement-{}", agreement_id),
+        "sharedExpenseId": shared_expense_id,
+        "userId": 2,
+        "paymentAccountId": 3,
+        "contributionType": 2,
+        "contributionValue": null,
+        "isPending": false,
+        "isActive": true,
+        "dateTimeCreated": "2022-04-30T00:00:00.000Z",
+        "dateTimeBecameActive": date_time_became_active,
+        "dateTimeBecameInactive": null,
+    });
+
+    json!({
+        "sharedExpense": {
+            "id": shared_expense_id,
+            "uuid": format!("expense-{}", shared_expense_id),
+            "uniqueVendorId": vendor_id,
+            "expenseOwnerUserId": 1,
+            "expenseOwnerSourceAccountId": null,
+            "expenseOwnerDestinationAccountId": 4,
+            "expenseNickName": format!("{} bill", vendor_name),
+            "dateTimeCreated": "2022-04-30T00:00:00.000Z",
+            "isActive": true,
+            "isPending": false,
+            "sharedExpenseType": 0,
+            "expenseRecurrenceInterval": null,
+            "expenseRecurrenceFrequency": null,
+            "targetDateOfFirstCharge": null,
+            "dateLastCharged": null,
+            "dateNextPaymentScheduled": null,
+            "recurringPaymentEndDate": null,
+            "dateTimeDeactivated": null,
+        },
+        "agreements": [agreement],
+        "vendor": vendor,
+        "initiatingUser": initiating_user,
+        "activeUsers": [active_user],
+        "prospectiveUsers": [],
+        "userAgreement": agreement,
+    })
+}
+
+/// A transaction for agreement 1000 of the Netflix bill from `make_agreement_story`, paid by
+/// `payer_id` to user 1
+pub fn make_transaction_story(transaction_id: u32, payer_id: u32, dwolla_status: &str) -> Value {
+    let story = make_agreement_story(10, "Netflix", 100, 1000, "2022-05-10T12:00:00.000Z");
+
+    json!({
+        "transaction": {
+            "id": transaction_id,
+            "uuid": format!("transaction-{}", transaction_id),
+            "plaidTransactionId": 42,
+            "sharedExpenseId": 100,
+            "sharedExpenseUserAgreementId": 1000,
+            "sourceAccountId": 3,
+            "destinationAccountId": 4,
+            "sourceUserId": payer_id,
+            "destinationUserId": 1,
+            "totalTransactionAmount": 799,
+            "totalFeeAmount": 0,
+            "dateTimeInitiated": "2022-05-11T12:00:00.000Z",
+            "hasBeenTransferredToDestination": dwolla_status == "processed",
+            "dateTimeTransferredToDestination": null,
+            "numberOfTimesAttempted": 1,
+            "dateTimeTransactionScheduled": null,
+            "dwollaTransferUrl": null,
+            "dwollaTransferId": null,
+            "dwollaStatus": dwolla_status,
+            "dateTimeDwollaStatusUpdated": "2022-05-12T12:00:00.000Z",
+        },
+        "vendor": story["vendor"],
+        "payer": story["activeUsers"][0],
+        "recipient": story["initiatingUser"],
+        "sharedExpense": story["sharedExpense"],
+        "sharedExpenseAgreement": story["userAgreement"],
+    })
+}
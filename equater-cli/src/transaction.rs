@@ -0,0 +1,131 @@
+use database::models::money::Money;
+use fake::{Dummy, Fake};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::user::{User, UserAccount};
+
+/// A transaction pulled from Plaid
+#[derive(Debug, Deserialize, Serialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub id: u32,
+    pub account_id: u32,
+    pub unique_vendor_id: u32,
+    pub transaction_id: String,
+    pub category_id: String,
+    pub pending_transaction_id: Option<String>,
+    pub account_owner: Option<String>,
+    /// Cents. Positive values when money moves out of the account, negative when money moves in.
+    pub amount: i64,
+    /// ISO8601 date, e.g. 2022-05-14
+    pub date: String,
+    pub authorized_date: Option<String>,
+    /// ISO8601 String
+    pub date_time_captured: String,
+    pub iso_currency_code: Option<String>,
+    #[serde(deserialize_with = "deserialize_tinyint")]
+    pub is_pending: bool,
+    pub payment_channel: Option<String>,
+    pub ppd_id: Option<String>,
+    pub transaction_name: Option<String>,
+    pub merchant_name: Option<String>,
+}
+
+/// Response item of GET /api/transaction/vendor/:id
+#[derive(Debug, Deserialize, Serialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionWithContext {
+    pub user: User,
+    pub account: UserAccount,
+    pub transaction: Transaction,
+}
+
+/// Request body of PUT /api/expense/ops/transaction/simulate
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedArbitraryTransactionDto {
+    /// Dollars rather than cents, since it's passed straight through as a Plaid amount
+    pub amount: f64,
+    pub transaction_name: String,
+    pub merchant_name: String,
+    pub account_id: u32,
+    pub ppd_id: Option<String>,
+}
+
+/// Request body of POST /api/expense/ops/:sharedExpenseId/simulate
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTransactionDto {
+    /// Dollars. The API requires a number, but recurring payments ignore it and charge each
+    /// agreement's contribution instead, so they send 0.
+    pub amount: f64,
+}
+
+impl Transaction {
+    /// The name the bank reported, falling back to the merchant name
+    pub fn display_name(&self) -> &str {
+        self.transaction_name
+            .as_deref()
+            .or(self.merchant_name.as_deref())
+            .unwrap_or("")
+    }
+}
+
+/// Formats cents as dollars, e.g. -1234 -> -$12.34
+pub fn format_cents(cents: i64) -> String {
+    Money::from_cents(cents).to_string()
+}
+
+/// MySQL tinyint columns are serialized as either booleans or 0/1 depending on the driver
+fn deserialize_tinyint<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tinyint {
+        Bool(bool),
+        Int(u8),
+    }
+
+    Ok(match Tinyint::deserialize(deserializer)? {
+        Tinyint::Bool(value) => value,
+        Tinyint::Int(value) => value != 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_cents, Transaction};
+
+    #[test]
+    fn it_should_format_cents_as_dollars() {
+        assert_eq!(format_cents(1234), "$12.34");
+        assert_eq!(format_cents(-5), "-$0.05");
+        assert_eq!(format_cents(0), "$0.00");
+    }
+
+    #[test]
+    fn it_should_accept_tinyint_booleans() {
+        let json = r#"{
+            "id": 1,
+            "accountId": 2,
+            "uniqueVendorId": 3,
+            "transactionId": "abc",
+            "categoryId": "18061000",
+            "pendingTransactionId": null,
+            "accountOwner": null,
+            "amount": 1599,
+            "date": "2022-05-14",
+            "authorizedDate": null,
+            "dateTimeCaptured": "2022-05-14T17:53:16.000Z",
+            "isoCurrencyCode": "USD",
+            "isPending": 0,
+            "paymentChannel": "online",
+            "ppdId": null,
+            "transactionName": "NETFLIX.COM",
+            "merchantName": "Netflix"
+        }"#;
+        let transaction: Transaction = serde_json::from_str(json).unwrap();
+
+        assert!(!transaction.is_pending);
+        assert_eq!(transaction.display_name(), "NETFLIX.COM");
+    }
+}
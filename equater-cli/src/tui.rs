@@ -0,0 +1,3 @@
+pub mod tui_app;
+pub mod tui_command;
+mod tui_ui;
@@ -0,0 +1,484 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::error::Result;
+use crate::output::describe;
+use crate::user::User;
+use crate::vendor::Vendor;
+use crate::watchlist::watchlist_command::WatchlistEntry;
+
+/// How long typing has to pause before a search is sent to the API
+pub const SEARCH_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tab {
+    Users,
+    Vendors,
+    Review,
+    Watchlist,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 4] = [Tab::Users, Tab::Vendors, Tab::Review, Tab::Watchlist];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Tab::Users => "Users",
+            Tab::Vendors => "Vendors",
+            Tab::Review => "Review queue",
+            Tab::Watchlist => "Watchlist",
+        }
+    }
+
+    /// Users and vendors are searched on the server. The review queue and the watchlist are
+    /// fetched once and filtered as you type.
+    pub fn searches_remotely(&self) -> bool {
+        matches!(self, Tab::Users | Tab::Vendors)
+    }
+
+    fn index(&self) -> usize {
+        Tab::ALL.iter().position(|tab| tab == self).unwrap_or(0)
+    }
+}
+
+pub enum Record {
+    User(User),
+    Vendor(Vendor),
+    Watchlist(WatchlistEntry),
+}
+
+impl Record {
+    pub fn id(&self) -> u32 {
+        match self {
+            Record::User(user) => user.id,
+            Record::Vendor(vendor) => vendor.id,
+            Record::Watchlist(entry) => entry.agreement_id,
+        }
+    }
+
+    /// One line in the list
+    pub fn title(&self) -> String {
+        match self {
+            Record::User(user) => format!(
+                "{} {} <{}> (ID {})",
+                user.first_name, user.last_name, user.email, user.id
+            ),
+            Record::Vendor(vendor) => format!("{} (ID {})", vendor.friendly_name, vendor.id),
+            Record::Watchlist(entry) => format!(
+                "{} -- {} waiting {}",
+                entry.vendor_name, entry.expense_nick_name, entry.waiting
+            ),
+        }
+    }
+
+    /// Every field for the detail pane
+    pub fn fields(&self) -> Result<Vec<(String, String)>> {
+        match self {
+            Record::User(user) => describe(user),
+            Record::Vendor(vendor) => describe(vendor),
+            Record::Watchlist(entry) => describe(entry),
+        }
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let searchable = match self {
+            Record::User(user) => vec![
+                user.email.as_str(),
+                user.first_name.as_str(),
+                user.last_name.as_str(),
+            ],
+            Record::Vendor(vendor) => vec![vendor.friendly_name.as_str()],
+            Record::Watchlist(entry) => vec![
+                entry.vendor_name.as_str(),
+                entry.expense_nick_name.as_str(),
+                entry.user_email.as_deref().unwrap_or_default(),
+                entry.initiating_user_email.as_str(),
+            ],
+        };
+
+        searchable
+            .iter()
+            .any(|text| text.to_lowercase().contains(&query))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Browse,
+    /// Keys are typed into the search box
+    Search,
+}
+
+/// What the command has to do after a key is handled
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    None,
+    Quit,
+    /// Fetch the records for a tab with its current query
+    Load(Tab),
+}
+
+#[derive(Default)]
+struct TabState {
+    query: String,
+    records: Vec<Record>,
+    selected: usize,
+    loaded: bool,
+    /// Set while a remote search is waiting for typing to pause
+    query_changed_at: Option<Instant>,
+    /// Record to select once the tab loads, after jumping to it from another tab
+    select_id: Option<u32>,
+}
+
+/// Everything shown by `equater tui`. Key handling doesn't touch the terminal or the API, so the
+/// command decides when to fetch based on the returned `Action`.
+pub struct TuiApp {
+    tab: Tab,
+    mode: Mode,
+    tabs: Vec<TabState>,
+    status: Option<String>,
+}
+
+impl TuiApp {
+    /// Starts on the users tab with the search box focused, since users can't be listed
+    /// without a search term
+    pub fn new() -> Self {
+        Self {
+            tab: Tab::Users,
+            mode: Mode::Search,
+            tabs: Tab::ALL.iter().map(|_| TabState::default()).collect(),
+            status: None,
+        }
+    }
+
+    pub fn tab(&self) -> Tab {
+        self.tab
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn query(&self, tab: Tab) -> &str {
+        &self.tabs[tab.index()].query
+    }
+
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    pub fn set_status(&mut self, status: String) {
+        self.status = Some(status);
+    }
+
+    /// Records on the current tab that match the search box
+    pub fn visible_records(&self) -> Vec<&Record> {
+        let state = self.current();
+        state
+            .records
+            .iter()
+            .filter(|record| self.tab.searches_remotely() || record.matches(&state.query))
+            .collect()
+    }
+
+    /// Index into `visible_records`
+    pub fn selected_index(&self) -> usize {
+        self.current().selected
+    }
+
+    pub fn selected(&self) -> Option<&Record> {
+        self.visible_records().get(self.selected_index()).copied()
+    }
+
+    pub fn set_records(&mut self, tab: Tab, records: Vec<Record>) {
+        let state = &mut self.tabs[tab.index()];
+        state.selected = state
+            .select_id
+            .take()
+            .and_then(|id| records.iter().position(|record| record.id() == id))
+            .unwrap_or(0);
+        state.records = records;
+        state.loaded = true;
+        self.status = None;
+    }
+
+    /// Returns the tab whose search should be sent now that typing has paused
+    pub fn due_search(&mut self, now: Instant) -> Option<Tab> {
+        let state = &mut self.tabs[self.tab.index()];
+        match state.query_changed_at {
+            Some(changed_at) if now.duration_since(changed_at) >= SEARCH_DELAY => {
+                state.query_changed_at = None;
+                Some(self.tab)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, now: Instant) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        match self.mode {
+            Mode::Search => self.handle_search_key(key, now),
+            Mode::Browse => self.handle_browse_key(key),
+        }
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent, now: Instant) -> Action {
+        match key.code {
+            KeyCode::Char(character) => {
+                self.current_mut().query.push(character);
+                self.query_changed(now);
+            }
+            KeyCode::Backspace => {
+                self.current_mut().query.pop();
+                self.query_changed(now);
+            }
+            KeyCode::Enter | KeyCode::Esc => {
+                self.mode = Mode::Browse;
+                // Don't wait out the delay when the search was finished on purpose
+                if self.current_mut().query_changed_at.take().is_some() {
+                    return Action::Load(self.tab);
+                }
+            }
+            KeyCode::Down => self.move_selection(1),
+            KeyCode::Up => self.move_selection(-1),
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('q') => return Action::Quit,
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Tab | KeyCode::Right => return self.switch_tab(self.tab.index() + 1),
+            KeyCode::BackTab | KeyCode::Left => {
+                return self.switch_tab(self.tab.index() + Tab::ALL.len() - 1)
+            }
+            KeyCode::Char(digit @ '1'..='4') => {
+                return self.switch_tab(digit as usize - '1' as usize)
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Home | KeyCode::Char('g') => self.current_mut().selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX),
+            KeyCode::Char('r') => return Action::Load(self.tab),
+            KeyCode::Char('u') => return self.open_user(false),
+            KeyCode::Char('i') => return self.open_user(true),
+            KeyCode::Char('v') => return self.open_vendor(),
+            KeyCode::Char('w') => return self.open_watchlist(),
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    /// Opens the agreement's user on the users tab, or the user who created the shared expense
+    /// when `initiating_user` is set
+    fn open_user(&mut self, initiating_user: bool) -> Action {
+        let target = match self.selected() {
+            Some(Record::Watchlist(entry)) if initiating_user => {
+                Some((entry.initiating_user_email.clone(), None))
+            }
+            Some(Record::Watchlist(entry)) => entry
+                .user_email
+                .clone()
+                .map(|email| (email, Some(entry.user_id))),
+            _ => None,
+        };
+
+        match target {
+            Some((email, id)) => self.open(Tab::Users, email, id),
+            None => self.unavailable("This record isn't linked to a user"),
+        }
+    }
+
+    fn open_vendor(&mut self) -> Action {
+        let target = match self.selected() {
+            Some(Record::Vendor(vendor)) if self.tab != Tab::Vendors => {
+                Some((vendor.friendly_name.clone(), vendor.id))
+            }
+            Some(Record::Watchlist(entry)) => {
+                entry.vendor_id.map(|id| (entry.vendor_name.clone(), id))
+            }
+            _ => None,
+        };
+
+        match target {
+            Some((name, id)) => self.open(Tab::Vendors, name, Some(id)),
+            None => self.unavailable("This record isn't linked to another vendor"),
+        }
+    }
+
+    /// Filters the watchlist down to the selected user's or vendor's agreements
+    fn open_watchlist(&mut self) -> Action {
+        let query = match self.selected() {
+            Some(Record::User(user)) => Some(user.email.clone()),
+            Some(Record::Vendor(vendor)) => Some(vendor.friendly_name.clone()),
+            _ => None,
+        };
+
+        match query {
+            Some(query) => self.open(Tab::Watchlist, query, None),
+            None => self.unavailable("Select a user or vendor to find it on the watchlist"),
+        }
+    }
+
+    fn open(&mut self, tab: Tab, query: String, select_id: Option<u32>) -> Action {
+        self.tab = tab;
+        self.mode = Mode::Browse;
+        self.status = None;
+        let state = self.current_mut();
+        state.query = query;
+        state.selected = 0;
+        state.query_changed_at = None;
+
+        if tab.searches_remotely() || !state.loaded {
+            state.select_id = select_id;
+            return Action::Load(tab);
+        }
+
+        Action::None
+    }
+
+    fn switch_tab(&mut self, index: usize) -> Action {
+        self.tab = Tab::ALL[index % Tab::ALL.len()];
+        self.status = None;
+        let state = self.current();
+        // Users can't be listed without a search term
+        if state.loaded || (self.tab == Tab::Users && state.query.is_empty()) {
+            return Action::None;
+        }
+
+        Action::Load(self.tab)
+    }
+
+    fn query_changed(&mut self, now: Instant) {
+        let remote = self.tab.searches_remotely();
+        let state = self.current_mut();
+        state.selected = 0;
+        if remote {
+            state.query_changed_at = Some(now);
+        }
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.visible_records().len().saturating_sub(1);
+        let state = self.current_mut();
+        state.selected = state.selected.saturating_add_signed(offset).min(last);
+    }
+
+    fn unavailable(&mut self, message: &str) -> Action {
+        self.status = Some(String::from(message));
+
+        Action::None
+    }
+
+    fn current(&self) -> &TabState {
+        &self.tabs[self.tab.index()]
+    }
+
+    fn current_mut(&mut self) -> &mut TabState {
+        &mut self.tabs[self.tab.index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use chrono::Utc;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Action, Mode, Record, Tab, TuiApp, SEARCH_DELAY};
+    use crate::watchlist::watchlist_api_fake::WatchlistApiFake;
+    use crate::watchlist::watchlist_command::{group_by_vendor, FetchesWatchlist};
+
+    fn press(app: &mut TuiApp, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE), Instant::now())
+    }
+
+    fn type_text(app: &mut TuiApp, text: &str) {
+        for character in text.chars() {
+            press(app, KeyCode::Char(character));
+        }
+    }
+
+    async fn make_watchlist_records() -> Vec<Record> {
+        let watchlist = WatchlistApiFake {
+            should_error: false,
+        }
+        .fetch_agreement_watchlist()
+        .await
+        .unwrap();
+
+        group_by_vendor(&watchlist, false, Utc::now())
+            .into_iter()
+            .flat_map(|group| group.entries)
+            .map(Record::Watchlist)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_should_filter_the_watchlist_as_you_type() {
+        let mut app = TuiApp::new();
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(
+            press(&mut app, KeyCode::Char('4')),
+            Action::Load(Tab::Watchlist)
+        );
+        app.set_records(Tab::Watchlist, make_watchlist_records().await);
+
+        press(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "NETF");
+        let netflix = app.visible_records().len();
+        type_text(&mut app, "x");
+        let nothing = app.visible_records().len();
+
+        assert_eq!(netflix, 2);
+        assert_eq!(nothing, 0);
+        assert_eq!(app.due_search(Instant::now() + SEARCH_DELAY), None);
+    }
+
+    #[test]
+    fn it_should_wait_for_typing_to_pause_before_searching_users() {
+        let mut app = TuiApp::new();
+        let typed_at = Instant::now();
+
+        for character in "robert".chars() {
+            app.handle_key(
+                KeyEvent::new(KeyCode::Char(character), KeyModifiers::NONE),
+                typed_at,
+            );
+        }
+
+        assert_eq!(app.query(Tab::Users), "robert");
+        assert_eq!(app.due_search(typed_at), None);
+        assert_eq!(app.due_search(typed_at + SEARCH_DELAY), Some(Tab::Users));
+        assert_eq!(app.due_search(typed_at + SEARCH_DELAY), None);
+    }
+
+    #[tokio::test]
+    async fn it_should_open_the_vendor_and_user_of_a_watchlist_entry() {
+        let mut app = TuiApp::new();
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('4'));
+        app.set_records(Tab::Watchlist, make_watchlist_records().await);
+
+        let open_vendor = press(&mut app, KeyCode::Char('v'));
+        let vendor_tab = (app.tab(), app.query(Tab::Vendors).to_string());
+        press(&mut app, KeyCode::Char('4'));
+        let open_user = press(&mut app, KeyCode::Char('u'));
+
+        assert_eq!(open_vendor, Action::Load(Tab::Vendors));
+        assert_eq!(vendor_tab, (Tab::Vendors, String::from("Comcast")));
+        assert_eq!(open_user, Action::Load(Tab::Users));
+        assert_eq!(app.query(Tab::Users), "member@example.com");
+        assert_eq!(app.mode(), Mode::Browse);
+    }
+}
@@ -0,0 +1,233 @@
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use cli_table::ColorChoice;
+use crossterm::event::{self, Event, KeyEventKind};
+use database::models::vendor_review::VendorReview;
+
+use crate::error::{EquaterError, Result};
+use crate::fetch_users::fetch_users_command::FetchesUsers;
+use crate::fetch_vendors::fetch_vendors_command::FetchesVendors;
+use crate::output::color_choice;
+use crate::review_vendors::review_vendors_command::remove_reviewed_vendors;
+use crate::watchlist::watchlist_command::{group_by_vendor, FetchesWatchlist};
+
+use super::tui_app::{Action, Record, Tab, TuiApp};
+use super::tui_ui::{draw, TerminalSession};
+
+/// How often the screen is redrawn while waiting for a key, which is also how soon a search
+/// goes out once typing pauses
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Full-screen browser for users, vendors, the review queue and the agreement watchlist. Nothing
+/// is changed on the server -- `equater review vendors` is still where vendors get edited.
+pub struct TuiCommand<U: FetchesUsers, V: FetchesVendors, W: FetchesWatchlist> {
+    user_repository: U,
+    vendor_repository: V,
+    watchlist_repository: W,
+    /// Vendors already decided on in the current `review vendors` pass, left out of the queue
+    reviews: Vec<VendorReview>,
+}
+
+impl<U: FetchesUsers, V: FetchesVendors, W: FetchesWatchlist> TuiCommand<U, V, W> {
+    pub fn new(
+        user_repository: U,
+        vendor_repository: V,
+        watchlist_repository: W,
+        reviews: Vec<VendorReview>,
+    ) -> Self {
+        Self {
+            user_repository,
+            vendor_repository,
+            watchlist_repository,
+            reviews,
+        }
+    }
+
+    pub async fn run(&self) -> Result<String> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err(EquaterError::Validation(String::from(
+                "equater tui needs an interactive terminal",
+            )));
+        }
+
+        let colorful = color_choice(true) != ColorChoice::Never;
+        let mut session = TerminalSession::enter()?;
+        let mut app = TuiApp::new();
+        loop {
+            session.terminal.draw(|frame| draw(frame, &app, colorful))?;
+
+            if let Some(tab) = app.due_search(Instant::now()) {
+                self.load(&mut app, tab).await?;
+                continue;
+            }
+            if !event::poll(POLL_INTERVAL)? {
+                continue;
+            }
+
+            let key = match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                _ => continue,
+            };
+            match app.handle_key(key, Instant::now()) {
+                Action::Quit => break,
+                Action::Load(tab) => self.load(&mut app, tab).await?,
+                Action::None => {}
+            }
+        }
+
+        Ok(String::from(""))
+    }
+
+    /// Failures are shown on the status line so that one bad search doesn't close the UI. An
+    /// expired session is returned instead, since signing in again needs the normal terminal.
+    pub async fn load(&self, app: &mut TuiApp, tab: Tab) -> Result<()> {
+        match self.fetch(tab, app.query(tab)).await {
+            Ok(records) => app.set_records(tab, records),
+            Err(err) if err.is_unauthorized() => return Err(err),
+            Err(err) => app.set_status(err.to_string()),
+        }
+
+        Ok(())
+    }
+
+    async fn fetch(&self, tab: Tab, query: &str) -> Result<Vec<Record>> {
+        let records = match tab {
+            Tab::Users if query.is_empty() => vec![],
+            Tab::Users => self
+                .user_repository
+                .fetch_users(query)
+                .await?
+                .into_iter()
+                .map(Record::User)
+                .collect(),
+            Tab::Vendors => {
+                let vendors = if query.is_empty() {
+                    self.vendor_repository.fetch_popular_vendors().await?
+                } else {
+                    self.vendor_repository.search_vendors(query).await?
+                };
+
+                vendors.into_iter().map(Record::Vendor).collect()
+            }
+            Tab::Review => {
+                let vendors = self
+                    .vendor_repository
+                    .fetch_vendors_that_require_review()
+                    .await?;
+
+                remove_reviewed_vendors(vendors, &self.reviews)
+                    .into_iter()
+                    .map(Record::Vendor)
+                    .collect()
+            }
+            Tab::Watchlist => {
+                let watchlist = self
+                    .watchlist_repository
+                    .fetch_agreement_watchlist()
+                    .await?;
+
+                group_by_vendor(&watchlist, false, Utc::now())
+                    .into_iter()
+                    .flat_map(|group| group.entries)
+                    .map(Record::Watchlist)
+                    .collect()
+            }
+        };
+
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use tui::backend::TestBackend;
+    use tui::Terminal;
+
+    use super::TuiCommand;
+    use crate::fetch_users::fetch_user_api_fake::FetchUserApiFake;
+    use crate::fetch_vendors::fetch_vendors_api_fake::FetchVendorsApiFake;
+    use crate::tui::tui_app::{Action, Tab, TuiApp};
+    use crate::tui::tui_ui::draw;
+    use crate::watchlist::watchlist_api_fake::WatchlistApiFake;
+
+    fn make_command(
+        should_error: bool,
+    ) -> TuiCommand<FetchUserApiFake, FetchVendorsApiFake, WatchlistApiFake> {
+        TuiCommand::new(
+            FetchUserApiFake { should_error },
+            FetchVendorsApiFake { should_error },
+            WatchlistApiFake {
+                should_error: false,
+            },
+            vec![],
+        )
+    }
+
+    fn press(app: &mut TuiApp, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE), Instant::now())
+    }
+
+    fn render(app: &TuiApp) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| draw(frame, app, false)).unwrap();
+
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_should_show_the_selected_watchlist_entry_in_the_detail_pane() {
+        let command = make_command(false);
+        let mut app = TuiApp::new();
+        press(&mut app, KeyCode::Esc);
+        press(&mut app, KeyCode::Char('4'));
+
+        command.load(&mut app, Tab::Watchlist).await.unwrap();
+        let screen = render(&app);
+
+        assert!(screen.contains("Watchlist (3)"));
+        assert!(screen.contains("> Comcast -- Comcast bill"));
+        assert!(screen.contains("Agreement ID: 2000"));
+    }
+
+    #[tokio::test]
+    async fn it_should_search_users_through_the_remote_repository() {
+        let command = make_command(false);
+        let mut app = TuiApp::new();
+        press(&mut app, KeyCode::Char('r'));
+        press(&mut app, KeyCode::Char('o'));
+
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Load(Tab::Users));
+        command.load(&mut app, Tab::Users).await.unwrap();
+        let screen = render(&app);
+
+        assert!(screen.contains("Users (2)"));
+        assert!(screen.contains("> Robert Paulson <robert.paulson@example.com> (ID 3)"));
+        assert!(screen.contains("Roberta Diaz <roberta.diaz@example.com> (ID 9)"));
+        assert!(screen.contains("ID: 3"));
+        assert!(screen.contains("Email: robert.paulson@example.com"));
+    }
+
+    #[tokio::test]
+    async fn it_should_show_remote_errors_on_the_status_line() {
+        let command = make_command(true);
+        let mut app = TuiApp::new();
+        press(&mut app, KeyCode::Char('x'));
+
+        command.load(&mut app, Tab::Users).await.unwrap();
+
+        assert!(app.status().is_some());
+        assert!(app.visible_records().is_empty());
+        assert!(render(&app).contains(app.status().unwrap()));
+    }
+}
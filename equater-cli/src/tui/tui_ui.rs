@@ -0,0 +1,184 @@
+use std::io::{self, Stdout};
+
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use tui::backend::{Backend, CrosstermBackend};
+use tui::layout::{Constraint, Direction, Layout, Rect};
+use tui::style::{Color, Modifier, Style};
+use tui::text::{Span, Spans};
+use tui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use tui::{Frame, Terminal};
+
+use crate::error::Result;
+
+use super::tui_app::{Mode, Record, Tab, TuiApp};
+
+const BROWSE_HELP: &str =
+    "q quit  / search  tab switch tabs  j/k move  r refresh  u user  i initiating user  v vendor  w watchlist";
+const SEARCH_HELP: &str = "Type to search  enter/esc done  up/down move";
+
+/// Raw mode and the alternate screen, which are put back when this is dropped -- including when a
+/// command fails or panics part way through
+pub struct TerminalSession {
+    pub terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl TerminalSession {
+    pub fn enter() -> Result<Self> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        if let Err(err) = execute!(stdout, EnterAlternateScreen) {
+            disable_raw_mode()?;
+            return Err(err.into());
+        }
+
+        Ok(Self {
+            terminal: Terminal::new(CrosstermBackend::new(stdout))?,
+        })
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        disable_raw_mode().ok();
+        execute!(self.terminal.backend_mut(), LeaveAlternateScreen).ok();
+        self.terminal.show_cursor().ok();
+    }
+}
+
+/// Tabs along the top, the search box, the list next to the selected record's fields, and a
+/// status line with the keyboard shortcuts. `colorful` is false for `--color never`.
+pub fn draw<B: Backend>(frame: &mut Frame<B>, app: &TuiApp, colorful: bool) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(frame.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+        .split(rows[2]);
+
+    draw_tabs(frame, app, rows[0], colorful);
+    draw_search(frame, app, rows[1], colorful);
+    draw_list(frame, app, columns[0], colorful);
+    draw_detail(frame, app.selected(), columns[1]);
+    draw_status(frame, app, rows[3]);
+}
+
+fn draw_tabs<B: Backend>(frame: &mut Frame<B>, app: &TuiApp, area: Rect, colorful: bool) {
+    let titles = Tab::ALL
+        .iter()
+        .enumerate()
+        .map(|(index, tab)| Spans::from(format!("{} {}", index + 1, tab.title())))
+        .collect();
+    let selected = Tab::ALL
+        .iter()
+        .position(|tab| *tab == app.tab())
+        .unwrap_or(0);
+    let tabs = Tabs::new(titles)
+        .block(Block::default().borders(Borders::ALL).title("Equater"))
+        .select(selected)
+        .highlight_style(highlight(colorful));
+
+    frame.render_widget(tabs, area);
+}
+
+fn draw_search<B: Backend>(frame: &mut Frame<B>, app: &TuiApp, area: Rect, colorful: bool) {
+    let query = app.query(app.tab());
+    let searching = app.mode() == Mode::Search;
+    let title = if app.tab().searches_remotely() {
+        "Search"
+    } else {
+        "Filter"
+    };
+    let border_style = if searching && colorful {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    let search = Paragraph::new(query).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(title),
+    );
+    frame.render_widget(search, area);
+
+    if searching {
+        let width = query.chars().count() as u16;
+        frame.set_cursor(
+            (area.x + 1 + width).min(area.right().saturating_sub(2)),
+            area.y + 1,
+        );
+    }
+}
+
+fn draw_list<B: Backend>(frame: &mut Frame<B>, app: &TuiApp, area: Rect, colorful: bool) {
+    let records = app.visible_records();
+    let items: Vec<ListItem> = records
+        .iter()
+        .map(|record| ListItem::new(record.title()))
+        .collect();
+    let title = format!("{} ({})", app.tab().title(), records.len());
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .highlight_style(highlight(colorful))
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !records.is_empty() {
+        state.select(Some(app.selected_index()));
+    }
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_detail<B: Backend>(frame: &mut Frame<B>, record: Option<&Record>, area: Rect) {
+    let lines = match record.map(Record::fields) {
+        Some(Ok(fields)) => fields
+            .into_iter()
+            .map(|(title, value)| {
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{}: ", title),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(value),
+                ])
+            })
+            .collect(),
+        Some(Err(err)) => vec![Spans::from(err.to_string())],
+        None => vec![],
+    };
+    let detail = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Details"))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(detail, area);
+}
+
+fn draw_status<B: Backend>(frame: &mut Frame<B>, app: &TuiApp, area: Rect) {
+    let text = match (app.status(), app.mode()) {
+        (Some(status), _) => status,
+        (None, Mode::Search) => SEARCH_HELP,
+        (None, Mode::Browse) => BROWSE_HELP,
+    };
+
+    frame.render_widget(Paragraph::new(text), area);
+}
+
+/// Reversed video still shows the selection when colors are turned off
+fn highlight(colorful: bool) -> Style {
+    let style = Style::default().add_modifier(Modifier::REVERSED);
+    if colorful {
+        return style.fg(Color::Cyan);
+    }
+
+    style
+}
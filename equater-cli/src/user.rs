@@ -2,11 +2,14 @@ use fake::{faker::internet::en::SafeEmail, uuid::UUIDv5};
 use fake::{Dummy, Fake};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Dummy)]
+use crate::output::Columns;
+
+#[derive(Debug, Deserialize, Serialize, Dummy)]
 #[serde(rename_all = "camelCase")]
 pub struct SignInResponse {
     pub auth_token: String,
     pub user: User,
+    #[allow(dead_code)]
     pub user_accounts: Vec<UserAccount>,
 }
 
@@ -45,6 +48,41 @@ pub struct User {
     pub dwolla_reverification_needed: bool,
 }
 
+impl Columns for User {
+    fn default_columns() -> &'static [&'static str] {
+        &["id", "email", "uuid", "first_name", "last_name"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "uuid",
+            "email",
+            "first_name",
+            "last_name",
+            "email_is_confirmed",
+            "can_receive_funds",
+            "profile_photo_url",
+            "profile_photo_upload_completed",
+            "profile_photo_sha256_hash",
+            "cover_photo_upload_completed",
+            "cover_photo_sha256_hash",
+            "date_time_created",
+            "address_one",
+            "address_two",
+            "city",
+            "state",
+            "postal_code",
+            "pre_signed_photo_download_url",
+            "pre_signed_cover_photo_download_url",
+            "accepted_terms_of_service",
+            "accepted_privacy_policy",
+            "link_tokens",
+            "dwolla_reverification_needed",
+        ]
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Dummy)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaidLinkToken {
@@ -92,7 +130,10 @@ pub struct UserAccount {
     /// ISO8601 String,
     pub date_of_last_plaid_transaction_pull: Option<String>,
     pub requires_plaid_re_authentication: bool,
-    pub institution: Institution,
+    /// Relations aren't included when the account is serialized as part of another model
+    #[serde(default)]
+    pub institution: Option<Institution>,
+    #[serde(default)]
     pub link_tokens: Vec<PlaidLinkToken>,
 }
 
@@ -0,0 +1,5 @@
+pub mod user_dossier_api;
+#[cfg(test)]
+pub mod user_dossier_api_fake;
+pub mod user_dossier_command;
+mod user_dossier_ui;
@@ -0,0 +1,56 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::shared_expense::{TransactionStory, UserAgreementStory};
+use crate::user::{User, UserAccount};
+use async_trait::async_trait;
+
+use super::user_dossier_command::FetchesUserDossiers;
+
+pub struct UserDossierApi {
+    client: EquaterClient,
+}
+
+impl UserDossierApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl FetchesUserDossiers for UserDossierApi {
+    async fn fetch_user(&self, user_id: u32) -> Result<User> {
+        self.client
+            .get(&format!("/api/user/{}", user_id))
+            .send_json::<User>()
+            .await
+    }
+
+    async fn fetch_relationships(&self, user_id: u32) -> Result<Vec<User>> {
+        self.client
+            .get(&format!("/api/user/{}/relationships", user_id))
+            .send_json::<Vec<User>>()
+            .await
+    }
+
+    async fn fetch_accounts(&self, user_id: u32) -> Result<Vec<UserAccount>> {
+        self.client
+            .get(&format!("/api/account/user/{}", user_id))
+            .send_json::<Vec<UserAccount>>()
+            .await
+    }
+
+    async fn fetch_agreements(&self, user_id: u32) -> Result<Vec<UserAgreementStory>> {
+        self.client
+            .get(&format!("/api/expense/user/agreements/{}", user_id))
+            .send_json::<Vec<UserAgreementStory>>()
+            .await
+    }
+
+    async fn fetch_transactions(&self, user_id: u32) -> Result<Vec<TransactionStory>> {
+        self.client
+            .get(&format!("/api/expense/user/transactions/{}", user_id))
+            .send_json::<Vec<TransactionStory>>()
+            .await
+    }
+}
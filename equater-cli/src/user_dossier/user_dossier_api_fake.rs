@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::fetch_users::fetch_users_command::FetchesUsers;
+use crate::shared_expense::{TransactionStory, UserAgreementStory};
+use crate::test_common::{
+    make_agreement_story, make_error_response, make_transaction_story, UNAUTHORIZED_BODY,
+};
+use crate::user::{User, UserAccount};
+use fake::{Fake, Faker};
+
+use super::user_dossier_command::FetchesUserDossiers;
+
+pub const FAKE_USER_ID: u32 = 2;
+pub const FAKE_USER_EMAIL: &str = "member@example.com";
+
+pub struct UserDossierApiFake {
+    pub should_error: bool,
+}
+
+fn make_user(id: u32, email: &str) -> User {
+    let mut user: User = Faker.fake();
+    user.id = id;
+    user.email = String::from(email);
+    user.email_is_confirmed = true;
+    user.dwolla_reverification_needed = false;
+
+    user
+}
+
+#[async_trait]
+impl FetchesUsers for UserDossierApiFake {
+    async fn fetch_users(&self, _search_term: &str) -> Result<Vec<User>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        // Searches match partial emails, so the exact match isn't necessarily first
+        Ok(vec![
+            make_user(3, "other.member@example.com"),
+            make_user(FAKE_USER_ID, FAKE_USER_EMAIL),
+        ])
+    }
+}
+
+#[async_trait]
+impl FetchesUserDossiers for UserDossierApiFake {
+    async fn fetch_user(&self, user_id: u32) -> Result<User> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let mut user = make_user(user_id, FAKE_USER_EMAIL);
+        user.email_is_confirmed = false;
+
+        Ok(user)
+    }
+
+    async fn fetch_relationships(&self, _user_id: u32) -> Result<Vec<User>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        Ok(vec![make_user(1, "owner@example.com")])
+    }
+
+    async fn fetch_accounts(&self, user_id: u32) -> Result<Vec<UserAccount>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let accounts = (1..=2)
+            .map(|id| {
+                let mut account: UserAccount = Faker.fake();
+                account.id = id;
+                account.user_id = user_id;
+                account.account_name = format!("Checking {}", id);
+                account.is_active = true;
+                account.requires_plaid_re_authentication = id == 2;
+
+                account
+            })
+            .collect();
+
+        Ok(accounts)
+    }
+
+    async fn fetch_agreements(&self, _user_id: u32) -> Result<Vec<UserAgreementStory>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let story = make_agreement_story(10, "Netflix", 100, 1000, "2022-05-10T12:00:00.000Z");
+
+        Ok(vec![serde_json::from_value(story).unwrap()])
+    }
+
+    async fn fetch_transactions(&self, _user_id: u32) -> Result<Vec<TransactionStory>> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let transaction = make_transaction_story(500, FAKE_USER_ID, "failed");
+
+        Ok(vec![serde_json::from_value(transaction).unwrap()])
+    }
+}
@@ -0,0 +1,280 @@
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::error::{EquaterError, Result};
+use crate::fetch_users::fetch_users_command::FetchesUsers;
+use crate::output::{Columns, OutputOptions};
+use crate::shared_expense::{TransactionStory, UserAgreementStory};
+use crate::user::{User, UserAccount};
+
+use super::user_dossier_ui::UserDossierUi;
+
+/// Transfers are abandoned after this many attempts. Mirrors MAXIMUM_TRANSACTION_ATTEMPTS in the
+/// API.
+const MAXIMUM_TRANSACTION_ATTEMPTS: u32 = 5;
+
+#[async_trait]
+pub trait FetchesUserDossiers {
+    async fn fetch_user(&self, user_id: u32) -> Result<User>;
+    async fn fetch_relationships(&self, user_id: u32) -> Result<Vec<User>>;
+    async fn fetch_accounts(&self, user_id: u32) -> Result<Vec<UserAccount>>;
+    async fn fetch_agreements(&self, user_id: u32) -> Result<Vec<UserAgreementStory>>;
+    async fn fetch_transactions(&self, user_id: u32) -> Result<Vec<TransactionStory>>;
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UserIdentifier {
+    Id(u32),
+    Email(String),
+}
+
+impl UserIdentifier {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+
+        if let Ok(id) = value.parse::<u32>() {
+            Ok(UserIdentifier::Id(id))
+        } else if value.contains('@') {
+            Ok(UserIdentifier::Email(value.to_lowercase()))
+        } else {
+            Err(EquaterError::Validation(format!(
+                "{} isn't a user ID or email",
+                value
+            )))
+        }
+    }
+}
+
+/// Everything support needs to know about one user
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDossier {
+    pub user: User,
+    pub red_flags: Vec<String>,
+    pub accounts: Vec<UserAccount>,
+    pub relationships: Vec<User>,
+    pub agreements: Vec<UserAgreementStory>,
+    pub transactions: Vec<TransactionStory>,
+}
+
+impl Columns for UserDossier {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "user.id",
+            "user.email",
+            "user.first_name",
+            "user.last_name",
+            "red_flags",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "user",
+            "red_flags",
+            "accounts",
+            "relationships",
+            "agreements",
+            "transactions",
+        ]
+    }
+}
+
+impl UserDossier {
+    pub fn new(
+        user: User,
+        accounts: Vec<UserAccount>,
+        relationships: Vec<User>,
+        agreements: Vec<UserAgreementStory>,
+        transactions: Vec<TransactionStory>,
+    ) -> Self {
+        let red_flags = find_red_flags(&user, &accounts, &transactions);
+
+        Self {
+            user,
+            red_flags,
+            accounts,
+            relationships,
+            agreements,
+            transactions,
+        }
+    }
+}
+
+/// Things that stop a user from splitting bills and usually explain why they've contacted support
+fn find_red_flags(
+    user: &User,
+    accounts: &[UserAccount],
+    transactions: &[TransactionStory],
+) -> Vec<String> {
+    let mut red_flags = vec![];
+
+    if !user.email_is_confirmed {
+        red_flags.push(String::from("Email hasn't been confirmed"));
+    }
+    if user.dwolla_reverification_needed {
+        red_flags.push(String::from(
+            "Dwolla needs the user to verify their identity again",
+        ));
+    }
+    for account in accounts.iter().filter(|account| account.is_active) {
+        if account.requires_plaid_re_authentication {
+            red_flags.push(format!(
+                "{} at {} (account ID {}) requires Plaid re-authentication",
+                account.account_name, account.institution_name, account.id
+            ));
+        }
+    }
+    for story in transactions {
+        let transaction = &story.transaction;
+        let status = transaction.dwolla_status.as_deref().unwrap_or("");
+
+        if status == "failed" || status == "cancelled" {
+            red_flags.push(format!(
+                "Transfer for transaction {} {}",
+                transaction.id, status
+            ));
+        } else if !transaction.has_been_transferred_to_destination
+            && transaction.number_of_times_attempted >= MAXIMUM_TRANSACTION_ATTEMPTS
+        {
+            red_flags.push(format!(
+                "Transaction {} was abandoned after {} attempts",
+                transaction.id, transaction.number_of_times_attempted
+            ));
+        }
+    }
+
+    red_flags
+}
+
+pub struct UserDossierCommand<F: FetchesUsers, D: FetchesUserDossiers> {
+    user_repository: F,
+    dossier_repository: D,
+}
+
+impl<F: FetchesUsers, D: FetchesUserDossiers> UserDossierCommand<F, D> {
+    pub fn new(user_repository: F, dossier_repository: D) -> Self {
+        Self {
+            user_repository,
+            dossier_repository,
+        }
+    }
+
+    pub async fn show_user(&self, user: &str, output: &OutputOptions) -> Result<String> {
+        let dossier = self.fetch_dossier(UserIdentifier::parse(user)?).await?;
+
+        UserDossierUi::new(&dossier).render(output)?;
+
+        Ok(String::from(""))
+    }
+
+    pub async fn fetch_dossier(&self, identifier: UserIdentifier) -> Result<UserDossier> {
+        let user_id = self.resolve_user_id(identifier).await?;
+        let (user, relationships, accounts, agreements, transactions) = tokio::try_join!(
+            self.dossier_repository.fetch_user(user_id),
+            self.dossier_repository.fetch_relationships(user_id),
+            self.dossier_repository.fetch_accounts(user_id),
+            self.dossier_repository.fetch_agreements(user_id),
+            self.dossier_repository.fetch_transactions(user_id),
+        )?;
+
+        Ok(UserDossier::new(
+            user,
+            accounts,
+            relationships,
+            agreements,
+            transactions,
+        ))
+    }
+
+    async fn resolve_user_id(&self, identifier: UserIdentifier) -> Result<u32> {
+        let email = match identifier {
+            UserIdentifier::Id(id) => return Ok(id),
+            UserIdentifier::Email(email) => email,
+        };
+
+        self.user_repository
+            .fetch_users(&email)
+            .await?
+            .into_iter()
+            .find(|user| user.email.to_lowercase() == email)
+            .map(|user| user.id)
+            .ok_or_else(|| EquaterError::Validation(format!("No user has the email {}", email)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UserDossierCommand, UserIdentifier};
+    use crate::user_dossier::user_dossier_api_fake::{
+        UserDossierApiFake, FAKE_USER_EMAIL, FAKE_USER_ID,
+    };
+
+    fn make_command(
+        should_error: bool,
+    ) -> UserDossierCommand<UserDossierApiFake, UserDossierApiFake> {
+        UserDossierCommand::new(
+            UserDossierApiFake { should_error },
+            UserDossierApiFake { should_error },
+        )
+    }
+
+    #[test]
+    fn it_should_parse_user_identifiers() {
+        assert_eq!(UserIdentifier::parse("42").unwrap(), UserIdentifier::Id(42));
+        assert_eq!(
+            UserIdentifier::parse("Holden@Example.com").unwrap(),
+            UserIdentifier::Email(String::from("holden@example.com"))
+        );
+        // User search only matches names and emails, so there's no way to look up a UUID
+        assert_eq!(
+            UserIdentifier::parse("0b5a4b8e-5d2f-4c1e-9a55-0e6f0c1f1d2a")
+                .unwrap_err()
+                .exit_code(),
+            2
+        );
+        assert_eq!(UserIdentifier::parse("holden").unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_find_users_by_exact_email() {
+        let dossier = make_command(false)
+            .fetch_dossier(UserIdentifier::Email(String::from(FAKE_USER_EMAIL)))
+            .await
+            .unwrap();
+
+        assert_eq!(dossier.user.id, FAKE_USER_ID);
+        assert_eq!(dossier.relationships.len(), 1);
+        assert_eq!(dossier.agreements.len(), 1);
+        assert_eq!(dossier.transactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_should_highlight_red_flags() {
+        let dossier = make_command(false)
+            .fetch_dossier(UserIdentifier::Id(FAKE_USER_ID))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            dossier.red_flags,
+            vec![
+                String::from("Email hasn't been confirmed"),
+                format!(
+                    "Checking 2 at {} (account ID 2) requires Plaid re-authentication",
+                    dossier.accounts[1].institution_name
+                ),
+                String::from("Transfer for transaction 500 failed"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let result = make_command(true)
+            .fetch_dossier(UserIdentifier::Id(FAKE_USER_ID))
+            .await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+    }
+}
@@ -0,0 +1,259 @@
+use cli_table::{print_stdout, Cell, CellStruct, Color, Style, Table};
+
+use crate::error::Result;
+use crate::output::OutputOptions;
+use crate::shared_expense::status_label;
+
+use super::user_dossier_command::UserDossier;
+
+/// Prints a dossier as one section per source. Any format other than table prints the dossier
+/// as a single record.
+pub struct UserDossierUi<'a> {
+    dossier: &'a UserDossier,
+}
+
+impl<'a> UserDossierUi<'a> {
+    pub fn new(dossier: &'a UserDossier) -> Self {
+        Self { dossier }
+    }
+
+    pub fn render(&self, output: &OutputOptions) -> Result<()> {
+        if !output.is_table() {
+            return output.render(std::slice::from_ref(self.dossier));
+        }
+
+        let user = &self.dossier.user;
+        println!("{} {} <{}>", user.first_name, user.last_name, user.email);
+        self.render_red_flags()?;
+
+        println!();
+        println!("Profile");
+        self.render_profile()?;
+
+        println!();
+        println!("Accounts");
+        if self.dossier.accounts.is_empty() {
+            println!("No accounts have been linked");
+        } else {
+            self.render_accounts()?;
+        }
+
+        println!();
+        println!("Relationships");
+        if self.dossier.relationships.is_empty() {
+            println!("No relationships");
+        } else {
+            output.render(&self.dossier.relationships)?;
+        }
+
+        println!();
+        println!("Agreements");
+        if self.dossier.agreements.is_empty() {
+            println!("No agreements");
+        } else {
+            self.render_agreements()?;
+        }
+
+        println!();
+        println!("Transactions");
+        if self.dossier.transactions.is_empty() {
+            println!("No transactions");
+        } else {
+            self.render_transactions()?;
+        }
+
+        Ok(())
+    }
+
+    fn render_red_flags(&self) -> Result<()> {
+        if self.dossier.red_flags.is_empty() {
+            println!("No red flags");
+            return Ok(());
+        }
+
+        let data: Vec<Vec<CellStruct>> = self
+            .dossier
+            .red_flags
+            .iter()
+            .map(|red_flag| {
+                vec![red_flag
+                    .clone()
+                    .cell()
+                    .foreground_color(Some(Color::Red))
+                    .bold(true)]
+            })
+            .collect();
+        let table = data
+            .table()
+            .title(vec!["Red Flags".cell().bold(true)])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+
+    fn render_profile(&self) -> Result<()> {
+        let user = &self.dossier.user;
+        let address = [
+            user.address_one.as_deref(),
+            user.address_two.as_deref(),
+            user.city.as_deref(),
+            user.state.as_deref(),
+            user.postal_code.as_deref(),
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(", ");
+        let data: Vec<Vec<CellStruct>> = vec![
+            vec!["ID".cell(), user.id.cell()],
+            vec!["UUID".cell(), user.uuid.clone().cell()],
+            vec!["Joined".cell(), user.date_time_created.clone().cell()],
+            vec!["Email Confirmed".cell(), user.email_is_confirmed.cell()],
+            vec!["Can Receive Funds".cell(), user.can_receive_funds.cell()],
+            vec![
+                "Dwolla Reverification Needed".cell(),
+                user.dwolla_reverification_needed.cell(),
+            ],
+            vec![
+                "Accepted Terms".cell(),
+                user.accepted_terms_of_service.cell(),
+            ],
+            vec![
+                "Accepted Privacy Policy".cell(),
+                user.accepted_privacy_policy.cell(),
+            ],
+            vec!["Address".cell(), address.cell()],
+        ];
+
+        Ok(print_stdout(data.table().bold(true))?)
+    }
+
+    fn render_accounts(&self) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = self
+            .dossier
+            .accounts
+            .iter()
+            .map(|account| {
+                let re_authentication = account
+                    .requires_plaid_re_authentication
+                    .cell()
+                    .foreground_color(
+                        Some(Color::Red).filter(|_| account.requires_plaid_re_authentication),
+                    );
+
+                vec![
+                    account.id.cell(),
+                    account.institution_name.clone().cell(),
+                    account.account_name.clone().cell(),
+                    format!("{} {}", account.account_type, account.account_sub_type).cell(),
+                    account.is_active.cell(),
+                    re_authentication,
+                    account.dwolla_funding_source_id.is_some().cell(),
+                    account
+                        .date_of_last_plaid_transaction_pull
+                        .clone()
+                        .unwrap_or_default()
+                        .cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "Institution".cell().bold(true),
+                "Name".cell().bold(true),
+                "Type".cell().bold(true),
+                "Active".cell().bold(true),
+                "Requires Re-Authentication".cell().bold(true),
+                "Funding Source".cell().bold(true),
+                "Last Transaction Pull".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+
+    fn render_agreements(&self) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = self
+            .dossier
+            .agreements
+            .iter()
+            .map(|story| {
+                let agreement = &story.user_agreement;
+                vec![
+                    agreement.id.cell(),
+                    story.story.shared_expense.expense_nick_name.clone().cell(),
+                    story
+                        .story
+                        .vendor
+                        .as_ref()
+                        .map(|vendor| vendor.friendly_name.clone())
+                        .unwrap_or_default()
+                        .cell(),
+                    story.story.initiating_user.email.clone().cell(),
+                    agreement
+                        .contribution()
+                        .map(|contribution| contribution.to_string())
+                        .unwrap_or_default()
+                        .cell(),
+                    status_label(agreement.is_active, agreement.is_pending).cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "Expense".cell().bold(true),
+                "Vendor".cell().bold(true),
+                "Owner".cell().bold(true),
+                "Contribution".cell().bold(true),
+                "Status".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+
+    fn render_transactions(&self) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = self
+            .dossier
+            .transactions
+            .iter()
+            .map(|story| {
+                let transaction = &story.transaction;
+
+                vec![
+                    transaction.id.cell(),
+                    transaction.date_time_initiated.clone().cell(),
+                    story.shared_expense.expense_nick_name.clone().cell(),
+                    story.payer.email.clone().cell(),
+                    story.recipient.email.clone().cell(),
+                    transaction.total_transaction_amount.to_string().cell(),
+                    transaction.dwolla_status.clone().unwrap_or_default().cell(),
+                    transaction.number_of_times_attempted.cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "Initiated".cell().bold(true),
+                "Expense".cell().bold(true),
+                "Payer".cell().bold(true),
+                "Recipient".cell().bold(true),
+                "Amount".cell().bold(true),
+                "Dwolla Status".cell().bold(true),
+                "Attempts".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+}
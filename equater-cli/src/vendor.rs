@@ -1,8 +1,12 @@
+use clap::ValueEnum;
 use fake::uuid::UUIDv5;
 use fake::{Dummy, Fake};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug, Deserialize, Dummy)]
+use crate::output::Columns;
+
+#[derive(Debug, Deserialize, Serialize, Dummy)]
 #[serde(rename_all = "camelCase")]
 pub struct Vendor {
     pub id: u32,
@@ -22,8 +26,180 @@ pub struct Vendor {
     pub logo_sha256_hash: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Dummy)]
+impl Columns for Vendor {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "friendly_name",
+            "uuid",
+            "has_been_reviewed_internally",
+            "vendor_identity_cannot_be_determined",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "id",
+            "uuid",
+            "ppd_id",
+            "date_time_added",
+            "date_time_modified",
+            "total_number_of_expense_sharing_agreements",
+            "has_been_reviewed_internally",
+            "vendor_identity_cannot_be_determined",
+            "friendly_name",
+            "logo_s3_bucket",
+            "logo_s3_key",
+            "logo_url",
+            "logo_upload_completed",
+            "logo_sha256_hash",
+        ]
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Dummy)]
 #[serde(rename_all = "camelCase")]
 pub struct VendorResponse {
     pub vendors: Vec<Vendor>,
 }
+
+/// Response of GET /api/vendor?page=N. Pages start at 0 and hold up to 100 vendors. `next_page`
+/// is a full url that's null on the last page.
+#[derive(Debug, Deserialize, Serialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorPage {
+    pub vendors: Vec<Vendor>,
+    pub next_page: Option<String>,
+    pub previous_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct SingleVendorResponse {
+    pub vendor: Vendor,
+}
+
+/// Response of GET /api/vendor/:id/logo-upload-url
+#[derive(Debug, Deserialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoUploadUrlResponse {
+    pub pre_signed_upload_url: String,
+}
+
+/// Body of PATCH /api/vendor/:id. Patching a vendor also marks it as reviewed internally.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchVendorDto {
+    pub friendly_name: String,
+    pub pre_processed_logo_was_uploaded: bool,
+    pub ppd_id: Option<String>,
+    pub vendor_identity_cannot_be_determined: bool,
+}
+
+impl From<&Vendor> for PatchVendorDto {
+    fn from(vendor: &Vendor) -> Self {
+        Self {
+            friendly_name: vendor.friendly_name.clone(),
+            pre_processed_logo_was_uploaded: false,
+            ppd_id: vendor.ppd_id.clone(),
+            vendor_identity_cannot_be_determined: vendor.vendor_identity_cannot_be_determined,
+        }
+    }
+}
+
+/// Body of PUT /api/vendor, which responds with the bare vendor. The client picks the uuid, and
+/// the new vendor is marked as reviewed internally.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVendorDto {
+    pub friendly_name: String,
+    pub uuid: String,
+}
+
+impl CreateVendorDto {
+    pub fn new(friendly_name: &str) -> Self {
+        Self {
+            friendly_name: String::from(friendly_name),
+            uuid: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+/// Mirrors UniqueVendorAssociationType in the API
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, Dummy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VendorAssociationType {
+    Other,
+    /// The associated vendor is the parent company, e.g. a property management group
+    ParentCompany,
+    /// The associated vendor is owned by this vendor
+    SubsidiaryCompany,
+}
+
+impl VendorAssociationType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            VendorAssociationType::Other => "Other",
+            VendorAssociationType::ParentCompany => "Parent Company",
+            VendorAssociationType::SubsidiaryCompany => "Subsidiary Company",
+        }
+    }
+}
+
+/// Lets charges from one vendor count towards bills split with another, e.g. charges from
+/// "TRG Management Group" for a bill split with "Icon Central"
+#[derive(Debug, Deserialize, Serialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorAssociation {
+    pub id: u32,
+    pub unique_vendor_id: u32,
+    pub associated_unique_vendor_id: u32,
+    pub association_type: VendorAssociationType,
+    pub notes: Option<String>,
+}
+
+/// Response of GET /api/vendor/:id/associations (as a list) and PUT /api/vendor/:id/associate-with/:id
+#[derive(Debug, Deserialize, Serialize, Dummy)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorAssociationResponse {
+    pub association: VendorAssociation,
+    pub vendor: Vendor,
+    pub associated_vendor: Vendor,
+}
+
+impl Columns for VendorAssociationResponse {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "association.id",
+            "vendor.id",
+            "vendor.friendly_name",
+            "associated_vendor.id",
+            "associated_vendor.friendly_name",
+            "association.association_type",
+            "association.notes",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["association", "vendor", "associated_vendor"]
+    }
+
+    fn table_label(column: &str, value: &Value) -> Option<String> {
+        if column != "association.association_type" {
+            return None;
+        }
+
+        serde_json::from_value::<VendorAssociationType>(value.clone())
+            .ok()
+            .map(|association_type| String::from(association_type.label()))
+    }
+}
+
+/// Body of PUT /api/vendor/:vendorId/associate-with/:associatedVendorId
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssociateVendorDto {
+    pub association_type: VendorAssociationType,
+    /// The API requires a string here, so no notes are sent as an empty string
+    pub notes: String,
+}
@@ -0,0 +1,6 @@
+pub mod vendor_apply_api;
+#[cfg(test)]
+pub mod vendor_apply_api_fake;
+pub mod vendor_apply_command;
+pub mod vendor_apply_file;
+mod vendor_apply_ui;
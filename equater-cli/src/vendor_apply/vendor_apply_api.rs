@@ -0,0 +1,49 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::vendor::{CreateVendorDto, PatchVendorDto, SingleVendorResponse, Vendor};
+use async_trait::async_trait;
+
+use super::vendor_apply_command::AppliesVendorChanges;
+
+pub struct VendorApplyApi {
+    client: EquaterClient,
+}
+
+impl VendorApplyApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl AppliesVendorChanges for VendorApplyApi {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor> {
+        let response = self
+            .client
+            .get(&format!("/api/vendor/{}", vendor_id))
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
+
+    async fn patch_vendor(&self, vendor_id: u32, dto: &PatchVendorDto) -> Result<Vendor> {
+        let response = self
+            .client
+            .patch(&format!("/api/vendor/{}", vendor_id))
+            .json(dto)
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
+
+    async fn create_vendor(&self, dto: &CreateVendorDto) -> Result<Vendor> {
+        self.client
+            .put("/api/vendor")
+            .json(dto)
+            .send_json::<Vendor>()
+            .await
+    }
+}
@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use fake::{Fake, Faker};
+
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+use crate::vendor::{CreateVendorDto, PatchVendorDto, Vendor};
+
+use super::vendor_apply_command::AppliesVendorChanges;
+
+/// Vendors are named "Vendor <id>", have been reviewed and have no ppd_id. Vendors 900 and up
+/// don't exist and patching vendors 800 to 899 fails. Created vendors get id 5000, and creating
+/// one named "Vendor <id>" is refused as a duplicate.
+pub struct VendorApplyApiFake {
+    pub should_error: bool,
+}
+
+#[async_trait]
+impl AppliesVendorChanges for VendorApplyApiFake {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        if vendor_id >= 900 {
+            return make_error_response(
+                404,
+                r#"{"statusCode": 404, "message": "Vendor not found", "error": "Not Found"}"#,
+            )
+            .await;
+        }
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = vendor_id;
+        vendor.friendly_name = format!("Vendor {}", vendor_id);
+        vendor.ppd_id = None;
+        vendor.has_been_reviewed_internally = true;
+        vendor.vendor_identity_cannot_be_determined = false;
+
+        Ok(vendor)
+    }
+
+    async fn patch_vendor(&self, vendor_id: u32, dto: &PatchVendorDto) -> Result<Vendor> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+        if (800..900).contains(&vendor_id) {
+            return make_error_response(
+                500,
+                r#"{"statusCode": 500, "message": "Internal server error"}"#,
+            )
+            .await;
+        }
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = vendor_id;
+        vendor.friendly_name = dto.friendly_name.clone();
+        vendor.ppd_id = dto.ppd_id.clone();
+        vendor.vendor_identity_cannot_be_determined = dto.vendor_identity_cannot_be_determined;
+        vendor.has_been_reviewed_internally = true;
+
+        Ok(vendor)
+    }
+
+    async fn create_vendor(&self, dto: &CreateVendorDto) -> Result<Vendor> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        if dto.friendly_name.starts_with("Vendor ") {
+            return make_error_response(
+                409,
+                r#"{"statusCode": 409, "message": "Vendor already exists at PUT /api/vendor"}"#,
+            )
+            .await;
+        }
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = 5000;
+        vendor.uuid = dto.uuid.clone();
+        vendor.friendly_name = dto.friendly_name.clone();
+        vendor.ppd_id = None;
+        vendor.vendor_identity_cannot_be_determined = false;
+        vendor.has_been_reviewed_internally = true;
+
+        Ok(vendor)
+    }
+}
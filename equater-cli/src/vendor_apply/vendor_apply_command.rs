@@ -0,0 +1,574 @@
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use serde::Serialize;
+
+use crate::commands::ApplyVendorsArgs;
+use crate::error::Result;
+use crate::output::{Columns, OutputOptions};
+use crate::vendor::{CreateVendorDto, PatchVendorDto, Vendor};
+
+use super::vendor_apply_file::{read_rows, write_rows, VendorRow};
+use super::vendor_apply_ui::VendorApplyUi;
+
+#[async_trait]
+pub trait AppliesVendorChanges {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor>;
+    async fn patch_vendor(&self, vendor_id: u32, dto: &PatchVendorDto) -> Result<Vendor>;
+    async fn create_vendor(&self, dto: &CreateVendorDto) -> Result<Vendor>;
+}
+
+/// One field that applying a row will change. New vendors have no id or current values.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorChange {
+    pub row: usize,
+    pub vendor_id: Option<u32>,
+    pub vendor: String,
+    pub field: String,
+    pub current: String,
+    pub new: String,
+}
+
+impl Columns for VendorChange {
+    fn default_columns() -> &'static [&'static str] {
+        &["row", "vendor_id", "vendor", "field", "current", "new"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["row", "vendor_id", "vendor", "field", "current", "new"]
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RowOutcome {
+    Updated,
+    Created,
+    Unchanged,
+    Failed,
+}
+
+/// What happened to one row of the file
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowResult {
+    pub row: usize,
+    pub vendor_id: Option<u32>,
+    pub friendly_name: String,
+    pub result: RowOutcome,
+    pub error: Option<String>,
+    /// Written to the failures file when the row fails
+    #[serde(skip)]
+    pub source: VendorRow,
+}
+
+impl Columns for RowResult {
+    fn default_columns() -> &'static [&'static str] {
+        &["row", "vendor_id", "friendly_name", "result", "error"]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["row", "vendor_id", "friendly_name", "result", "error"]
+    }
+}
+
+/// What applying a row will do, worked out from the vendor's current state
+#[derive(Debug)]
+enum Plan {
+    Update {
+        vendor: Vendor,
+        dto: PatchVendorDto,
+    },
+    /// PUT /api/vendor only takes a name, so the rest of the row is patched in afterwards
+    Create {
+        dto: CreateVendorDto,
+        ppd_id: Option<String>,
+        vendor_identity_cannot_be_determined: bool,
+    },
+    Unchanged(Vendor),
+    /// The vendor couldn't be fetched, so there's nothing to compare the row against
+    Failed(String),
+}
+
+#[derive(Debug)]
+struct PlannedRow {
+    /// Starts at 1, not counting a CSV header
+    number: usize,
+    row: VendorRow,
+    plan: Plan,
+}
+
+/// Edits and creates vendors in bulk from a file. Nothing is sent until every row has been read,
+/// compared against the vendor's current state and the operator has confirmed the diff.
+pub struct VendorApplyCommand<T: AppliesVendorChanges> {
+    remote_repository: T,
+    /// Set by `--yes`, which skips the confirmation
+    assume_yes: bool,
+}
+
+impl<T: AppliesVendorChanges + Sync> VendorApplyCommand<T> {
+    pub fn new(remote_repository: T, assume_yes: bool) -> Self {
+        Self {
+            remote_repository,
+            assume_yes,
+        }
+    }
+
+    /// Machine readable output holds a single listing, so it's the diff on a dry run and the
+    /// result report otherwise. Tables show both.
+    pub async fn apply(&self, args: &ApplyVendorsArgs, output: &OutputOptions) -> Result<String> {
+        let rows = read_rows(&args.file)?;
+        let concurrency = args.concurrency as usize;
+        let planned = self.plan(rows, concurrency).await?;
+        let changes: Vec<VendorChange> = planned.iter().flat_map(describe_changes).collect();
+        let updates = count(&planned, |plan| matches!(plan, Plan::Update { .. }));
+        let creates = count(&planned, |plan| matches!(plan, Plan::Create { .. }));
+
+        if args.dry_run || output.is_table() {
+            VendorApplyUi::render_changes(&changes, output)?;
+        }
+        for planned_row in &planned {
+            if let Plan::Failed(error) = &planned_row.plan {
+                VendorApplyUi::warn(planned_row.number, error);
+            }
+        }
+
+        if args.dry_run {
+            return Ok(format!(
+                "Dry run -- {} to update and {} to create. Nothing was changed.",
+                updates, creates
+            ));
+        }
+        if updates + creates > 0 && !self.assume_yes && !VendorApplyUi::confirm(updates, creates)? {
+            return Ok(String::from("Cancelled -- nothing was changed"));
+        }
+
+        let results = self.apply_rows(planned, concurrency).await;
+        VendorApplyUi::render_results(&results, output)?;
+
+        if let Some(path) = &args.failures {
+            let failures: Vec<VendorRow> = results
+                .iter()
+                .filter(|result| result.result == RowOutcome::Failed)
+                .map(|result| result.source.clone())
+                .collect();
+            write_rows(path, &failures)?;
+        }
+
+        Ok(summarize(&results, args))
+    }
+
+    /// Fetches the vendors in file order. A vendor that can't be fetched only fails its own row,
+    /// but a rejected session stops everything so that the command can be retried after signing
+    /// in again.
+    async fn plan(&self, rows: Vec<VendorRow>, concurrency: usize) -> Result<Vec<PlannedRow>> {
+        stream::iter(rows.into_iter().enumerate())
+            .map(|(index, row)| async move {
+                let plan = self.plan_row(&row).await?;
+
+                Ok(PlannedRow {
+                    number: index + 1,
+                    row,
+                    plan,
+                })
+            })
+            .buffered(concurrency)
+            .collect::<Vec<Result<PlannedRow>>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn plan_row(&self, row: &VendorRow) -> Result<Plan> {
+        let vendor_id = match row.id {
+            Some(vendor_id) => vendor_id,
+            None => {
+                return Ok(Plan::Create {
+                    dto: CreateVendorDto::new(row.friendly_name.as_deref().unwrap_or_default()),
+                    ppd_id: row.ppd_id.clone(),
+                    vendor_identity_cannot_be_determined: row
+                        .vendor_identity_cannot_be_determined
+                        .unwrap_or(false),
+                })
+            }
+        };
+
+        let vendor = match self.remote_repository.fetch_vendor(vendor_id).await {
+            Ok(vendor) => vendor,
+            Err(err) if err.is_unauthorized() => return Err(err),
+            Err(err) => return Ok(Plan::Failed(err.to_string())),
+        };
+        let current = PatchVendorDto::from(&vendor);
+        let dto = PatchVendorDto {
+            friendly_name: row
+                .friendly_name
+                .clone()
+                .unwrap_or_else(|| current.friendly_name.clone()),
+            ppd_id: row.ppd_id.clone().or_else(|| current.ppd_id.clone()),
+            vendor_identity_cannot_be_determined: row
+                .vendor_identity_cannot_be_determined
+                .unwrap_or(current.vendor_identity_cannot_be_determined),
+            ..current.clone()
+        };
+
+        if dto == current && vendor.has_been_reviewed_internally {
+            Ok(Plan::Unchanged(vendor))
+        } else {
+            Ok(Plan::Update { vendor, dto })
+        }
+    }
+
+    /// Every error, even a rejected session, only fails its own row. Retrying the whole command
+    /// would create the new vendors a second time.
+    async fn apply_rows(&self, planned: Vec<PlannedRow>, concurrency: usize) -> Vec<RowResult> {
+        let mut results: Vec<RowResult> = stream::iter(planned)
+            .map(|planned_row| self.apply_row(planned_row))
+            .buffer_unordered(concurrency)
+            .collect()
+            .await;
+        results.sort_by_key(|result| result.row);
+
+        results
+    }
+
+    async fn apply_row(&self, planned_row: PlannedRow) -> RowResult {
+        let PlannedRow { number, row, plan } = planned_row;
+        let result = |vendor_id: Option<u32>, friendly_name: String, outcome, error| RowResult {
+            row: number,
+            vendor_id,
+            friendly_name,
+            result: outcome,
+            error,
+            source: row.clone(),
+        };
+
+        match plan {
+            Plan::Update { vendor, dto } => {
+                match self.remote_repository.patch_vendor(vendor.id, &dto).await {
+                    Ok(updated) => result(
+                        Some(updated.id),
+                        updated.friendly_name,
+                        RowOutcome::Updated,
+                        None,
+                    ),
+                    Err(err) => result(
+                        Some(vendor.id),
+                        dto.friendly_name,
+                        RowOutcome::Failed,
+                        Some(err.to_string()),
+                    ),
+                }
+            }
+            Plan::Create {
+                dto,
+                ppd_id,
+                vendor_identity_cannot_be_determined,
+            } => {
+                let created = match self.remote_repository.create_vendor(&dto).await {
+                    Ok(created) => created,
+                    Err(err) => {
+                        return result(
+                            None,
+                            dto.friendly_name,
+                            RowOutcome::Failed,
+                            Some(err.to_string()),
+                        )
+                    }
+                };
+                if ppd_id.is_none() && !vendor_identity_cannot_be_determined {
+                    return result(
+                        Some(created.id),
+                        created.friendly_name,
+                        RowOutcome::Created,
+                        None,
+                    );
+                }
+
+                let patch = PatchVendorDto {
+                    ppd_id,
+                    vendor_identity_cannot_be_determined,
+                    ..PatchVendorDto::from(&created)
+                };
+                match self
+                    .remote_repository
+                    .patch_vendor(created.id, &patch)
+                    .await
+                {
+                    Ok(updated) => result(
+                        Some(updated.id),
+                        updated.friendly_name,
+                        RowOutcome::Created,
+                        None,
+                    ),
+                    // Retrying the row as written would be refused as a duplicate, so the
+                    // failures file points at the vendor that was created instead
+                    Err(err) => RowResult {
+                        source: VendorRow {
+                            id: Some(created.id),
+                            ..row.clone()
+                        },
+                        ..result(
+                            Some(created.id),
+                            created.friendly_name,
+                            RowOutcome::Failed,
+                            Some(format!(
+                                "Created vendor {}, but couldn't set its other fields: {}",
+                                created.id, err
+                            )),
+                        )
+                    },
+                }
+            }
+            Plan::Unchanged(vendor) => result(
+                Some(vendor.id),
+                vendor.friendly_name,
+                RowOutcome::Unchanged,
+                None,
+            ),
+            Plan::Failed(error) => result(
+                row.id,
+                row.friendly_name.clone().unwrap_or_default(),
+                RowOutcome::Failed,
+                Some(error),
+            ),
+        }
+    }
+}
+
+fn count(planned: &[PlannedRow], predicate: impl Fn(&Plan) -> bool) -> usize {
+    planned
+        .iter()
+        .filter(|planned_row| predicate(&planned_row.plan))
+        .count()
+}
+
+/// Patching a vendor also marks it as reviewed, so that's listed as a change too
+fn describe_changes(planned_row: &PlannedRow) -> Vec<VendorChange> {
+    let change =
+        |vendor_id: Option<u32>, vendor: &str, field: &str, current: String, new: String| {
+            VendorChange {
+                row: planned_row.number,
+                vendor_id,
+                vendor: String::from(vendor),
+                field: String::from(field),
+                current,
+                new,
+            }
+        };
+
+    match &planned_row.plan {
+        Plan::Update { vendor, dto } => {
+            let fields = [
+                (
+                    "friendly_name",
+                    vendor.friendly_name.clone(),
+                    dto.friendly_name.clone(),
+                ),
+                (
+                    "ppd_id",
+                    vendor.ppd_id.clone().unwrap_or_default(),
+                    dto.ppd_id.clone().unwrap_or_default(),
+                ),
+                (
+                    "vendor_identity_cannot_be_determined",
+                    vendor.vendor_identity_cannot_be_determined.to_string(),
+                    dto.vendor_identity_cannot_be_determined.to_string(),
+                ),
+                (
+                    "has_been_reviewed_internally",
+                    vendor.has_been_reviewed_internally.to_string(),
+                    true.to_string(),
+                ),
+            ];
+
+            fields
+                .into_iter()
+                .filter(|(_, current, new)| current != new)
+                .map(|(field, current, new)| {
+                    change(Some(vendor.id), &vendor.friendly_name, field, current, new)
+                })
+                .collect()
+        }
+        Plan::Create {
+            dto,
+            ppd_id,
+            vendor_identity_cannot_be_determined,
+        } => {
+            let mut changes = vec![change(
+                None,
+                &dto.friendly_name,
+                "friendly_name",
+                String::new(),
+                dto.friendly_name.clone(),
+            )];
+            if let Some(ppd_id) = ppd_id {
+                changes.push(change(
+                    None,
+                    &dto.friendly_name,
+                    "ppd_id",
+                    String::new(),
+                    ppd_id.clone(),
+                ));
+            }
+            if *vendor_identity_cannot_be_determined {
+                changes.push(change(
+                    None,
+                    &dto.friendly_name,
+                    "vendor_identity_cannot_be_determined",
+                    String::new(),
+                    true.to_string(),
+                ));
+            }
+
+            changes
+        }
+        Plan::Unchanged(_) | Plan::Failed(_) => vec![],
+    }
+}
+
+fn summarize(results: &[RowResult], args: &ApplyVendorsArgs) -> String {
+    let count = |outcome: RowOutcome| {
+        results
+            .iter()
+            .filter(|result| result.result == outcome)
+            .count()
+    };
+    let failed = count(RowOutcome::Failed);
+    let mut summary = format!(
+        "{} updated, {} created, {} unchanged, {} failed",
+        count(RowOutcome::Updated),
+        count(RowOutcome::Created),
+        count(RowOutcome::Unchanged),
+        failed
+    );
+
+    match &args.failures {
+        Some(path) if failed > 0 => summary.push_str(&format!(
+            ". Apply {} to retry the failed rows.",
+            path.display()
+        )),
+        None if failed > 0 => {
+            summary.push_str(". Pass --failures <FILE> to save the failed rows for a retry.")
+        }
+        _ => {}
+    }
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use fake::{Fake, Faker};
+
+    use super::{describe_changes, Plan, PlannedRow, VendorApplyCommand, VendorChange};
+    use crate::commands::ApplyVendorsArgs;
+    use crate::output::{OutputFormat, OutputOptions};
+    use crate::vendor::{PatchVendorDto, Vendor};
+    use crate::vendor_apply::vendor_apply_api_fake::VendorApplyApiFake;
+    use crate::vendor_apply::vendor_apply_file::VendorRow;
+
+    fn make_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("equater-apply-{}-{}", std::process::id(), name))
+    }
+
+    fn make_args(file: PathBuf, failures: Option<PathBuf>) -> ApplyVendorsArgs {
+        ApplyVendorsArgs {
+            file,
+            dry_run: false,
+            concurrency: 2,
+            failures,
+        }
+    }
+
+    #[test]
+    fn it_should_only_list_the_fields_that_change() {
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = 12;
+        vendor.friendly_name = String::from("NETFLIX.COM");
+        vendor.ppd_id = Some(String::from("PPD-1"));
+        vendor.has_been_reviewed_internally = false;
+        vendor.vendor_identity_cannot_be_determined = false;
+        let dto = PatchVendorDto {
+            friendly_name: String::from("Netflix"),
+            ..PatchVendorDto::from(&vendor)
+        };
+        let planned_row = PlannedRow {
+            number: 3,
+            row: VendorRow::default(),
+            plan: Plan::Update { vendor, dto },
+        };
+
+        let changes = describe_changes(&planned_row);
+
+        let change = |field: &str, current: &str, new: &str| VendorChange {
+            row: 3,
+            vendor_id: Some(12),
+            vendor: String::from("NETFLIX.COM"),
+            field: String::from(field),
+            current: String::from(current),
+            new: String::from(new),
+        };
+        assert_eq!(
+            changes,
+            vec![
+                change("friendly_name", "NETFLIX.COM", "Netflix"),
+                change("has_been_reviewed_internally", "false", "true"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_report_every_row_and_save_the_failures() {
+        let file = make_path("rows.csv");
+        let failures = make_path("failures.csv");
+        fs::write(
+            &file,
+            "id,friendly_name,ppd_id,vendor_identity_cannot_be_determined
+12,Netflix,,
+13,Vendor 13,,
+,Acme Water,PPD-1,
+,Vendor 14,,
+950,Missing,,
+850,Broken,,true
+",
+        )
+        .unwrap();
+        let command = VendorApplyCommand::new(
+            VendorApplyApiFake {
+                should_error: false,
+            },
+            true,
+        );
+        let output = OutputOptions {
+            format: OutputFormat::Json,
+            columns: None,
+        };
+
+        let summary = command
+            .apply(&make_args(file.clone(), Some(failures.clone())), &output)
+            .await
+            .unwrap();
+        let saved = fs::read_to_string(&failures).unwrap();
+        fs::remove_file(&file).ok();
+        fs::remove_file(&failures).ok();
+
+        assert_eq!(
+            summary,
+            format!(
+                "1 updated, 1 created, 1 unchanged, 3 failed. Apply {} to retry the failed rows.",
+                failures.display()
+            )
+        );
+        assert_eq!(
+            saved,
+            "id,friendly_name,ppd_id,vendor_identity_cannot_be_determined
+,Vendor 14,,
+950,Missing,,
+850,Broken,,true
+"
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EquaterError, Result};
+
+/// One row of a `vendors apply` file. Rows with an `id` edit that vendor and rows without one
+/// create a vendor. Empty fields leave the vendor's current value alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct VendorRow {
+    #[serde(default)]
+    pub id: Option<u32>,
+    #[serde(default)]
+    pub friendly_name: Option<String>,
+    #[serde(default)]
+    pub ppd_id: Option<String>,
+    #[serde(default)]
+    pub vendor_identity_cannot_be_determined: Option<bool>,
+}
+
+/// Picked from the file's extension
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RowFormat {
+    Csv,
+    /// A JSON array of rows
+    Json,
+    /// One JSON row per line
+    Ndjson,
+}
+
+impl RowFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+
+        match extension.as_deref() {
+            Some("csv") => Ok(RowFormat::Csv),
+            Some("json") => Ok(RowFormat::Json),
+            Some("ndjson") | Some("jsonl") => Ok(RowFormat::Ndjson),
+            _ => Err(EquaterError::Validation(format!(
+                "{} isn't a .csv, .json or .ndjson file",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Reads and validates every row before anything is sent to the API
+pub fn read_rows(path: &Path) -> Result<Vec<VendorRow>> {
+    let format = RowFormat::from_path(path)?;
+    let contents = fs::read_to_string(path).map_err(|err| {
+        EquaterError::Validation(format!("Couldn't read {}: {}", path.display(), err))
+    })?;
+    let rows = parse_rows(format, &contents)?;
+    validate_rows(&rows)?;
+
+    Ok(rows)
+}
+
+/// Rows are written in the same format they're read in, so a failures file can be applied again
+pub fn write_rows(path: &Path, rows: &[VendorRow]) -> Result<()> {
+    let contents = match RowFormat::from_path(path)? {
+        RowFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(vec![]);
+            for row in rows {
+                writer
+                    .serialize(row)
+                    .map_err(|err| EquaterError::Validation(err.to_string()))?;
+            }
+
+            writer
+                .into_inner()
+                .map_err(|err| EquaterError::Validation(err.to_string()))?
+        }
+        RowFormat::Json => {
+            let mut contents = serde_json::to_vec_pretty(rows)
+                .map_err(|err| EquaterError::Validation(err.to_string()))?;
+            contents.push(b'\n');
+
+            contents
+        }
+        RowFormat::Ndjson => rows
+            .iter()
+            .map(|row| serde_json::to_string(row).map(|line| line + "\n"))
+            .collect::<std::result::Result<String, _>>()
+            .map_err(|err| EquaterError::Validation(err.to_string()))?
+            .into_bytes(),
+    };
+
+    fs::write(path, contents)?;
+
+    Ok(())
+}
+
+/// Row numbers in errors start at 1 and don't count the CSV header
+fn parse_rows(format: RowFormat, contents: &str) -> Result<Vec<VendorRow>> {
+    let invalid_row = |number: usize, err: &dyn std::fmt::Display| {
+        EquaterError::Validation(format!("Row {}: {}", number, err))
+    };
+
+    let rows = match format {
+        RowFormat::Csv => csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(contents.as_bytes())
+            .deserialize::<VendorRow>()
+            .enumerate()
+            .map(|(index, row)| row.map_err(|err| invalid_row(index + 1, &err)))
+            .collect::<Result<Vec<VendorRow>>>()?,
+        RowFormat::Json => serde_json::from_str(contents).map_err(|err| {
+            EquaterError::Validation(format!("Expected an array of rows: {}", err))
+        })?,
+        RowFormat::Ndjson => contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|err| invalid_row(index + 1, &err))
+            })
+            .collect::<Result<Vec<VendorRow>>>()?,
+    };
+
+    Ok(rows.into_iter().map(normalize).collect())
+}
+
+/// A blank ppd_id means "leave it alone", the same as a missing one. A blank friendly_name is
+/// kept so that validation can reject it.
+fn normalize(mut row: VendorRow) -> VendorRow {
+    row.friendly_name = row
+        .friendly_name
+        .map(|friendly_name| friendly_name.trim().to_string());
+    row.ppd_id = row
+        .ppd_id
+        .map(|ppd_id| ppd_id.trim().to_string())
+        .filter(|ppd_id| !ppd_id.is_empty());
+
+    row
+}
+
+fn validate_rows(rows: &[VendorRow]) -> Result<()> {
+    if rows.is_empty() {
+        return Err(EquaterError::Validation(String::from(
+            "The file doesn't have any rows",
+        )));
+    }
+
+    let mut ids = HashSet::new();
+    for (index, row) in rows.iter().enumerate() {
+        let number = index + 1;
+        let friendly_name = row.friendly_name.as_deref();
+
+        if friendly_name == Some("") {
+            return Err(EquaterError::Validation(format!(
+                "Row {}: friendly_name can't be blank",
+                number
+            )));
+        }
+        match row.id {
+            Some(id) if !ids.insert(id) => {
+                return Err(EquaterError::Validation(format!(
+                    "Row {}: vendor {} appears more than once",
+                    number, id
+                )));
+            }
+            None if friendly_name.is_none() => {
+                return Err(EquaterError::Validation(format!(
+                    "Row {}: new vendors need a friendly_name",
+                    number
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_rows, validate_rows, RowFormat, VendorRow};
+    use crate::error::EquaterError;
+
+    #[test]
+    fn it_should_leave_empty_csv_fields_unset() {
+        let rows = parse_rows(
+            RowFormat::Csv,
+            "id,friendly_name,ppd_id,vendor_identity_cannot_be_determined
+12, Netflix ,,
+,Acme Water,PPD-1,true
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                VendorRow {
+                    id: Some(12),
+                    friendly_name: Some(String::from("Netflix")),
+                    ppd_id: None,
+                    vendor_identity_cannot_be_determined: None,
+                },
+                VendorRow {
+                    id: None,
+                    friendly_name: Some(String::from("Acme Water")),
+                    ppd_id: Some(String::from("PPD-1")),
+                    vendor_identity_cannot_be_determined: Some(true),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_reject_unknown_fields() {
+        let result = parse_rows(
+            RowFormat::Ndjson,
+            "{\"id\": 12}\n{\"id\": 13, \"friendlyName\": \"Netflix\"}\n",
+        );
+
+        assert!(
+            matches!(result, Err(EquaterError::Validation(message)) if message.starts_with("Row 2: unknown field `friendlyName`"))
+        );
+    }
+
+    #[test]
+    fn it_should_reject_new_vendors_without_a_name() {
+        let result = validate_rows(&[VendorRow {
+            ppd_id: Some(String::from("PPD-1")),
+            ..VendorRow::default()
+        }]);
+
+        assert!(
+            matches!(result, Err(EquaterError::Validation(message)) if message == "Row 1: new vendors need a friendly_name")
+        );
+    }
+}
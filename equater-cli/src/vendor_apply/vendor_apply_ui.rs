@@ -0,0 +1,40 @@
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::error::Result;
+use crate::output::OutputOptions;
+
+use super::vendor_apply_command::{RowResult, VendorChange};
+
+pub struct VendorApplyUi;
+
+impl VendorApplyUi {
+    pub fn render_changes(changes: &[VendorChange], output: &OutputOptions) -> Result<()> {
+        if changes.is_empty() && output.is_table() {
+            println!("Every vendor already matches the file");
+            return Ok(());
+        }
+
+        output.render(changes)
+    }
+
+    pub fn render_results(results: &[RowResult], output: &OutputOptions) -> Result<()> {
+        output.render(results)
+    }
+
+    /// Goes to stderr so that it doesn't end up in piped output
+    pub fn warn(row: usize, error: &str) {
+        eprintln!("Row {} will be skipped: {}", row, error);
+    }
+
+    pub fn confirm(updates: usize, creates: usize) -> Result<bool> {
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Update {} and create {} vendor(s)?",
+                updates, creates
+            ))
+            .default(false)
+            .interact()?;
+
+        Ok(confirmed)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod vendor_associations_command;
+mod vendor_associations_ui;
@@ -0,0 +1,197 @@
+use crate::commands::{AssociationsCommand, CreateAssociationArgs, MergeVendorsArgs};
+use crate::error::{EquaterError, Result};
+use crate::fetch_vendors::fetch_vendors_command::ManagesVendorAssociations;
+use crate::output::OutputOptions;
+use crate::vendor::{AssociateVendorDto, Vendor};
+
+use super::vendor_associations_ui::{MergeVendorsUi, VendorAssociationsUi};
+
+pub struct VendorAssociationsCommand<T: ManagesVendorAssociations> {
+    remote_repository: T,
+    /// Set by `--yes`, which skips the merge confirmation
+    assume_yes: bool,
+}
+
+impl<T: ManagesVendorAssociations> VendorAssociationsCommand<T> {
+    pub fn new(remote_repository: T, assume_yes: bool) -> Self {
+        Self {
+            remote_repository,
+            assume_yes,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        command: &AssociationsCommand,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        match command {
+            AssociationsCommand::List { vendor_id } => {
+                let associations = self
+                    .remote_repository
+                    .fetch_associations(*vendor_id)
+                    .await?;
+                VendorAssociationsUi::new(associations).render(output)?;
+
+                Ok(String::from(""))
+            }
+            AssociationsCommand::Create(args) => self.create_association(args, output).await,
+            AssociationsCommand::Delete {
+                vendor_id,
+                association_id,
+            } => {
+                self.remote_repository
+                    .delete_association(*vendor_id, *association_id)
+                    .await?;
+
+                Ok(format!(
+                    "Removed association {} from vendor {}",
+                    association_id, vendor_id
+                ))
+            }
+            AssociationsCommand::Merge(args) => self.merge_vendors(args).await,
+        }
+    }
+
+    async fn create_association(
+        &self,
+        args: &CreateAssociationArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        if args.vendor_id == args.associated_vendor_id {
+            return Err(EquaterError::Validation(String::from(
+                "A vendor can't be associated with itself",
+            )));
+        }
+
+        let dto = AssociateVendorDto {
+            association_type: args.association_type,
+            notes: args.notes.clone().unwrap_or_default(),
+        };
+        let association = self
+            .remote_repository
+            .associate_vendors(args.vendor_id, args.associated_vendor_id, &dto)
+            .await?;
+        VendorAssociationsUi::new(vec![association]).render(output)?;
+
+        Ok(String::from(""))
+    }
+
+    /// Merging deletes the vendor, so both vendors are shown and the operator has to confirm
+    /// before anything is sent to the API
+    async fn merge_vendors(&self, args: &MergeVendorsArgs) -> Result<String> {
+        let (vendor, existing_vendor) = self.preview_merge(args).await?;
+        let ui = MergeVendorsUi::new(&vendor, &existing_vendor);
+        ui.render()?;
+
+        if !self.assume_yes && !ui.confirm()? {
+            return Ok(String::from("Merge cancelled -- nothing was changed"));
+        }
+
+        let merged = self
+            .remote_repository
+            .assign_to_existing_vendor(vendor.id, existing_vendor.id)
+            .await?;
+
+        Ok(format!(
+            "Merged {} ({}) into {} ({})",
+            vendor.friendly_name, vendor.id, merged.friendly_name, merged.id
+        ))
+    }
+
+    async fn preview_merge(&self, args: &MergeVendorsArgs) -> Result<(Vendor, Vendor)> {
+        if args.vendor_id == args.into {
+            return Err(EquaterError::Validation(String::from(
+                "A vendor can't be merged into itself",
+            )));
+        }
+
+        tokio::try_join!(
+            self.remote_repository.fetch_vendor(args.vendor_id),
+            self.remote_repository.fetch_vendor(args.into)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VendorAssociationsCommand;
+    use crate::commands::{AssociationsCommand, MergeVendorsArgs};
+    use crate::fetch_vendors::fetch_vendors_api_fake::FetchVendorsApiFake;
+    use crate::output::{OutputFormat, OutputOptions};
+    use crate::vendor::{VendorAssociationResponse, VendorAssociationType};
+    use fake::{Fake, Faker};
+
+    fn make_merge_args(vendor_id: u32, into: u32) -> MergeVendorsArgs {
+        MergeVendorsArgs { vendor_id, into }
+    }
+
+    #[tokio::test]
+    async fn it_should_preview_both_vendors_before_merging() {
+        let command = VendorAssociationsCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            true,
+        );
+        let (vendor, existing_vendor) = command
+            .preview_merge(&make_merge_args(12, 34))
+            .await
+            .unwrap();
+
+        assert_eq!(vendor.id, 12);
+        assert_eq!(existing_vendor.id, 34);
+    }
+
+    #[tokio::test]
+    async fn it_should_refuse_to_merge_a_vendor_into_itself() {
+        let command = VendorAssociationsCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            true,
+        );
+        let result = command.merge_vendors(&make_merge_args(12, 12)).await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let command =
+            VendorAssociationsCommand::new(FetchVendorsApiFake { should_error: true }, true);
+        let result = command
+            .execute(
+                &AssociationsCommand::Delete {
+                    vendor_id: 12,
+                    association_id: 1,
+                },
+                &OutputOptions::default(),
+            )
+            .await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+    }
+
+    #[test]
+    fn it_should_label_association_types_in_tables_only() {
+        let mut association: VendorAssociationResponse = Faker.fake();
+        association.association.association_type = VendorAssociationType::ParentCompany;
+        let render = |format| {
+            let options = OutputOptions {
+                format,
+                columns: Some(vec![String::from("association.association_type")]),
+            };
+            let mut out = Vec::new();
+            options.render_to(&[&association], &mut out).unwrap();
+
+            String::from_utf8(out).unwrap()
+        };
+
+        assert!(render(OutputFormat::Table).contains("Parent Company"));
+        assert_eq!(
+            render(OutputFormat::Csv),
+            "association.association_type\nPARENT_COMPANY\n"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use cli_table::{print_stdout, Cell, CellStruct, Style, Table};
+use dialoguer::{theme::ColorfulTheme, Confirm};
+
+use crate::output::OutputOptions;
+use crate::vendor::{Vendor, VendorAssociationResponse};
+use std::io::Result;
+
+pub struct VendorAssociationsUi {
+    items: Vec<VendorAssociationResponse>,
+}
+
+impl VendorAssociationsUi {
+    pub fn new(associations: Vec<VendorAssociationResponse>) -> Self {
+        Self {
+            items: associations,
+        }
+    }
+
+    pub fn render(&self, output: &OutputOptions) -> crate::error::Result<()> {
+        if self.items.is_empty() && output.is_table() {
+            println!("This vendor has no associations");
+            return Ok(());
+        }
+
+        output.render(&self.items)
+    }
+}
+
+/// Shows what a merge will do before it happens. There's no undo for a merge.
+pub struct MergeVendorsUi<'a> {
+    vendor: &'a Vendor,
+    existing_vendor: &'a Vendor,
+}
+
+impl<'a> MergeVendorsUi<'a> {
+    pub fn new(vendor: &'a Vendor, existing_vendor: &'a Vendor) -> Self {
+        Self {
+            vendor,
+            existing_vendor,
+        }
+    }
+
+    pub fn render(&self) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = vec![
+            self.vendor_to_cell("Deleted", self.vendor),
+            self.vendor_to_cell("Kept", self.existing_vendor),
+        ];
+
+        let table = data
+            .table()
+            .title(vec![
+                "After Merge".cell().bold(true),
+                "ID".cell().bold(true),
+                "Name".cell().bold(true),
+                "Has Been Reviewed".cell().bold(true),
+                "Bills Split".cell().bold(true),
+            ])
+            .bold(true);
+
+        print_stdout(table)?;
+        println!(
+            "Every transaction and transaction name matched to {} will be moved to {}.",
+            self.vendor.friendly_name, self.existing_vendor.friendly_name
+        );
+        if !self.vendor.has_been_reviewed_internally {
+            println!(
+                "{} hasn't been reviewed, so its transactions will also be processed against bills split with {}.",
+                self.vendor.friendly_name, self.existing_vendor.friendly_name
+            );
+        }
+
+        Ok(())
+    }
+
+    pub fn confirm(&self) -> Result<bool> {
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Merge {} into {}? This can't be undone",
+                self.vendor.friendly_name, self.existing_vendor.friendly_name
+            ))
+            .default(false)
+            .interact()
+    }
+
+    fn vendor_to_cell(&self, label: &str, vendor: &Vendor) -> Vec<CellStruct> {
+        vec![
+            label.cell(),
+            vendor.id.cell(),
+            vendor.friendly_name.clone().cell(),
+            vendor.has_been_reviewed_internally.cell(),
+            vendor.total_number_of_expense_sharing_agreements.cell(),
+        ]
+    }
+}
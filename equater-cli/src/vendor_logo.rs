@@ -0,0 +1,5 @@
+pub mod logo_file;
+pub mod vendor_logo_api;
+#[cfg(test)]
+mod vendor_logo_api_fake;
+pub mod vendor_logo_command;
@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ring::digest::{digest, SHA256};
+
+use crate::error::{EquaterError, Result};
+
+/// The API resizes logos with sharp, so anything sharp can decode is accepted. Large files are
+/// rejected up front since the processed logo is only a few hundred pixels wide.
+pub const MAX_LOGO_SIZE_IN_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogoImageType {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+}
+
+impl LogoImageType {
+    /// Detects the image type from the file's magic bytes rather than trusting the extension
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            Some(LogoImageType::Png)
+        } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            Some(LogoImageType::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(LogoImageType::Gif)
+        } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(LogoImageType::Webp)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            LogoImageType::Png => "image/png",
+            LogoImageType::Jpeg => "image/jpeg",
+            LogoImageType::Gif => "image/gif",
+            LogoImageType::Webp => "image/webp",
+        }
+    }
+}
+
+/// A validated logo that's been read into memory, along with the SHA-256 of its contents
+#[derive(Debug, Clone)]
+pub struct LogoFile {
+    pub path: PathBuf,
+    pub image_type: LogoImageType,
+    pub bytes: Vec<u8>,
+    pub sha256: String,
+}
+
+impl LogoFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).map_err(|err| {
+            EquaterError::Validation(format!("Could not read {}: {}", path.display(), err))
+        })?;
+
+        Self::from_bytes(path, bytes)
+    }
+
+    pub fn from_bytes(path: &Path, bytes: Vec<u8>) -> Result<Self> {
+        if bytes.is_empty() {
+            return Err(EquaterError::Validation(format!(
+                "{} is empty",
+                path.display()
+            )));
+        }
+
+        if bytes.len() > MAX_LOGO_SIZE_IN_BYTES {
+            return Err(EquaterError::Validation(format!(
+                "{} is {} -- logos can be at most {}",
+                path.display(),
+                format_size(bytes.len()),
+                format_size(MAX_LOGO_SIZE_IN_BYTES)
+            )));
+        }
+
+        let image_type = LogoImageType::sniff(&bytes).ok_or_else(|| {
+            EquaterError::Validation(format!(
+                "{} isn't a PNG, JPEG, GIF or WebP image",
+                path.display()
+            ))
+        })?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            image_type,
+            sha256: sha256_hex(&bytes),
+            bytes,
+        })
+    }
+
+    pub fn size(&self) -> String {
+        format_size(self.bytes.len())
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    digest(&SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn format_size(size_in_bytes: usize) -> String {
+    if size_in_bytes < 1024 {
+        format!("{} B", size_in_bytes)
+    } else if size_in_bytes < 1024 * 1024 {
+        format!("{:.1} KB", size_in_bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", size_in_bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sha256_hex, LogoFile, LogoImageType, MAX_LOGO_SIZE_IN_BYTES};
+    use std::path::Path;
+
+    const PNG_HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    #[test]
+    fn it_should_hash_the_file_contents() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn it_should_detect_the_image_type_from_magic_bytes() {
+        let logo = LogoFile::from_bytes(Path::new("logo.jpg"), PNG_HEADER.to_vec()).unwrap();
+        assert_eq!(logo.image_type, LogoImageType::Png);
+        assert_eq!(logo.image_type.content_type(), "image/png");
+
+        let webp = b"RIFF\x24\x00\x00\x00WEBPVP8 ".to_vec();
+        assert_eq!(LogoImageType::sniff(&webp), Some(LogoImageType::Webp));
+    }
+
+    #[test]
+    fn it_should_reject_files_that_are_not_images() {
+        let error =
+            LogoFile::from_bytes(Path::new("logo.png"), b"<svg></svg>".to_vec()).unwrap_err();
+
+        assert_eq!(error.exit_code(), 2);
+        assert_eq!(
+            error.to_string(),
+            "logo.png isn't a PNG, JPEG, GIF or WebP image"
+        );
+    }
+
+    #[test]
+    fn it_should_reject_files_that_are_too_large() {
+        let mut bytes = PNG_HEADER.to_vec();
+        bytes.resize(MAX_LOGO_SIZE_IN_BYTES + 1, 0);
+        let error = LogoFile::from_bytes(Path::new("logo.png"), bytes).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "logo.png is 5.0 MB -- logos can be at most 5.0 MB"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use crate::equater_client::{read_empty, EquaterClient, SendJson};
+use crate::error::Result;
+use crate::vendor::{LogoUploadUrlResponse, PatchVendorDto, SingleVendorResponse, Vendor};
+use async_trait::async_trait;
+use http::header::CONTENT_TYPE;
+
+use super::logo_file::LogoFile;
+use super::vendor_logo_command::UploadsVendorLogos;
+
+pub struct VendorLogoApi {
+    client: EquaterClient,
+}
+
+impl VendorLogoApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl UploadsVendorLogos for VendorLogoApi {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor> {
+        let response = self
+            .client
+            .get(&format!("/api/vendor/{}", vendor_id))
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
+
+    async fn fetch_logo_upload_url(&self, vendor_id: u32) -> Result<String> {
+        let response = self
+            .client
+            .get(&format!("/api/vendor/{}/logo-upload-url", vendor_id))
+            .send_json::<LogoUploadUrlResponse>()
+            .await?;
+
+        Ok(response.pre_signed_upload_url)
+    }
+
+    async fn upload_logo(&self, pre_signed_upload_url: &str, logo: &LogoFile) -> Result<()> {
+        let response = self
+            .client
+            .put_pre_signed(pre_signed_upload_url)
+            .header(CONTENT_TYPE, logo.image_type.content_type())
+            .body(logo.bytes.clone())
+            .send()
+            .await?;
+
+        read_empty(response).await
+    }
+
+    async fn mark_logo_uploaded(&self, vendor: &Vendor) -> Result<Vendor> {
+        let dto = PatchVendorDto {
+            pre_processed_logo_was_uploaded: true,
+            ..PatchVendorDto::from(vendor)
+        };
+        let response = self
+            .client
+            .patch(&format!("/api/vendor/{}", vendor.id))
+            .json(&dto)
+            .send_json::<SingleVendorResponse>()
+            .await?;
+
+        Ok(response.vendor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VendorLogoApi;
+    use crate::equater_client::EquaterClient;
+    use crate::vendor_logo::logo_file::{sha256_hex, LogoFile};
+    use crate::vendor_logo::vendor_logo_command::UploadsVendorLogos;
+    use std::path::Path;
+    use test_support::mock_server::MockServer;
+
+    const PNG_HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+    fn make_logo() -> LogoFile {
+        let mut bytes = PNG_HEADER.to_vec();
+        bytes.extend((0..64 * 1024).map(|index| (index % 251) as u8));
+
+        LogoFile::from_bytes(Path::new("logo.png"), bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_should_put_the_logo_to_the_pre_signed_url() {
+        let s3 = MockServer::start();
+        s3.respond_next("PUT", "/vendor-logos-pre-processing/uuid", 200, "");
+        let api = VendorLogoApi::new(
+            EquaterClient::new("http://localhost:7111")
+                .unwrap()
+                .with_auth_token("token"),
+        );
+        let logo = make_logo();
+        let url = format!(
+            "{}/vendor-logos-pre-processing/uuid?X-Amz-Signature=abc",
+            s3.url()
+        );
+
+        api.upload_logo(&url, &logo).await.unwrap();
+        let received = s3
+            .last_request("PUT", "/vendor-logos-pre-processing/uuid")
+            .unwrap();
+
+        assert_eq!(received.query_param("X-Amz-Signature"), Some("abc"));
+        assert_eq!(received.header("content-type"), Some("image/png"));
+        assert!(received.header("authorization").is_none());
+        assert_eq!(sha256_hex(&received.body_bytes), logo.sha256);
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_s3_errors() {
+        let s3 = MockServer::start();
+        s3.respond_next(
+            "PUT",
+            "/logo",
+            403,
+            "<Error><Code>SignatureDoesNotMatch</Code></Error>",
+        );
+        let api = VendorLogoApi::new(EquaterClient::new("http://localhost:7111").unwrap());
+
+        let error = api
+            .upload_logo(&format!("{}/logo", s3.url()), &make_logo())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.exit_code(), 6);
+        assert_eq!(
+            error.to_string(),
+            "The server responded with 403 Forbidden: <Error><Code>SignatureDoesNotMatch</Code></Error>"
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use fake::{Fake, Faker};
+
+use crate::error::Result;
+use crate::test_common::{make_error_response, UNAUTHORIZED_BODY};
+use crate::vendor::Vendor;
+
+use super::logo_file::{sha256_hex, LogoFile};
+use super::vendor_logo_command::UploadsVendorLogos;
+
+pub struct VendorLogoApiFake {
+    pub should_error: bool,
+}
+
+#[async_trait]
+impl UploadsVendorLogos for VendorLogoApiFake {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let mut vendor: Vendor = Faker.fake();
+        vendor.id = vendor_id;
+        vendor.logo_upload_completed = false;
+
+        Ok(vendor)
+    }
+
+    async fn fetch_logo_upload_url(&self, vendor_id: u32) -> Result<String> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        Ok(format!(
+            "http://localhost:4566/vendor-logos-pre-processing/{}",
+            vendor_id
+        ))
+    }
+
+    async fn upload_logo(&self, _pre_signed_upload_url: &str, _logo: &LogoFile) -> Result<()> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        Ok(())
+    }
+
+    async fn mark_logo_uploaded(&self, vendor: &Vendor) -> Result<Vendor> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        let mut processed: Vendor = Faker.fake();
+        processed.id = vendor.id;
+        processed.friendly_name = vendor.friendly_name.clone();
+        processed.logo_upload_completed = true;
+        processed.has_been_reviewed_internally = true;
+        processed.logo_sha256_hash = Some(sha256_hex(processed.uuid.as_bytes()));
+
+        Ok(processed)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::commands::UploadLogoArgs;
+use crate::error::Result;
+use crate::vendor::Vendor;
+use async_trait::async_trait;
+
+use super::logo_file::LogoFile;
+
+#[async_trait]
+pub trait UploadsVendorLogos {
+    async fn fetch_vendor(&self, vendor_id: u32) -> Result<Vendor>;
+    async fn fetch_logo_upload_url(&self, vendor_id: u32) -> Result<String>;
+    async fn upload_logo(&self, pre_signed_upload_url: &str, logo: &LogoFile) -> Result<()>;
+    /// Tells the API to resize the uploaded logo. This also marks the vendor as reviewed.
+    async fn mark_logo_uploaded(&self, vendor: &Vendor) -> Result<Vendor>;
+}
+
+pub struct VendorLogoCommand<T: UploadsVendorLogos> {
+    remote_repository: T,
+}
+
+impl<T: UploadsVendorLogos> VendorLogoCommand<T> {
+    pub fn new(remote_repository: T) -> Self {
+        Self { remote_repository }
+    }
+
+    /// The file is validated before anything is sent so that a bad file never replaces a logo
+    pub async fn upload_logo(&self, args: &UploadLogoArgs) -> Result<String> {
+        let logo = LogoFile::read(&args.file)?;
+        verbose!(
+            "{} is a {} ({}) with SHA-256 {}",
+            logo.path.display(),
+            logo.image_type.content_type(),
+            logo.size(),
+            logo.sha256
+        );
+
+        let vendor = self.remote_repository.fetch_vendor(args.vendor_id).await?;
+        let pre_signed_upload_url = self
+            .remote_repository
+            .fetch_logo_upload_url(vendor.id)
+            .await?;
+        self.remote_repository
+            .upload_logo(&pre_signed_upload_url, &logo)
+            .await?;
+        verbose!("Uploaded {} to S3", logo.path.display());
+
+        let vendor = self.remote_repository.mark_logo_uploaded(&vendor).await?;
+
+        Ok(format!(
+            "Uploaded {} ({}, SHA-256 {}) for {} ({}). Processed logo SHA-256: {}",
+            logo.path.display(),
+            logo.size(),
+            logo.sha256,
+            vendor.friendly_name,
+            vendor.id,
+            vendor.logo_sha256_hash.as_deref().unwrap_or("pending")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VendorLogoCommand;
+    use crate::commands::UploadLogoArgs;
+    use crate::vendor_logo::vendor_logo_api_fake::VendorLogoApiFake;
+    use std::path::PathBuf;
+
+    #[tokio::test]
+    async fn it_should_validate_the_file_before_calling_the_api() {
+        let command = VendorLogoCommand::new(VendorLogoApiFake { should_error: true });
+        let args = UploadLogoArgs {
+            vendor_id: 42,
+            file: PathBuf::from("Cargo.toml"),
+        };
+        let error = command.upload_logo(&args).await.unwrap_err();
+
+        // A 403 from the fake would exit with 6
+        assert_eq!(error.exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_report_the_hash_of_the_uploaded_file() {
+        let path = std::env::temp_dir().join("equater-cli-vendor-logo-test.png");
+        std::fs::write(&path, [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]).unwrap();
+        let command = VendorLogoCommand::new(VendorLogoApiFake {
+            should_error: false,
+        });
+        let args = UploadLogoArgs {
+            vendor_id: 42,
+            file: path.clone(),
+        };
+        let message = command.upload_logo(&args).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(message
+            .contains("SHA-256 4c4b6a3be1314ab86138bef4314dde022e600960d8689a2c8f8631802d20dab6"));
+    }
+}
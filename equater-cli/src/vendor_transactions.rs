@@ -0,0 +1,2 @@
+pub mod vendor_transactions_command;
+mod vendor_transactions_ui;
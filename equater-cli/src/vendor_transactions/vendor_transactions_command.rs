@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use database::models::money::Money;
+use serde::Serialize;
+
+use crate::commands::VendorTransactionsArgs;
+use crate::error::{EquaterError, Result};
+use crate::fetch_vendors::fetch_vendors_command::ManagesVendorAssociations;
+use crate::output::{Columns, OutputOptions};
+use crate::review_vendors::review_vendors_command::ReviewsVendors;
+use crate::transaction::TransactionWithContext;
+use crate::vendor::Vendor;
+
+use super::vendor_transactions_ui::VendorTransactionsUi;
+
+/// Summary of the transactions matched to a vendor. Amounts follow Plaid's convention, so
+/// charges are positive and refunds are negative.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatistics {
+    pub count: usize,
+    pub total: Money,
+    /// Rounded to the nearest cent. None when there are no transactions.
+    pub mean: Option<Money>,
+    /// The mean of the two middle amounts when there's an even number of transactions
+    pub median: Option<Money>,
+    /// ISO8601 date, e.g. 2022-05-14
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    pub distinct_payers: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VendorTransactionHistory {
+    pub vendor: Vendor,
+    pub statistics: TransactionStatistics,
+    /// Newest first
+    pub transactions: Vec<TransactionWithContext>,
+}
+
+impl Columns for VendorTransactionHistory {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "vendor.id",
+            "vendor.friendly_name",
+            "statistics.count",
+            "statistics.total",
+            "statistics.mean",
+            "statistics.median",
+            "statistics.first_seen",
+            "statistics.last_seen",
+            "statistics.distinct_payers",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &["vendor", "statistics", "transactions"]
+    }
+}
+
+/// Lists the transactions matched to a vendor. This is how we decide whether a vendor's identity
+/// can really be determined before flipping `vendor_identity_cannot_be_determined`.
+pub struct VendorTransactionsCommand<V: ManagesVendorAssociations, R: ReviewsVendors> {
+    vendor_repository: V,
+    remote_repository: R,
+}
+
+impl<V: ManagesVendorAssociations, R: ReviewsVendors> VendorTransactionsCommand<V, R> {
+    pub fn new(vendor_repository: V, remote_repository: R) -> Self {
+        Self {
+            vendor_repository,
+            remote_repository,
+        }
+    }
+
+    pub async fn show_transactions(
+        &self,
+        args: &VendorTransactionsArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let history = self.fetch_history(args).await?;
+        VendorTransactionsUi::new(output).render(&history)?;
+
+        Ok(String::from(""))
+    }
+
+    pub async fn fetch_history(
+        &self,
+        args: &VendorTransactionsArgs,
+    ) -> Result<VendorTransactionHistory> {
+        if let (Some(since), Some(until)) = (args.since, args.until) {
+            if since > until {
+                return Err(EquaterError::Validation(format!(
+                    "--since ({}) must be on or before --until ({})",
+                    since, until
+                )));
+            }
+        }
+
+        let (vendor, transactions) = tokio::try_join!(
+            self.vendor_repository.fetch_vendor(args.vendor_id),
+            self.remote_repository
+                .fetch_transactions_for_vendor(args.vendor_id),
+        )?;
+        let mut transactions = filter_by_date(transactions, args.since, args.until);
+        transactions.sort_by(|a, b| b.transaction.date.cmp(&a.transaction.date));
+        let statistics = summarize(&transactions);
+
+        Ok(VendorTransactionHistory {
+            vendor,
+            statistics,
+            transactions,
+        })
+    }
+}
+
+/// Both bounds are inclusive. Transactions with a date that can't be parsed are only kept when
+/// there's nothing to filter by.
+fn filter_by_date(
+    transactions: Vec<TransactionWithContext>,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> Vec<TransactionWithContext> {
+    if since.is_none() && until.is_none() {
+        return transactions;
+    }
+
+    transactions
+        .into_iter()
+        .filter(|context| {
+            context
+                .transaction
+                .date
+                .parse::<NaiveDate>()
+                .map(|date| {
+                    since.is_none_or(|since| date >= since)
+                        && until.is_none_or(|until| date <= until)
+                })
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn summarize(transactions: &[TransactionWithContext]) -> TransactionStatistics {
+    let mut amounts: Vec<i64> = transactions
+        .iter()
+        .map(|context| context.transaction.amount)
+        .collect();
+    amounts.sort_unstable();
+
+    let count = amounts.len();
+    let total: i64 = amounts.iter().sum();
+    let mean = (count > 0).then(|| Money::from_cents(divide_rounded(total, count as i64)));
+    let median = match count {
+        0 => None,
+        count if count % 2 == 1 => Some(Money::from_cents(amounts[count / 2])),
+        count => Some(Money::from_cents(divide_rounded(
+            amounts[count / 2 - 1] + amounts[count / 2],
+            2,
+        ))),
+    };
+    let dates = transactions.iter().map(|context| &context.transaction.date);
+    let payers: HashSet<u32> = transactions.iter().map(|context| context.user.id).collect();
+
+    TransactionStatistics {
+        count,
+        total: Money::from_cents(total),
+        mean,
+        median,
+        first_seen: dates.clone().min().cloned(),
+        last_seen: dates.max().cloned(),
+        distinct_payers: payers.len(),
+    }
+}
+
+/// Integer division rounded half away from zero, so a mean or median lands on the nearest cent
+fn divide_rounded(dividend: i64, divisor: i64) -> i64 {
+    let rounded = (dividend.abs() * 2 + divisor) / (divisor * 2);
+
+    rounded * dividend.signum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{filter_by_date, summarize, VendorTransactionsCommand};
+    use crate::commands::VendorTransactionsArgs;
+    use crate::fetch_vendors::fetch_vendors_api_fake::FetchVendorsApiFake;
+    use crate::review_vendors::review_vendors_api_fake::ReviewVendorsApiFake;
+    use crate::transaction::TransactionWithContext;
+    use chrono::NaiveDate;
+    use database::models::money::Money;
+    use fake::{Fake, Faker};
+
+    fn make_transaction(user_id: u32, amount: i64, date: &str) -> TransactionWithContext {
+        let mut context: TransactionWithContext = Faker.fake();
+        context.user.id = user_id;
+        context.transaction.amount = amount;
+        context.transaction.date = String::from(date);
+
+        context
+    }
+
+    fn make_args(since: Option<&str>, until: Option<&str>) -> VendorTransactionsArgs {
+        VendorTransactionsArgs {
+            vendor_id: 7,
+            since: since.map(|date| date.parse().unwrap()),
+            until: until.map(|date| date.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn it_should_summarize_transactions() {
+        let transactions = vec![
+            make_transaction(1, 1599, "2022-05-14"),
+            make_transaction(2, 1599, "2022-04-14"),
+            make_transaction(1, 1000, "2022-06-14"),
+            make_transaction(3, -500, "2022-03-02"),
+        ];
+
+        let statistics = summarize(&transactions);
+
+        assert_eq!(statistics.count, 4);
+        assert_eq!(statistics.total, Money::from_cents(3698));
+        assert_eq!(statistics.mean, Some(Money::from_cents(925)));
+        assert_eq!(statistics.median, Some(Money::from_cents(1300)));
+        assert_eq!(statistics.first_seen.as_deref(), Some("2022-03-02"));
+        assert_eq!(statistics.last_seen.as_deref(), Some("2022-06-14"));
+        assert_eq!(statistics.distinct_payers, 3);
+    }
+
+    #[test]
+    fn it_should_summarize_no_transactions() {
+        let statistics = summarize(&[]);
+
+        assert_eq!(statistics.count, 0);
+        assert_eq!(statistics.total, Money::ZERO);
+        assert!(statistics.mean.is_none());
+        assert!(statistics.median.is_none());
+        assert!(statistics.first_seen.is_none());
+        assert_eq!(statistics.distinct_payers, 0);
+    }
+
+    #[test]
+    fn it_should_filter_transactions_by_an_inclusive_date_range() {
+        let transactions = vec![
+            make_transaction(1, 100, "2022-04-30"),
+            make_transaction(1, 200, "2022-05-01"),
+            make_transaction(1, 300, "2022-05-31"),
+            make_transaction(1, 400, "2022-06-01"),
+            make_transaction(1, 500, "not a date"),
+        ];
+
+        let filtered = filter_by_date(
+            transactions,
+            NaiveDate::from_ymd_opt(2022, 5, 1),
+            NaiveDate::from_ymd_opt(2022, 5, 31),
+        );
+        let amounts: Vec<i64> = filtered
+            .iter()
+            .map(|context| context.transaction.amount)
+            .collect();
+
+        assert_eq!(amounts, vec![200, 300]);
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_a_date_range_that_ends_before_it_starts() {
+        let command = VendorTransactionsCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            ReviewVendorsApiFake::default(),
+        );
+
+        let result = command
+            .fetch_history(&make_args(Some("2022-06-01"), Some("2022-05-01")))
+            .await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let command = VendorTransactionsCommand::new(
+            FetchVendorsApiFake {
+                should_error: false,
+            },
+            ReviewVendorsApiFake {
+                should_error: true,
+                ..Default::default()
+            },
+        );
+
+        let result = command.fetch_history(&make_args(None, None)).await;
+
+        assert_eq!(result.unwrap_err().exit_code(), 6);
+    }
+}
@@ -0,0 +1,103 @@
+use std::slice;
+
+use cli_table::{print_stdout, Cell, CellStruct, Style, Table};
+use database::models::money::Money;
+
+use crate::error::Result;
+use crate::output::OutputOptions;
+use crate::transaction::format_cents;
+
+use super::vendor_transactions_command::VendorTransactionHistory;
+
+/// Prints the statistics followed by every matched transaction. Any format other than table
+/// prints the history as a single record.
+pub struct VendorTransactionsUi<'a> {
+    output: &'a OutputOptions,
+}
+
+impl<'a> VendorTransactionsUi<'a> {
+    pub fn new(output: &'a OutputOptions) -> Self {
+        Self { output }
+    }
+
+    pub fn render(&self, history: &VendorTransactionHistory) -> Result<()> {
+        if !self.output.is_table() {
+            return self.output.render(slice::from_ref(history));
+        }
+
+        let vendor = &history.vendor;
+        println!("{} (vendor {})", vendor.friendly_name, vendor.id);
+        if vendor.vendor_identity_cannot_be_determined {
+            println!("This vendor is currently flagged as identity cannot be determined");
+        }
+        self.render_statistics(history)?;
+
+        println!();
+        if history.transactions.is_empty() {
+            println!("No transactions have been matched to this vendor in this date range");
+            return Ok(());
+        }
+
+        self.render_transactions(history)
+    }
+
+    fn render_statistics(&self, history: &VendorTransactionHistory) -> Result<()> {
+        let statistics = &history.statistics;
+        let data: Vec<Vec<CellStruct>> = vec![
+            vec!["Transactions".cell(), statistics.count.cell()],
+            vec!["Total".cell(), statistics.total.to_string().cell()],
+            vec!["Mean".cell(), optional_money(statistics.mean).cell()],
+            vec!["Median".cell(), optional_money(statistics.median).cell()],
+            vec![
+                "First Seen".cell(),
+                statistics.first_seen.clone().unwrap_or_default().cell(),
+            ],
+            vec![
+                "Last Seen".cell(),
+                statistics.last_seen.clone().unwrap_or_default().cell(),
+            ],
+            vec!["Distinct Payers".cell(), statistics.distinct_payers.cell()],
+        ];
+
+        Ok(print_stdout(data.table().bold(true))?)
+    }
+
+    fn render_transactions(&self, history: &VendorTransactionHistory) -> Result<()> {
+        let data: Vec<Vec<CellStruct>> = history
+            .transactions
+            .iter()
+            .map(|context| {
+                let transaction = &context.transaction;
+
+                vec![
+                    transaction.id.cell(),
+                    transaction.date.clone().cell(),
+                    transaction.display_name().cell(),
+                    format_cents(transaction.amount).cell(),
+                    transaction.ppd_id.clone().unwrap_or_default().cell(),
+                    context.user.email.clone().cell(),
+                    context.account.account_name.clone().cell(),
+                ]
+            })
+            .collect();
+
+        let table = data
+            .table()
+            .title(vec![
+                "ID".cell().bold(true),
+                "Date".cell().bold(true),
+                "Name".cell().bold(true),
+                "Amount".cell().bold(true),
+                "PPD ID".cell().bold(true),
+                "Payer".cell().bold(true),
+                "Account".cell().bold(true),
+            ])
+            .bold(true);
+
+        Ok(print_stdout(table)?)
+    }
+}
+
+fn optional_money(amount: Option<Money>) -> String {
+    amount.map(|amount| amount.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,21 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Set once from the global `--verbose` flag before any command runs
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Diagnostic output that's only printed (to stderr) when `--verbose` is passed
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::verbose::is_verbose() {
+            eprintln!($($arg)*);
+        }
+    };
+}
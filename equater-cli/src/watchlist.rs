@@ -0,0 +1,5 @@
+pub mod watchlist_api;
+#[cfg(test)]
+pub mod watchlist_api_fake;
+pub mod watchlist_command;
+mod watchlist_ui;
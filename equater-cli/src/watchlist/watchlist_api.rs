@@ -0,0 +1,27 @@
+use crate::equater_client::{EquaterClient, SendJson};
+use crate::error::Result;
+use crate::shared_expense::AgreementWatchlist;
+use async_trait::async_trait;
+
+use super::watchlist_command::FetchesWatchlist;
+
+pub struct WatchlistApi {
+    client: EquaterClient,
+}
+
+impl WatchlistApi {
+    /// `client` must be authenticated with the local user's auth token
+    pub fn new(client: EquaterClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl FetchesWatchlist for WatchlistApi {
+    async fn fetch_agreement_watchlist(&self) -> Result<AgreementWatchlist> {
+        self.client
+            .get("/api/expense/ops/agreement-watchlist")
+            .send_json::<AgreementWatchlist>()
+            .await
+    }
+}
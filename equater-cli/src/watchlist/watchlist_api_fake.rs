@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use crate::equater_client::read_json;
+use crate::error::Result;
+use crate::shared_expense::AgreementWatchlist;
+use crate::test_common::{make_agreement_story, make_error_response, UNAUTHORIZED_BODY};
+use http::response;
+use serde_json::json;
+
+use super::watchlist_command::FetchesWatchlist;
+
+pub struct WatchlistApiFake {
+    pub should_error: bool,
+}
+
+#[async_trait]
+impl FetchesWatchlist for WatchlistApiFake {
+    async fn fetch_agreement_watchlist(&self) -> Result<AgreementWatchlist> {
+        if self.should_error {
+            return make_error_response(403, UNAUTHORIZED_BODY).await;
+        }
+
+        // Round trip the fixture through reqwest so the models are deserialized exactly as they
+        // would be from the API
+        let body = json!({
+            "newAgreements": [
+                make_agreement_story(10, "Netflix", 100, 1000, "2022-05-10T12:00:00.000Z"),
+                make_agreement_story(10, "Netflix", 101, 1001, "2022-05-14T09:30:00.000Z"),
+            ],
+            "newAgreementsWithNewVendors": [
+                make_agreement_story(20, "Comcast", 200, 2000, "2022-05-01T00:00:00.000Z"),
+            ],
+        });
+        let response: reqwest::Response = response::Builder::new()
+            .status(200)
+            .body(body.to_string())
+            .unwrap()
+            .into();
+
+        read_json(response).await
+    }
+}
@@ -0,0 +1,286 @@
+use std::io::{self, IsTerminal};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::commands::WatchlistArgs;
+use crate::error::Result;
+use crate::output::{format_duration, Columns, OutputOptions};
+use crate::shared_expense::{AgreementWatchlist, UserAgreementStory};
+use crate::vendor::Vendor;
+
+use super::watchlist_ui::WatchlistUi;
+
+#[async_trait]
+pub trait FetchesWatchlist {
+    async fn fetch_agreement_watchlist(&self) -> Result<AgreementWatchlist>;
+}
+
+/// A single agreement on the watchlist, flattened out of its story so it can be printed
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchlistEntry {
+    /// True when the vendor has never been matched to any shared bill transaction
+    pub new_vendor: bool,
+    pub vendor_id: Option<u32>,
+    pub vendor_name: String,
+    pub shared_expense_id: u32,
+    pub expense_nick_name: String,
+    pub agreement_id: u32,
+    pub user_id: u32,
+    pub user_email: Option<String>,
+    pub initiating_user_email: String,
+    /// ISO8601 String
+    pub waiting_since: String,
+    pub waiting: String,
+    #[serde(skip)]
+    waiting_for: Duration,
+}
+
+impl Columns for WatchlistEntry {
+    fn default_columns() -> &'static [&'static str] {
+        &[
+            "vendor_name",
+            "expense_nick_name",
+            "agreement_id",
+            "user_email",
+            "initiating_user_email",
+            "waiting",
+        ]
+    }
+
+    fn available_columns() -> &'static [&'static str] {
+        &[
+            "new_vendor",
+            "vendor_id",
+            "vendor_name",
+            "shared_expense_id",
+            "expense_nick_name",
+            "agreement_id",
+            "user_id",
+            "user_email",
+            "initiating_user_email",
+            "waiting_since",
+            "waiting",
+        ]
+    }
+}
+
+/// Every watchlist entry for one vendor. Recurring payments aren't tied to a vendor, so they're
+/// grouped together under `vendor: None`.
+pub struct WatchlistGroup<'a> {
+    pub vendor: Option<&'a Vendor>,
+    pub new_vendor: bool,
+    pub entries: Vec<WatchlistEntry>,
+    pub stories: Vec<&'a UserAgreementStory>,
+}
+
+impl<'a> WatchlistGroup<'a> {
+    pub fn title(&self) -> String {
+        match (self.vendor, self.new_vendor) {
+            (Some(vendor), true) => {
+                format!("{} (ID {}) -- new vendor", vendor.friendly_name, vendor.id)
+            }
+            (Some(vendor), false) => format!("{} (ID {})", vendor.friendly_name, vendor.id),
+            (None, _) => String::from("No vendor"),
+        }
+    }
+}
+
+pub struct WatchlistCommand<T: FetchesWatchlist> {
+    remote_repository: T,
+}
+
+impl<T: FetchesWatchlist> WatchlistCommand<T> {
+    pub fn new(remote_repository: T) -> Self {
+        Self { remote_repository }
+    }
+
+    pub async fn show_watchlist(
+        &self,
+        args: &WatchlistArgs,
+        output: &OutputOptions,
+    ) -> Result<String> {
+        let watchlist = self.remote_repository.fetch_agreement_watchlist().await?;
+        let groups = group_by_vendor(&watchlist, args.new_vendors, Utc::now());
+        let ui = WatchlistUi::new(&groups);
+
+        if !output.is_table() {
+            ui.render_flat(output)?;
+            return Ok(String::from(""));
+        }
+
+        if groups.is_empty() {
+            return Ok(String::from("No agreements are waiting on a transaction"));
+        }
+
+        ui.render_groups(output)?;
+        if !args.no_jump && io::stdin().is_terminal() && io::stdout().is_terminal() {
+            ui.jump(output)?;
+        }
+
+        let total: usize = groups.iter().map(|group| group.entries.len()).sum();
+
+        Ok(format!(
+            "{} agreement(s) across {} vendor(s) are waiting on a transaction",
+            total,
+            groups.len()
+        ))
+    }
+}
+
+/// Groups the watchlist by vendor with new vendors first. Groups and the entries inside them are
+/// ordered by how long they've been waiting, longest first.
+pub fn group_by_vendor(
+    watchlist: &AgreementWatchlist,
+    new_vendors_only: bool,
+    now: DateTime<Utc>,
+) -> Vec<WatchlistGroup<'_>> {
+    let known_vendors = watchlist
+        .new_agreements
+        .iter()
+        .filter(|_| !new_vendors_only)
+        .map(|story| (story, false));
+    let new_vendors = watchlist
+        .new_agreements_with_new_vendors
+        .iter()
+        .map(|story| (story, true));
+
+    let mut groups: Vec<WatchlistGroup> = vec![];
+    for (story, new_vendor) in new_vendors.chain(known_vendors) {
+        let vendor = story.story.vendor.as_ref();
+        let vendor_id = vendor.map(|vendor| vendor.id);
+        let entry = make_entry(story, new_vendor, now);
+
+        match groups
+            .iter_mut()
+            .find(|group| group.vendor.map(|vendor| vendor.id) == vendor_id)
+        {
+            Some(group) => {
+                group.entries.push(entry);
+                group.stories.push(story);
+            }
+            None => groups.push(WatchlistGroup {
+                vendor,
+                new_vendor,
+                entries: vec![entry],
+                stories: vec![story],
+            }),
+        }
+    }
+
+    for group in groups.iter_mut() {
+        group
+            .entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.waiting_for));
+    }
+    groups.sort_by(|a, b| {
+        b.new_vendor
+            .cmp(&a.new_vendor)
+            .then_with(|| b.entries[0].waiting_for.cmp(&a.entries[0].waiting_for))
+    });
+
+    groups
+}
+
+fn make_entry(story: &UserAgreementStory, new_vendor: bool, now: DateTime<Utc>) -> WatchlistEntry {
+    let agreement = &story.user_agreement;
+    let shared_expense = &story.story.shared_expense;
+    // An agreement can't be matched until it's active, so that's when the wait starts
+    let waiting_since = agreement
+        .date_time_became_active
+        .clone()
+        .unwrap_or_else(|| agreement.date_time_created.clone());
+    let waiting_for = DateTime::parse_from_rfc3339(&waiting_since)
+        .map(|since| now.signed_duration_since(since))
+        .unwrap_or_else(|_| Duration::zero());
+
+    WatchlistEntry {
+        new_vendor,
+        vendor_id: story.story.vendor.as_ref().map(|vendor| vendor.id),
+        vendor_name: story
+            .story
+            .vendor
+            .as_ref()
+            .map(|vendor| vendor.friendly_name.clone())
+            .unwrap_or_default(),
+        shared_expense_id: shared_expense.id,
+        expense_nick_name: shared_expense.expense_nick_name.clone(),
+        agreement_id: agreement.id,
+        user_id: agreement.user_id,
+        user_email: story.agreement_user().map(|user| user.email.clone()),
+        initiating_user_email: story.story.initiating_user.email.clone(),
+        waiting_since,
+        waiting: format_duration(waiting_for),
+        waiting_for,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{group_by_vendor, FetchesWatchlist, WatchlistCommand};
+    use crate::commands::WatchlistArgs;
+    use crate::output::OutputOptions;
+    use crate::watchlist::watchlist_api_fake::WatchlistApiFake;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn it_should_group_entries_by_vendor_with_new_vendors_first() {
+        let watchlist = WatchlistApiFake {
+            should_error: false,
+        }
+        .fetch_agreement_watchlist()
+        .await
+        .unwrap();
+        let now = Utc.ymd(2022, 5, 15).and_hms(12, 0, 0);
+        let groups = group_by_vendor(&watchlist, false, now);
+
+        assert_eq!(groups.len(), 2);
+        assert!(groups[0].new_vendor);
+        assert_eq!(groups[0].title(), "Comcast (ID 20) -- new vendor");
+        assert_eq!(groups[0].entries[0].waiting, "14d 12h");
+
+        let agreement_ids: Vec<u32> = groups[1]
+            .entries
+            .iter()
+            .map(|entry| entry.agreement_id)
+            .collect();
+        assert_eq!(agreement_ids, vec![1000, 1001]);
+        assert_eq!(
+            groups[1].entries[0].user_email.as_deref(),
+            Some("member@example.com")
+        );
+        assert_eq!(groups[1].entries[1].waiting, "1d 2h");
+    }
+
+    #[tokio::test]
+    async fn it_should_only_show_new_vendors_when_asked() {
+        let watchlist = WatchlistApiFake {
+            should_error: false,
+        }
+        .fetch_agreement_watchlist()
+        .await
+        .unwrap();
+        let groups = group_by_vendor(&watchlist, true, Utc::now());
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].entries.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_should_surface_remote_errors() {
+        let command = WatchlistCommand::new(WatchlistApiFake { should_error: true });
+        let args = WatchlistArgs {
+            new_vendors: false,
+            no_jump: true,
+        };
+        let result = command
+            .show_watchlist(&args, &OutputOptions::default())
+            .await;
+
+        let error = result.unwrap_err();
+        assert_eq!(error.exit_code(), 6);
+        assert_eq!(error.status().map(|status| status.as_u16()), Some(403));
+    }
+}
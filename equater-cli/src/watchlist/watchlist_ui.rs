@@ -0,0 +1,124 @@
+use std::slice;
+
+use dialoguer::{theme::ColorfulTheme, Select};
+
+use crate::error::Result;
+use crate::output::OutputOptions;
+use crate::user::User;
+use crate::vendor::Vendor;
+
+use super::watchlist_command::{WatchlistEntry, WatchlistGroup};
+
+enum JumpTarget<'a> {
+    Vendor(&'a WatchlistGroup<'a>),
+    User(&'a User),
+}
+
+pub struct WatchlistUi<'a> {
+    groups: &'a [WatchlistGroup<'a>],
+}
+
+impl<'a> WatchlistUi<'a> {
+    pub fn new(groups: &'a [WatchlistGroup<'a>]) -> Self {
+        Self { groups }
+    }
+
+    /// Prints a heading and a table for each vendor
+    pub fn render_groups(&self, output: &OutputOptions) -> Result<()> {
+        for group in self.groups {
+            println!();
+            println!("{}", group.title());
+            output.render(&group.entries)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints every entry as a single listing for machine readable formats
+    pub fn render_flat(&self, output: &OutputOptions) -> Result<()> {
+        let entries: Vec<&WatchlistEntry> = self
+            .groups
+            .iter()
+            .flat_map(|group| group.entries.iter())
+            .collect();
+
+        output.render(&entries)
+    }
+
+    /// Lets the operator look closer at any vendor or user on the watchlist until they're done
+    pub fn jump(&self, output: &OutputOptions) -> Result<()> {
+        let targets = self.jump_targets();
+        let mut items: Vec<String> = targets
+            .iter()
+            .map(|target| match target {
+                JumpTarget::Vendor(group) => format!("Vendor: {}", group.title()),
+                JumpTarget::User(user) => format!(
+                    "User: {} {} <{}> (ID {})",
+                    user.first_name, user.last_name, user.email, user.id
+                ),
+            })
+            .collect();
+        items.push(String::from("Done"));
+
+        loop {
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Jump to a vendor or user")
+                .items(&items)
+                .default(items.len() - 1)
+                .interact()?;
+
+            match targets.get(selection) {
+                Some(JumpTarget::Vendor(group)) => self.render_vendor(group, output)?,
+                Some(JumpTarget::User(user)) => self.render_user(user, output)?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn jump_targets(&self) -> Vec<JumpTarget<'a>> {
+        let mut users: Vec<&User> = vec![];
+        for story in self.groups.iter().flat_map(|group| group.stories.iter()) {
+            let involved = std::iter::once(&story.story.initiating_user)
+                .chain(story.story.active_users.iter());
+            for user in involved {
+                if !users.iter().any(|existing| existing.id == user.id) {
+                    users.push(user);
+                }
+            }
+        }
+
+        self.groups
+            .iter()
+            .filter(|group| group.vendor.is_some())
+            .map(JumpTarget::Vendor)
+            .chain(users.into_iter().map(JumpTarget::User))
+            .collect()
+    }
+
+    fn render_vendor(&self, group: &WatchlistGroup, output: &OutputOptions) -> Result<()> {
+        if let Some(vendor) = group.vendor {
+            output.render(slice::from_ref::<Vendor>(vendor))?;
+        }
+        let entries: Vec<&WatchlistEntry> = group.entries.iter().collect();
+
+        self.render_entries(&entries, output)
+    }
+
+    fn render_user(&self, user: &User, output: &OutputOptions) -> Result<()> {
+        output.render(slice::from_ref(user))?;
+        let entries: Vec<&WatchlistEntry> = self
+            .groups
+            .iter()
+            .flat_map(|group| group.entries.iter())
+            .filter(|entry| entry.user_id == user.id || entry.initiating_user_email == user.email)
+            .collect();
+
+        self.render_entries(&entries, output)
+    }
+
+    fn render_entries(&self, entries: &[&WatchlistEntry], output: &OutputOptions) -> Result<()> {
+        println!("Waiting on a transaction:");
+
+        output.render(entries)
+    }
+}
@@ -0,0 +1,52 @@
+use serde_json::{json, Value};
+
+/// A user as the API sends it, e.g. from GET /api/user
+pub fn make_user(id: u32, email: &str, first_name: &str, last_name: &str) -> Value {
+    json!({
+        "id": id,
+        "uuid": format!("00000000-0000-4000-8000-{:012}", id),
+        "email": email,
+        "firstName": first_name,
+        "lastName": last_name,
+        "emailIsConfirmed": true,
+        "canReceiveFunds": true,
+        "profilePhotoUrl": null,
+        "profilePhotoUploadCompleted": false,
+        "profilePhotoSha256Hash": null,
+        "coverPhotoUploadCompleted": false,
+        "coverPhotoSha256Hash": null,
+        "dateTimeCreated": "2022-03-01T15:21:09.000Z",
+        "addressOne": null,
+        "addressTwo": null,
+        "city": "Denver",
+        "state": "CO",
+        "postalCode": "80205",
+        "preSignedPhotoDownloadUrl": null,
+        "preSignedCoverPhotoDownloadUrl": null,
+        "acceptedTermsOfService": true,
+        "acceptedPrivacyPolicy": true,
+        "linkTokens": [],
+        "dwollaReverificationNeeded": false,
+    })
+}
+
+/// A vendor as the API sends it, e.g. from GET /api/vendor/:id. `totalNumberOfExpenseSharingAgreements`
+/// is what orders GET /api/vendor/popular.
+pub fn make_vendor(id: u32, friendly_name: &str, has_been_reviewed_internally: bool) -> Value {
+    json!({
+        "id": id,
+        "uuid": format!("00000000-0000-4000-9000-{:012}", id),
+        "ppdId": null,
+        "dateTimeAdded": "2022-03-01T15:21:09.000Z",
+        "dateTimeModified": null,
+        "totalNumberOfExpenseSharingAgreements": 0,
+        "hasBeenReviewedInternally": has_been_reviewed_internally,
+        "vendorIdentityCannotBeDetermined": false,
+        "friendlyName": friendly_name,
+        "logoS3Bucket": null,
+        "logoS3Key": null,
+        "logoUrl": null,
+        "logoUploadCompleted": false,
+        "logoSha256Hash": null,
+    })
+}
@@ -0,0 +1,6 @@
+//! Helpers for tests that run the real equater binary against a local stand-in for the API
+
+pub mod fixtures;
+pub mod mock_server;
+mod routes;
+pub mod sandbox;
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::Server;
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::routes;
+
+/// A local stand-in for the Equater API that implements sign in, the user routes and the vendor
+/// routes against state that a test sets up. It runs on its own thread, so it works the same from
+/// a plain `#[test]` or a `#[tokio::test]`, and it stops when dropped.
+///
+/// Routes other than `/api/auth/admin-login` and `/api/environment` need a bearer token from a
+/// sign in, just like the real API.
+pub struct MockServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// A request as the server received it
+#[derive(Debug, Clone)]
+pub struct ReceivedRequest {
+    pub method: String,
+    pub path: String,
+    /// Decoded query parameters in the order they were sent
+    pub query: Vec<(String, String)>,
+    /// Names are lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// The body exactly as sent, for uploads that aren't text
+    pub body_bytes: Vec<u8>,
+}
+
+impl ReceivedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A canned response to the next request with this method and path, ahead of the route
+#[derive(Debug, Clone)]
+pub struct CannedResponse {
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+#[derive(Default)]
+pub(crate) struct MockState {
+    pub api_base: String,
+    /// Reported by GET /api/environment
+    pub server_environment: String,
+    pub users: Vec<Value>,
+    pub vendors: Vec<Value>,
+    /// Email to password, for the users that can sign in
+    pub credentials: HashMap<String, String>,
+    /// Auth token to user id
+    pub sessions: HashMap<String, u64>,
+    pub sign_ins: u32,
+    pub canned_responses: Vec<CannedResponse>,
+    pub requests: Vec<ReceivedRequest>,
+}
+
+impl MockServer {
+    /// Listens on a free port on 127.0.0.1
+    pub fn start() -> Self {
+        let state = Arc::new(Mutex::new(MockState {
+            server_environment: String::from("development"),
+            ..MockState::default()
+        }));
+        let (address_sender, address_receiver) = mpsc::channel();
+        let (shutdown, shutdown_receiver) = oneshot::channel::<()>();
+        let server_state = state.clone();
+
+        let thread = thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Could not start the mock server's runtime");
+
+            runtime.block_on(async move {
+                let make_service = make_service_fn(move |_| {
+                    let state = server_state.clone();
+                    async move {
+                        Ok::<_, Infallible>(service_fn(move |request| {
+                            routes::handle(state.clone(), request)
+                        }))
+                    }
+                });
+                let server =
+                    Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+                address_sender.send(server.local_addr()).ok();
+
+                server
+                    .with_graceful_shutdown(async {
+                        shutdown_receiver.await.ok();
+                    })
+                    .await
+                    .expect("The mock server stopped unexpectedly");
+            });
+        });
+
+        let address = address_receiver
+            .recv()
+            .expect("The mock server didn't start");
+        state.lock().unwrap().api_base = format!("http://{}", address);
+
+        Self {
+            address,
+            state,
+            shutdown: Some(shutdown),
+            thread: Some(thread),
+        }
+    }
+
+    /// e.g. http://127.0.0.1:53712, for the api_base setting
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// A user that can sign in with `password`. `user` is usually made with
+    /// `fixtures::make_user`.
+    pub fn add_admin(&self, user: Value, password: &str) {
+        let email = user["email"].as_str().unwrap_or_default().to_string();
+        let mut state = self.lock();
+        state.credentials.insert(email, String::from(password));
+        state.users.push(user);
+    }
+
+    pub fn add_user(&self, user: Value) {
+        self.lock().users.push(user);
+    }
+
+    pub fn add_vendor(&self, vendor: Value) {
+        self.lock().vendors.push(vendor);
+    }
+
+    /// e.g. production, to test what the CLI refuses to do there
+    pub fn set_server_environment(&self, server_environment: &str) {
+        self.lock().server_environment = String::from(server_environment);
+    }
+
+    /// Responds to the next `method` request for `path` (without the query string) with
+    /// `status` and `body` instead of running the route. Failures for the same request are used
+    /// in the order they were added.
+    pub fn fail_next(&self, method: &str, path: &str, status: u16, body: Value) {
+        self.push_canned_response(method, path, status, "application/json", body.to_string());
+    }
+
+    /// Like `fail_next`, but `body` is sent as is. Paths outside /api have no routes, so this is
+    /// how a test stands in for another service, e.g. S3 behind a pre-signed URL.
+    pub fn respond_next(&self, method: &str, path: &str, status: u16, body: &str) {
+        self.push_canned_response(method, path, status, "application/xml", String::from(body));
+    }
+
+    /// Every auth token handed out so far stops working, as if the sessions timed out
+    pub fn expire_sessions(&self) {
+        self.lock().sessions.clear();
+    }
+
+    /// How many times someone signed in successfully
+    pub fn sign_ins(&self) -> u32 {
+        self.lock().sign_ins
+    }
+
+    /// Oldest first
+    pub fn requests(&self) -> Vec<ReceivedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// The last request for `path`, ignoring the query string
+    pub fn last_request(&self, method: &str, path: &str) -> Option<ReceivedRequest> {
+        self.lock()
+            .requests
+            .iter()
+            .rev()
+            .find(|request| request.method.eq_ignore_ascii_case(method) && request.path == path)
+            .cloned()
+    }
+
+    fn push_canned_response(
+        &self,
+        method: &str,
+        path: &str,
+        status: u16,
+        content_type: &'static str,
+        body: String,
+    ) {
+        self.lock().canned_responses.push(CannedResponse {
+            method: method.to_uppercase(),
+            path: String::from(path),
+            status,
+            content_type,
+            body,
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+        if let Some(thread) = self.thread.take() {
+            thread.join().ok();
+        }
+    }
+}
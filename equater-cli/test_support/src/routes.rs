@@ -0,0 +1,310 @@
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde_json::{json, Map, Value};
+
+use crate::mock_server::{MockState, ReceivedRequest};
+
+/// GET /api/vendor pages hold this many vendors, like the API's
+const VENDOR_PAGE_SIZE: usize = 100;
+
+type Reply = (u16, Value);
+
+pub(crate) async fn handle(
+    state: Arc<Mutex<MockState>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let (parts, body) = request.into_parts();
+    let body = hyper::body::to_bytes(body).await.unwrap_or_default();
+    let received = ReceivedRequest {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: url::form_urlencoded::parse(parts.uri.query().unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect(),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            })
+            .collect(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+        body_bytes: body.to_vec(),
+    };
+
+    let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+    state.requests.push(received.clone());
+    let canned = state
+        .canned_responses
+        .iter()
+        .position(|canned| canned.method == received.method && canned.path == received.path);
+    let (status, content_type, body) = match canned {
+        Some(index) => {
+            let canned = state.canned_responses.remove(index);
+            (canned.status, canned.content_type, canned.body)
+        }
+        None => {
+            let (status, body) = route(&mut state, &received);
+            (status, "application/json", body.to_string())
+        }
+    };
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+    Ok(response)
+}
+
+fn route(state: &mut MockState, request: &ReceivedRequest) -> Reply {
+    let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "environment"]) => (
+            200,
+            json!({
+                "plaidEnvironment": "sandbox",
+                "serverEnvironment": state.server_environment,
+            }),
+        ),
+        ("POST", ["api", "auth", "admin-login"]) => sign_in(state, request),
+        (_, ["api", ..]) => {
+            let user_id = match authenticate(state, request) {
+                Some(user_id) => user_id,
+                None => return error(401, "Unauthorized", "Unauthorized"),
+            };
+
+            match (request.method.as_str(), &segments[1..]) {
+                ("GET", ["user"]) => match find_by_id(&state.users, user_id) {
+                    Some(user) => (200, user.clone()),
+                    None => error(401, "User session is not valid", "Unauthorized"),
+                },
+                ("GET", ["user", "search"]) => search_users(state, request, user_id),
+                ("GET", ["vendor"]) => vendor_page(state, request),
+                ("PUT", ["vendor"]) => create_vendor(state, request),
+                ("GET", ["vendor", "search"]) => search_vendors(state, request),
+                ("GET", ["vendor", "popular"]) => popular_vendors(state),
+                ("GET", ["vendor", "requires-internal-review"]) => (
+                    200,
+                    json!({ "vendors": filter_vendors(state, |vendor| !is_reviewed(vendor)) }),
+                ),
+                ("GET", ["vendor", id]) => match find_vendor(state, id) {
+                    Some(vendor) => (200, json!({ "vendor": vendor })),
+                    None => not_found(request),
+                },
+                ("PATCH", ["vendor", id]) => patch_vendor(state, request, id),
+                _ => not_found(request),
+            }
+        }
+        _ => not_found(request),
+    }
+}
+
+fn sign_in(state: &mut MockState, request: &ReceivedRequest) -> Reply {
+    let body: Value = serde_json::from_str(&request.body).unwrap_or_default();
+    let email = body["email"].as_str().unwrap_or_default();
+    let password = body["password"].as_str().unwrap_or_default();
+    let user = state
+        .users
+        .iter()
+        .find(|user| user["email"] == email)
+        .filter(|_| state.credentials.get(email).map(String::as_str) == Some(password))
+        .cloned();
+
+    match user {
+        Some(user) => {
+            state.sign_ins += 1;
+            let auth_token = format!("mock-token-{}", state.sign_ins);
+            state
+                .sessions
+                .insert(auth_token.clone(), user["id"].as_u64().unwrap_or_default());
+
+            (
+                201,
+                json!({ "authToken": auth_token, "user": user, "userAccounts": [] }),
+            )
+        }
+        None => error(401, "Invalid username or password", "Unauthorized"),
+    }
+}
+
+fn authenticate(state: &MockState, request: &ReceivedRequest) -> Option<u64> {
+    let auth_token = request.header("authorization")?.strip_prefix("Bearer ")?;
+
+    state.sessions.get(auth_token).copied()
+}
+
+/// Matches the first name, last name or email. Nobody is a friend of the admin, so everyone is
+/// under `users`.
+fn search_users(state: &MockState, request: &ReceivedRequest, user_id: u64) -> Reply {
+    let search_term = request
+        .query_param("searchTerm")
+        .unwrap_or_default()
+        .to_lowercase();
+    let include_authenticated_user =
+        request.query_param("includeAuthenticatedUser") == Some("true");
+    let users: Vec<&Value> = state
+        .users
+        .iter()
+        .filter(|user| include_authenticated_user || user["id"] != user_id)
+        .filter(|user| {
+            ["firstName", "lastName", "email"].iter().any(|field| {
+                user[field]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    .contains(&search_term)
+            })
+        })
+        .collect();
+
+    (200, json!({ "friends": [], "users": users }))
+}
+
+/// `requiringInternalReview=false` only finds vendors that have been reviewed
+fn search_vendors(state: &MockState, request: &ReceivedRequest) -> Reply {
+    let search_term = request
+        .query_param("searchTerm")
+        .unwrap_or_default()
+        .to_lowercase();
+    let requiring_review = request.query_param("requiringInternalReview") == Some("true");
+    let vendors = filter_vendors(state, |vendor| {
+        is_reviewed(vendor) != requiring_review
+            && vendor["friendlyName"]
+                .as_str()
+                .unwrap_or_default()
+                .to_lowercase()
+                .contains(&search_term)
+    });
+
+    (200, json!({ "vendors": vendors }))
+}
+
+fn popular_vendors(state: &MockState) -> Reply {
+    let mut vendors = filter_vendors(state, is_reviewed);
+    vendors.sort_by_key(|vendor| {
+        std::cmp::Reverse(vendor["totalNumberOfExpenseSharingAgreements"].as_u64())
+    });
+
+    (200, json!({ "vendors": vendors }))
+}
+
+fn vendor_page(state: &MockState, request: &ReceivedRequest) -> Reply {
+    let page: usize = request
+        .query_param("page")
+        .and_then(|page| page.parse().ok())
+        .unwrap_or(0);
+    let vendors: Vec<&Value> = state
+        .vendors
+        .iter()
+        .skip(page * VENDOR_PAGE_SIZE)
+        .take(VENDOR_PAGE_SIZE)
+        .collect();
+    let page_url = |page: usize| format!("{}/api/vendor?page={}", state.api_base, page);
+    let next_page =
+        ((page + 1) * VENDOR_PAGE_SIZE < state.vendors.len()).then(|| page_url(page + 1));
+    let previous_page = (page > 0).then(|| page_url(page - 1));
+
+    (
+        200,
+        json!({ "vendors": vendors, "nextPage": next_page, "previousPage": previous_page }),
+    )
+}
+
+/// Like the API's CreateVendorDto, only `friendlyName` and a client-picked `uuid` are used. The
+/// new vendor takes the next id, is marked as reviewed and is sent back bare rather than wrapped in
+/// `{ vendor }`. Names that are already taken are a conflict.
+fn create_vendor(state: &mut MockState, request: &ReceivedRequest) -> Reply {
+    let body: Value = serde_json::from_str(&request.body).unwrap_or_default();
+    let (friendly_name, uuid) = match (body["friendlyName"].as_str(), body["uuid"].as_str()) {
+        (Some(friendly_name), Some(uuid)) => (friendly_name, uuid),
+        (None, _) => return error(400, "friendlyName must be a string", "Bad Request"),
+        (_, None) => return error(400, "uuid must be a string", "Bad Request"),
+    };
+    if state
+        .vendors
+        .iter()
+        .any(|vendor| vendor["friendlyName"] == friendly_name)
+    {
+        return error(409, "Vendor already exists at PUT /api/vendor", "Conflict");
+    }
+
+    let id = state
+        .vendors
+        .iter()
+        .filter_map(|vendor| vendor["id"].as_u64())
+        .max()
+        .unwrap_or_default()
+        + 1;
+    let mut vendor = crate::fixtures::make_vendor(id as u32, friendly_name, true);
+    vendor["uuid"] = Value::from(uuid);
+    state.vendors.push(vendor.clone());
+
+    (200, vendor)
+}
+
+/// Fields in the body replace the vendor's, the same as the API's PatchVendorDto, and the vendor
+/// is marked as reviewed
+fn patch_vendor(state: &mut MockState, request: &ReceivedRequest, id: &str) -> Reply {
+    let changes: Map<String, Value> = match serde_json::from_str(&request.body) {
+        Ok(changes) => changes,
+        Err(_) => return error(400, "Expected a JSON object", "Bad Request"),
+    };
+    let vendor = id
+        .parse::<u64>()
+        .ok()
+        .and_then(|id| state.vendors.iter_mut().find(|vendor| vendor["id"] == id));
+
+    match vendor.and_then(Value::as_object_mut) {
+        Some(vendor) => {
+            vendor.extend(changes);
+            vendor.insert(String::from("hasBeenReviewedInternally"), Value::Bool(true));
+            (200, json!({ "vendor": vendor }))
+        }
+        None => not_found(request),
+    }
+}
+
+fn filter_vendors(state: &MockState, predicate: impl Fn(&Value) -> bool) -> Vec<Value> {
+    state
+        .vendors
+        .iter()
+        .filter(|vendor| predicate(vendor))
+        .cloned()
+        .collect()
+}
+
+fn find_vendor<'a>(state: &'a MockState, id: &str) -> Option<&'a Value> {
+    find_by_id(&state.vendors, id.parse().ok()?)
+}
+
+fn find_by_id(records: &[Value], id: u64) -> Option<&Value> {
+    records.iter().find(|record| record["id"] == id)
+}
+
+fn is_reviewed(vendor: &Value) -> bool {
+    vendor["hasBeenReviewedInternally"].as_bool() == Some(true)
+}
+
+/// Nest's error body
+fn error(status: u16, message: &str, error: &str) -> Reply {
+    (
+        status,
+        json!({ "statusCode": status, "message": message, "error": error }),
+    )
+}
+
+fn not_found(request: &ReceivedRequest) -> Reply {
+    error(
+        404,
+        &format!("Cannot {} {}", request.method, request.path),
+        "Not Found",
+    )
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static NEXT_SANDBOX: AtomicU32 = AtomicU32::new(0);
+
+/// A scratch home directory for running the equater binary, so that a test never reads or
+/// writes the developer's config, local database or token key. Removed when dropped.
+pub struct Sandbox {
+    directory: PathBuf,
+    api_base: String,
+}
+
+impl Sandbox {
+    /// Commands run in the sandbox use `api_base`, usually `MockServer::url`
+    pub fn new(api_base: &str) -> Self {
+        let directory = std::env::temp_dir().join(format!(
+            "equater-sandbox-{}-{}",
+            std::process::id(),
+            NEXT_SANDBOX.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&directory).expect("Could not create the sandbox directory");
+
+        Self {
+            directory,
+            api_base: String::from(api_base),
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// `binary` is the path cargo builds it to, i.e. `env!("CARGO_BIN_EXE_equater")`. Nothing is
+    /// inherited from the test's environment, stdin is empty so prompts can't wait on it, and
+    /// the process runs inside the sandbox so that no .env file is picked up.
+    pub fn command(&self, binary: &str) -> Command {
+        let mut command = Command::new(binary);
+        command
+            .current_dir(&self.directory)
+            .env_clear()
+            .env("HOME", &self.directory)
+            .env("XDG_CONFIG_HOME", self.directory.join("config"))
+            .env("XDG_DATA_HOME", self.directory.join("data"))
+            .env("EQUATER_API_BASE", &self.api_base)
+            .env("EQUATER_TOKEN_PASSPHRASE", "sandbox")
+            .stdin(Stdio::null());
+
+        command
+    }
+
+    /// Runs `equater login --email <email> --password-stdin` with `password` written to stdin
+    pub fn sign_in(&self, binary: &str, email: &str, password: &str) -> Output {
+        let mut child = self
+            .command(binary)
+            .args(["login", "--email", email, "--password-stdin"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Could not start equater login");
+        child
+            .stdin
+            .take()
+            .expect("stdin is piped")
+            .write_all(format!("{}\n", password).as_bytes())
+            .expect("Could not write the password to equater login");
+
+        child
+            .wait_with_output()
+            .expect("Could not wait for equater login")
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.directory).ok();
+    }
+}
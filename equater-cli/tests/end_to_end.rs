@@ -0,0 +1,246 @@
+use std::process::Output;
+
+use serde_json::json;
+use test_support::fixtures::{make_user, make_vendor};
+use test_support::mock_server::MockServer;
+use test_support::sandbox::Sandbox;
+
+const EQUATER: &str = env!("CARGO_BIN_EXE_equater");
+const ADMIN_EMAIL: &str = "admin@example.com";
+const ADMIN_PASSWORD: &str = "correct horse battery staple";
+
+fn start_server() -> MockServer {
+    let server = MockServer::start();
+    server.add_admin(make_user(1, ADMIN_EMAIL, "Avery", "Admin"), ADMIN_PASSWORD);
+    server.add_user(make_user(
+        3,
+        "robert.paulson@example.com",
+        "Robert",
+        "Paulson",
+    ));
+    server.add_user(make_user(9, "roberta.diaz@example.com", "Roberta", "Diaz"));
+    server.add_user(make_user(14, "marla.singer@example.com", "Marla", "Singer"));
+
+    server
+}
+
+fn sign_in(sandbox: &Sandbox) -> Output {
+    sandbox.sign_in(EQUATER, ADMIN_EMAIL, ADMIN_PASSWORD)
+}
+
+/// There's no terminal to prompt in, so tests sign in first
+fn run(sandbox: &Sandbox, args: &[&str]) -> Output {
+    sandbox.command(EQUATER).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn it_should_sign_in_and_fetch_users() {
+    let server = start_server();
+    let sandbox = Sandbox::new(&server.url());
+
+    let login = sign_in(&sandbox);
+    let fetch = run(
+        &sandbox,
+        &["fetch", "users", "--search", "ROBERT", "--output", "csv"],
+    );
+
+    assert!(login.status.success(), "{}", stderr(&login));
+    assert_eq!(
+        stdout(&login),
+        "admin@example.com is signed in to the default profile\n"
+    );
+    assert!(fetch.status.success(), "{}", stderr(&fetch));
+    assert_eq!(
+        stdout(&fetch),
+        "id,email,uuid,first_name,last_name
+3,robert.paulson@example.com,00000000-0000-4000-8000-000000000003,Robert,Paulson
+9,roberta.diaz@example.com,00000000-0000-4000-8000-000000000009,Roberta,Diaz
+"
+    );
+    let search = server.last_request("GET", "/api/user/search").unwrap();
+    assert_eq!(
+        search.query,
+        vec![
+            (String::from("searchTerm"), String::from("ROBERT")),
+            (
+                String::from("includeAuthenticatedUser"),
+                String::from("true")
+            ),
+        ]
+    );
+    assert_eq!(search.header("authorization"), Some("Bearer mock-token-1"));
+    assert!(search
+        .header("user-agent")
+        .unwrap()
+        .starts_with("equater-cli/"));
+    assert_eq!(server.sign_ins(), 1);
+}
+
+#[test]
+fn it_should_exit_when_the_password_is_wrong() {
+    let server = start_server();
+    let sandbox = Sandbox::new(&server.url());
+
+    let login = sandbox.sign_in(EQUATER, ADMIN_EMAIL, "hunter2");
+
+    assert_eq!(login.status.code(), Some(6));
+    assert_eq!(
+        stderr(&login),
+        "Invalid username or password (401 Unauthorized)\n"
+    );
+    let sign_in = server
+        .last_request("POST", "/api/auth/admin-login")
+        .unwrap();
+    assert_eq!(sign_in.header("content-type"), Some("application/json"));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&sign_in.body).unwrap(),
+        json!({ "email": ADMIN_EMAIL, "password": "hunter2" })
+    );
+}
+
+#[test]
+fn it_should_ask_scripts_to_sign_in_again_when_the_session_expires() {
+    let server = start_server();
+    server.add_vendor(make_vendor(12, "Netflix", true));
+    server.add_vendor(make_vendor(13, "Netflix DVD", false));
+    let sandbox = Sandbox::new(&server.url());
+    let search = ["fetch", "vendors", "--search", "netflix", "--output", "csv"];
+
+    sign_in(&sandbox);
+    server.expire_sessions();
+    let expired = run(&sandbox, &search);
+    sign_in(&sandbox);
+    let fetch = run(&sandbox, &search);
+
+    assert_eq!(expired.status.code(), Some(6));
+    assert_eq!(
+        stderr(&expired),
+        "You're not signed in -- sign in with equater login\n"
+    );
+    assert!(fetch.status.success(), "{}", stderr(&fetch));
+    assert_eq!(
+        stdout(&fetch),
+        "id,friendly_name,uuid,has_been_reviewed_internally,vendor_identity_cannot_be_determined
+12,Netflix,00000000-0000-4000-9000-000000000012,true,false
+"
+    );
+    assert_eq!(server.sign_ins(), 2);
+    let search = server.last_request("GET", "/api/vendor/search").unwrap();
+    assert_eq!(search.query_param("requiringInternalReview"), Some("false"));
+    assert_eq!(search.header("authorization"), Some("Bearer mock-token-2"));
+}
+
+#[test]
+fn it_should_exit_with_the_servers_error() {
+    let server = start_server();
+    server.fail_next(
+        "GET",
+        "/api/vendor/popular",
+        503,
+        json!({ "statusCode": 503, "message": "Database unavailable", "error": "Service Unavailable" }),
+    );
+    let sandbox = Sandbox::new(&server.url());
+
+    sign_in(&sandbox);
+    let fetch = run(&sandbox, &["fetch", "vendors", "--popular"]);
+
+    assert_eq!(fetch.status.code(), Some(7));
+    assert_eq!(
+        stderr(&fetch),
+        "Database unavailable (503 Service Unavailable)\n"
+    );
+}
+
+#[test]
+fn it_should_apply_vendor_changes_from_a_csv_file() {
+    let server = start_server();
+    server.add_vendor(make_vendor(12, "NETFLIX.COM", false));
+    server.add_vendor(make_vendor(13, "Hulu", true));
+    let sandbox = Sandbox::new(&server.url());
+    std::fs::write(
+        sandbox.directory().join("vendors.csv"),
+        "id,friendly_name,ppd_id,vendor_identity_cannot_be_determined
+12,Netflix,,
+13,Hulu,,
+,Acme Water,PPD-1,
+99,Missing,,
+,Hulu,,
+",
+    )
+    .unwrap();
+
+    sign_in(&sandbox);
+    let apply = run(
+        &sandbox,
+        &[
+            "vendors",
+            "apply",
+            "vendors.csv",
+            "--failures",
+            "failures.csv",
+            "--yes",
+            "--output",
+            "csv",
+        ],
+    );
+
+    assert!(apply.status.success(), "{}", stderr(&apply));
+    assert_eq!(
+        stdout(&apply),
+        "row,vendor_id,friendly_name,result,error
+1,12,Netflix,updated,
+2,13,Hulu,unchanged,
+3,14,Acme Water,created,
+4,99,Missing,failed,Cannot GET /api/vendor/99 (404 Not Found)
+5,,Hulu,failed,Vendor already exists at PUT /api/vendor (409 Conflict)
+1 updated, 1 created, 1 unchanged, 2 failed. Apply failures.csv to retry the failed rows.
+"
+    );
+    assert_eq!(
+        std::fs::read_to_string(sandbox.directory().join("failures.csv")).unwrap(),
+        "id,friendly_name,ppd_id,vendor_identity_cannot_be_determined
+99,Missing,,
+,Hulu,,
+"
+    );
+    let patch = server.last_request("PATCH", "/api/vendor/12").unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&patch.body).unwrap(),
+        json!({
+            "friendlyName": "Netflix",
+            "preProcessedLogoWasUploaded": false,
+            "ppdId": null,
+            "vendorIdentityCannotBeDetermined": false,
+        })
+    );
+    let create: serde_json::Value = server
+        .requests()
+        .iter()
+        .filter(|request| request.method == "PUT" && request.path == "/api/vendor")
+        .map(|request| serde_json::from_str(&request.body).unwrap())
+        .find(|body: &serde_json::Value| body["friendlyName"] == "Acme Water")
+        .unwrap();
+    assert_eq!(
+        create.as_object().unwrap().keys().collect::<Vec<_>>(),
+        vec!["friendlyName", "uuid"]
+    );
+    let created = server.last_request("PATCH", "/api/vendor/14").unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&created.body).unwrap(),
+        json!({
+            "friendlyName": "Acme Water",
+            "preProcessedLogoWasUploaded": false,
+            "ppdId": "PPD-1",
+            "vendorIdentityCannotBeDetermined": false,
+        })
+    );
+    assert!(server.last_request("PATCH", "/api/vendor/13").is_none());
+}